@@ -0,0 +1,2 @@
+-- drop backup_settings table
+DROP TABLE IF EXISTS backup_settings;
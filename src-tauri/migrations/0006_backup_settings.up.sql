@@ -0,0 +1,11 @@
+-- backup_settings: single-row configuration for scheduled backups
+CREATE TABLE IF NOT EXISTS backup_settings (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  enabled INTEGER NOT NULL DEFAULT 1,
+  interval_hours INTEGER NOT NULL DEFAULT 24 CHECK (interval_hours > 0),
+  keep_last INTEGER NOT NULL DEFAULT 7 CHECK (keep_last >= 1),
+  keep_days INTEGER NOT NULL DEFAULT 30 CHECK (keep_days >= 0),
+  last_backup_at TEXT
+);
+
+INSERT OR IGNORE INTO backup_settings (id) VALUES (1);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_suppliers_deleted_at;
+DROP INDEX IF EXISTS idx_purchase_entries_deleted_at;
+ALTER TABLE suppliers DROP COLUMN deleted_by;
+ALTER TABLE suppliers DROP COLUMN deleted_at;
+ALTER TABLE purchase_entries DROP COLUMN deleted_by;
+ALTER TABLE purchase_entries DROP COLUMN deleted_at;
@@ -0,0 +1,8 @@
+-- Soft delete: rows stay in place with deleted_at/deleted_by set until purged
+ALTER TABLE purchase_entries ADD COLUMN deleted_at TEXT;
+ALTER TABLE purchase_entries ADD COLUMN deleted_by INTEGER; -- users.id
+ALTER TABLE suppliers ADD COLUMN deleted_at TEXT;
+ALTER TABLE suppliers ADD COLUMN deleted_by INTEGER; -- users.id
+
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_deleted_at ON purchase_entries(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_suppliers_deleted_at ON suppliers(deleted_at);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS trg_purchase_revisions_immutable;
+DROP INDEX IF EXISTS idx_purchase_revisions_purchase;
+DROP TABLE IF EXISTS purchase_revisions;
@@ -0,0 +1,40 @@
+-- purchase_revisions: immutable header + items snapshot written on every save
+CREATE TABLE IF NOT EXISTS purchase_revisions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL,
+  revision_no INTEGER NOT NULL,
+  action TEXT NOT NULL, -- create | update | items | revert | baseline
+  note TEXT,
+  header_json TEXT NOT NULL,
+  items_json TEXT NOT NULL DEFAULT '[]',
+  created_by INTEGER, -- users.id
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (purchase_id, revision_no),
+  FOREIGN KEY (purchase_id) REFERENCES purchase_entries(id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_revisions_purchase ON purchase_revisions(purchase_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_purchase_revisions_immutable
+BEFORE UPDATE ON purchase_revisions
+BEGIN
+  SELECT RAISE(ABORT, 'purchase revisions are immutable');
+END;
+
+-- Baseline revision for entries saved before history was tracked
+INSERT INTO purchase_revisions (purchase_id, revision_no, action, note, header_json, items_json)
+SELECT pe.id, 1, 'baseline', 'State before revision history was enabled',
+  json_object(
+    'id', pe.id, 'supplier_id', pe.supplier_id, 'invoice_no', pe.invoice_no, 'date', pe.date,
+    'entry_date', pe.entry_date, 'gst_rate', pe.gst_rate, 'basic_value', pe.basic_value,
+    'sgst', pe.sgst, 'cgst', pe.cgst, 'igst', pe.igst, 'invoice_value', pe.invoice_value,
+    'tds_value', pe.tds_value, 'narration', pe.narration, 'status', pe.status
+  ),
+  COALESCE((
+    SELECT json_group_array(json_object(
+      'id', pi.id, 'purchase_id', pi.purchase_id, 'part_no', pi.part_no, 'description', pi.description,
+      'qty', pi.qty, 'unit', pi.unit, 'price', pi.price, 'amount', pi.amount
+    ))
+    FROM (SELECT * FROM purchase_items WHERE purchase_id = pe.id ORDER BY id) pi
+  ), '[]')
+FROM purchase_entries pe;
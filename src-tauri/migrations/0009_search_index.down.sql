@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS trg_search_items_ad;
+DROP TRIGGER IF EXISTS trg_search_items_au;
+DROP TRIGGER IF EXISTS trg_search_items_ai;
+DROP TRIGGER IF EXISTS trg_search_purchases_ad;
+DROP TRIGGER IF EXISTS trg_search_purchases_au;
+DROP TRIGGER IF EXISTS trg_search_purchases_ai;
+DROP TRIGGER IF EXISTS trg_search_suppliers_ad;
+DROP TRIGGER IF EXISTS trg_search_suppliers_au;
+DROP TRIGGER IF EXISTS trg_search_suppliers_ai;
+DROP TABLE IF EXISTS search_index;
@@ -0,0 +1,61 @@
+-- search_index: FTS5 index over suppliers, purchase headers and purchase items.
+-- rowid encodes the source row as id * 4 + kind (1 supplier, 2 purchase, 3 item)
+-- so triggers can update a single row without scanning.
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+  kind UNINDEXED,
+  ref_id UNINDEXED,
+  purchase_id UNINDEXED,
+  title,
+  body,
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- suppliers: name / GSTIN
+CREATE TRIGGER IF NOT EXISTS trg_search_suppliers_ai AFTER INSERT ON suppliers BEGIN
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 1, 'supplier', new.id, NULL, new.name, COALESCE(new.gst_no, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_suppliers_au AFTER UPDATE OF name, gst_no ON suppliers BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 1;
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 1, 'supplier', new.id, NULL, new.name, COALESCE(new.gst_no, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_suppliers_ad AFTER DELETE ON suppliers BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 1;
+END;
+
+-- purchase_entries: invoice number / narration
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_ai AFTER INSERT ON purchase_entries BEGIN
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 2, 'purchase', new.id, new.id, new.invoice_no, COALESCE(new.narration, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_au AFTER UPDATE OF invoice_no, narration ON purchase_entries BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 2, 'purchase', new.id, new.id, new.invoice_no, COALESCE(new.narration, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_ad AFTER DELETE ON purchase_entries BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+END;
+
+-- purchase_items: part number / description
+CREATE TRIGGER IF NOT EXISTS trg_search_items_ai AFTER INSERT ON purchase_items BEGIN
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 3, 'item', new.id, new.purchase_id, COALESCE(new.part_no, ''), new.description);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_items_au AFTER UPDATE OF part_no, description, purchase_id ON purchase_items BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 3;
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 3, 'item', new.id, new.purchase_id, COALESCE(new.part_no, ''), new.description);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_items_ad AFTER DELETE ON purchase_items BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 3;
+END;
+
+-- Index existing data
+INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+SELECT id * 4 + 1, 'supplier', id, NULL, name, COALESCE(gst_no, '') FROM suppliers;
+INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+SELECT id * 4 + 2, 'purchase', id, id, invoice_no, COALESCE(narration, '') FROM purchase_entries;
+INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+SELECT id * 4 + 3, 'item', id, purchase_id, COALESCE(part_no, ''), description FROM purchase_items;
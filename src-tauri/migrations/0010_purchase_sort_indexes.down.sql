@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_purchase_entries_status_id;
+DROP INDEX IF EXISTS idx_purchase_entries_gst_rate_id;
+DROP INDEX IF EXISTS idx_purchase_entries_invoice_value_id;
+DROP INDEX IF EXISTS idx_purchase_entries_date_id;
+DROP INDEX IF EXISTS idx_purchase_entries_entry_date_id;
@@ -0,0 +1,6 @@
+-- Indexes backing keyset pagination on the sortable purchase columns
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_entry_date_id ON purchase_entries(entry_date, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_date_id ON purchase_entries(date, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_invoice_value_id ON purchase_entries(invoice_value, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_gst_rate_id ON purchase_entries(gst_rate, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_status_id ON purchase_entries(status, id);
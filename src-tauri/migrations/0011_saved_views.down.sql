@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS saved_view_defaults;
+DROP INDEX IF EXISTS idx_saved_views_shared;
+DROP TABLE IF EXISTS saved_views;
@@ -0,0 +1,23 @@
+-- Saved purchase list views: filters, sort, visible columns and page size per user
+CREATE TABLE IF NOT EXISTS saved_views (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  filters_json TEXT NOT NULL DEFAULT '{}', -- PurchaseFilters
+  sort_by TEXT,
+  sort_dir TEXT CHECK (sort_dir IN ('asc', 'desc')),
+  columns_json TEXT, -- visible columns in display order; NULL = all
+  page_size INTEGER CHECK (page_size BETWEEN 1 AND 200),
+  is_shared INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_views_shared ON saved_views(is_shared);
+
+-- Each user's default view; may point at a view shared by someone else
+CREATE TABLE IF NOT EXISTS saved_view_defaults (
+  user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+  view_id INTEGER NOT NULL REFERENCES saved_views(id) ON DELETE CASCADE
+);
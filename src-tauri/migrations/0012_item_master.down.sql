@@ -0,0 +1,16 @@
+DROP INDEX IF EXISTS idx_purchase_items_part_no;
+DROP INDEX IF EXISTS idx_items_active;
+DROP INDEX IF EXISTS idx_items_supplier_id;
+
+CREATE TABLE IF NOT EXISTS items_old (
+  part_no TEXT PRIMARY KEY,
+  description TEXT NOT NULL,
+  supplier INTEGER,
+  FOREIGN KEY (supplier) REFERENCES suppliers(id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+INSERT INTO items_old (part_no, description, supplier)
+SELECT part_no, description, supplier_id FROM items;
+
+DROP TABLE items;
+ALTER TABLE items_old RENAME TO items;
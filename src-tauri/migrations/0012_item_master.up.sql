@@ -0,0 +1,25 @@
+-- items: item master, rebuilt with a surrogate key and the defaults the purchase form fills in.
+-- Part numbers are unique ignoring case; purchase_items.part_no is checked against them on save.
+CREATE TABLE IF NOT EXISTS items_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  part_no TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  description TEXT NOT NULL,
+  hsn_code TEXT,
+  gst_rate REAL CHECK (gst_rate IS NULL OR gst_rate >= 0),
+  unit TEXT,
+  supplier_id INTEGER,
+  active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+INSERT OR IGNORE INTO items_new (part_no, description, supplier_id)
+SELECT part_no, description, supplier FROM items;
+
+DROP TABLE items;
+ALTER TABLE items_new RENAME TO items;
+
+CREATE INDEX IF NOT EXISTS idx_items_supplier_id ON items(supplier_id);
+CREATE INDEX IF NOT EXISTS idx_items_active ON items(active);
+CREATE INDEX IF NOT EXISTS idx_purchase_items_part_no ON purchase_items(part_no);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_price_variances_part_no;
+DROP INDEX IF EXISTS idx_price_variances_purchase;
+DROP TABLE IF EXISTS price_variances;
+DROP TABLE IF EXISTS price_variance_settings;
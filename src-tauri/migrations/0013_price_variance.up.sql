@@ -0,0 +1,29 @@
+-- price_variance_settings: single-row configuration for purchase price checks
+CREATE TABLE IF NOT EXISTS price_variance_settings (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  enabled INTEGER NOT NULL DEFAULT 1,
+  threshold_pct REAL NOT NULL DEFAULT 10 CHECK (threshold_pct > 0),
+  window_size INTEGER NOT NULL DEFAULT 5 CHECK (window_size >= 1) -- purchases in the rolling average
+);
+
+INSERT OR IGNORE INTO price_variance_settings (id) VALUES (1);
+
+-- price_variances: lines flagged when their purchase was last saved
+CREATE TABLE IF NOT EXISTS price_variances (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  line_no INTEGER NOT NULL, -- 1-based position among the entry's items
+  part_no TEXT NOT NULL,
+  description TEXT NOT NULL,
+  price REAL NOT NULL,
+  last_price REAL NOT NULL,
+  avg_price REAL NOT NULL,
+  samples INTEGER NOT NULL, -- earlier purchases behind avg_price
+  deviation_last_pct REAL NOT NULL,
+  deviation_avg_pct REAL NOT NULL,
+  threshold_pct REAL NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_variances_purchase ON price_variances(purchase_id);
+CREATE INDEX IF NOT EXISTS idx_price_variances_part_no ON price_variances(part_no);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_uom_conversions_unique;
+DROP TABLE IF EXISTS uom_conversions;
+DROP TABLE IF EXISTS uom_aliases;
+DROP TABLE IF EXISTS uoms;
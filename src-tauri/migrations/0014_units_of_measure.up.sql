@@ -0,0 +1,97 @@
+-- uoms: units of measure keyed by their GST UQC code
+CREATE TABLE IF NOT EXISTS uoms (
+  code TEXT PRIMARY KEY COLLATE NOCASE,
+  name TEXT NOT NULL,
+  uqc TEXT NOT NULL, -- GST unit quantity code reported in returns
+  active INTEGER NOT NULL DEFAULT 1
+);
+
+INSERT OR IGNORE INTO uoms (code, name, uqc) VALUES
+  ('BAG', 'Bags', 'BAG'),
+  ('BOX', 'Box', 'BOX'),
+  ('BTL', 'Bottles', 'BTL'),
+  ('BUN', 'Bunches', 'BUN'),
+  ('CAN', 'Cans', 'CAN'),
+  ('CMS', 'Centimeters', 'CMS'),
+  ('CTN', 'Cartons', 'CTN'),
+  ('DOZ', 'Dozens', 'DOZ'),
+  ('DRM', 'Drums', 'DRM'),
+  ('GMS', 'Grammes', 'GMS'),
+  ('KGS', 'Kilograms', 'KGS'),
+  ('KLR', 'Kilolitre', 'KLR'),
+  ('KME', 'Kilometre', 'KME'),
+  ('LTR', 'Litres', 'LTR'),
+  ('MLT', 'Millilitre', 'MLT'),
+  ('MTR', 'Meters', 'MTR'),
+  ('MTS', 'Metric ton', 'MTS'),
+  ('NOS', 'Numbers', 'NOS'),
+  ('PAC', 'Packs', 'PAC'),
+  ('PRS', 'Pairs', 'PRS'),
+  ('QTL', 'Quintal', 'QTL'),
+  ('ROL', 'Rolls', 'ROL'),
+  ('SET', 'Sets', 'SET'),
+  ('SQF', 'Square feet', 'SQF'),
+  ('SQM', 'Square meters', 'SQM'),
+  ('TON', 'Tonnes', 'TON'),
+  ('UNT', 'Units', 'UNT'),
+  ('OTH', 'Others', 'OTH');
+
+-- uom_aliases: free-text spellings that mean a unit (matched ignoring case)
+CREATE TABLE IF NOT EXISTS uom_aliases (
+  alias TEXT PRIMARY KEY COLLATE NOCASE,
+  uom_code TEXT NOT NULL REFERENCES uoms(code) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- Pieces are counted as numbers; add a PCS unit and drop these aliases to keep them apart
+INSERT OR IGNORE INTO uom_aliases (alias, uom_code) VALUES
+  ('no', 'NOS'), ('nos.', 'NOS'), ('no.', 'NOS'), ('number', 'NOS'), ('numbers', 'NOS'),
+  ('pc', 'NOS'), ('pcs', 'NOS'), ('pcs.', 'NOS'), ('piece', 'NOS'), ('pieces', 'NOS'),
+  ('kg', 'KGS'), ('kgs.', 'KGS'), ('kilo', 'KGS'), ('kilogram', 'KGS'),
+  ('g', 'GMS'), ('gm', 'GMS'), ('gram', 'GMS'), ('grams', 'GMS'),
+  ('l', 'LTR'), ('lt', 'LTR'), ('ltrs', 'LTR'), ('litre', 'LTR'), ('liter', 'LTR'),
+  ('ml', 'MLT'), ('m', 'MTR'), ('mtrs', 'MTR'), ('meter', 'MTR'), ('metre', 'MTR'),
+  ('cm', 'CMS'), ('dozen', 'DOZ'), ('dz', 'DOZ'), ('pair', 'PRS'), ('pairs', 'PRS'),
+  ('pkt', 'PAC'), ('packet', 'PAC'), ('pack', 'PAC'), ('roll', 'ROL'), ('rolls', 'ROL'),
+  ('bottle', 'BTL'), ('boxes', 'BOX'), ('carton', 'CTN'), ('drum', 'DRM'), ('tonne', 'TON'),
+  ('sqft', 'SQF'), ('sq ft', 'SQF'), ('sqm', 'SQM'), ('sq m', 'SQM'), ('unit', 'UNT'), ('units', 'UNT');
+
+-- uom_conversions: 1 from_code = factor to_code. item_id NULL applies to every item,
+-- an item-specific row (e.g. a box of 12) wins over the general one.
+CREATE TABLE IF NOT EXISTS uom_conversions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  item_id INTEGER REFERENCES items(id) ON DELETE CASCADE,
+  from_code TEXT NOT NULL REFERENCES uoms(code) ON UPDATE CASCADE ON DELETE CASCADE,
+  to_code TEXT NOT NULL REFERENCES uoms(code) ON UPDATE CASCADE ON DELETE CASCADE,
+  factor REAL NOT NULL CHECK (factor > 0),
+  CHECK (from_code <> to_code COLLATE NOCASE)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_uom_conversions_unique
+  ON uom_conversions(COALESCE(item_id, 0), from_code COLLATE NOCASE, to_code COLLATE NOCASE);
+
+INSERT INTO uom_conversions (item_id, from_code, to_code, factor) VALUES
+  (NULL, 'DOZ', 'NOS', 12),
+  (NULL, 'PRS', 'NOS', 2),
+  (NULL, 'KGS', 'GMS', 1000),
+  (NULL, 'QTL', 'KGS', 100),
+  (NULL, 'TON', 'KGS', 1000),
+  (NULL, 'MTS', 'KGS', 1000),
+  (NULL, 'LTR', 'MLT', 1000),
+  (NULL, 'KLR', 'LTR', 1000),
+  (NULL, 'MTR', 'CMS', 100),
+  (NULL, 'KME', 'MTR', 1000);
+
+-- Normalise stored units that match a code or alias; the rest is left for normalize_units
+UPDATE purchase_items SET unit = COALESCE(
+  (SELECT code FROM uoms WHERE code = TRIM(purchase_items.unit)),
+  (SELECT uom_code FROM uom_aliases WHERE alias = TRIM(purchase_items.unit)))
+WHERE unit IS NOT NULL AND COALESCE(
+  (SELECT code FROM uoms WHERE code = TRIM(purchase_items.unit)),
+  (SELECT uom_code FROM uom_aliases WHERE alias = TRIM(purchase_items.unit))) IS NOT NULL;
+
+UPDATE items SET unit = COALESCE(
+  (SELECT code FROM uoms WHERE code = TRIM(items.unit)),
+  (SELECT uom_code FROM uom_aliases WHERE alias = TRIM(items.unit)))
+WHERE unit IS NOT NULL AND COALESCE(
+  (SELECT code FROM uoms WHERE code = TRIM(items.unit)),
+  (SELECT uom_code FROM uom_aliases WHERE alias = TRIM(items.unit))) IS NOT NULL;
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_supplier_contacts_supplier;
+DROP TABLE IF EXISTS supplier_contacts;
+DROP INDEX IF EXISTS idx_supplier_bank_accounts_supplier;
+DROP TABLE IF EXISTS supplier_bank_accounts;
+DROP INDEX IF EXISTS idx_supplier_addresses_supplier;
+DROP TABLE IF EXISTS supplier_addresses;
+ALTER TABLE suppliers DROP COLUMN payment_terms;
+ALTER TABLE suppliers DROP COLUMN payment_terms_days;
+ALTER TABLE suppliers DROP COLUMN msme_category;
+ALTER TABLE suppliers DROP COLUMN msme_no;
+ALTER TABLE suppliers DROP COLUMN pan;
@@ -0,0 +1,56 @@
+-- Extended supplier master: tax identifiers, MSME registration and payment terms
+ALTER TABLE suppliers ADD COLUMN pan TEXT;
+ALTER TABLE suppliers ADD COLUMN msme_no TEXT; -- Udyam registration number
+ALTER TABLE suppliers ADD COLUMN msme_category TEXT CHECK (msme_category IN ('micro', 'small', 'medium'));
+ALTER TABLE suppliers ADD COLUMN payment_terms_days INTEGER CHECK (payment_terms_days >= 0); -- credit period
+ALTER TABLE suppliers ADD COLUMN payment_terms TEXT; -- free-text terms, e.g. "50% advance"
+
+-- supplier_addresses: billing and shipping addresses, one default per kind
+CREATE TABLE IF NOT EXISTS supplier_addresses (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL CHECK (kind IN ('billing', 'shipping')),
+  line1 TEXT NOT NULL,
+  line2 TEXT,
+  city TEXT,
+  state_code TEXT,
+  pincode TEXT,
+  country TEXT NOT NULL DEFAULT 'India',
+  is_default INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_addresses_supplier ON supplier_addresses(supplier_id);
+
+-- supplier_bank_accounts: payee accounts; only verified ones should be paid
+CREATE TABLE IF NOT EXISTS supplier_bank_accounts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+  account_name TEXT NOT NULL,
+  account_no TEXT NOT NULL,
+  ifsc TEXT NOT NULL,
+  bank_name TEXT,
+  branch TEXT,
+  verification_status TEXT NOT NULL CHECK (verification_status IN ('unverified', 'verified', 'failed')) DEFAULT 'unverified',
+  verified_at TEXT,
+  is_primary INTEGER NOT NULL DEFAULT 0,
+  UNIQUE (supplier_id, account_no, ifsc)
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_bank_accounts_supplier ON supplier_bank_accounts(supplier_id);
+
+-- supplier_contacts: contact persons with their role
+CREATE TABLE IF NOT EXISTS supplier_contacts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  role TEXT, -- e.g. sales, accounts, dispatch
+  phone TEXT,
+  email TEXT,
+  is_primary INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_contacts_supplier ON supplier_contacts(supplier_id);
+
+-- Carry the single contact over as the primary contact person
+INSERT INTO supplier_contacts (supplier_id, name, email, is_primary)
+SELECT id, contact, email, 1 FROM suppliers WHERE TRIM(COALESCE(contact, '')) <> '';
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_suppliers_pan;
+DROP INDEX IF EXISTS idx_suppliers_gst_no;
+DROP INDEX IF EXISTS idx_supplier_merges_survivor;
+DROP TABLE IF EXISTS supplier_merges;
+ALTER TABLE suppliers DROP COLUMN merged_into;
@@ -0,0 +1,20 @@
+-- Suppliers folded into another one keep a pointer to the survivor
+ALTER TABLE suppliers ADD COLUMN merged_into INTEGER; -- suppliers.id
+
+-- supplier_merges: audit trail of merged suppliers
+CREATE TABLE IF NOT EXISTS supplier_merges (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  survivor_id INTEGER NOT NULL, -- suppliers.id kept
+  merged_id INTEGER NOT NULL, -- suppliers.id folded in
+  merged_name TEXT NOT NULL, -- snapshot, the merged row may be purged later
+  merged_gst_no TEXT,
+  purchases_moved INTEGER NOT NULL DEFAULT 0,
+  items_moved INTEGER NOT NULL DEFAULT 0,
+  renamed_invoices TEXT NOT NULL DEFAULT '[]', -- JSON array of {purchase_id, from, to}
+  merged_by INTEGER, -- users.id
+  merged_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_merges_survivor ON supplier_merges(survivor_id);
+CREATE INDEX IF NOT EXISTS idx_suppliers_gst_no ON suppliers(gst_no);
+CREATE INDEX IF NOT EXISTS idx_suppliers_pan ON suppliers(pan);
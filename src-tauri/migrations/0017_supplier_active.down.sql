@@ -0,0 +1,3 @@
+ALTER TABLE suppliers DROP COLUMN deactivated_by;
+ALTER TABLE suppliers DROP COLUMN deactivated_at;
+ALTER TABLE suppliers DROP COLUMN active;
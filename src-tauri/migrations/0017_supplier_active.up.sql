@@ -0,0 +1,4 @@
+-- Inactive suppliers keep their history but are hidden from pickers and take no new purchases
+ALTER TABLE suppliers ADD COLUMN active INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE suppliers ADD COLUMN deactivated_at TEXT;
+ALTER TABLE suppliers ADD COLUMN deactivated_by INTEGER; -- users.id
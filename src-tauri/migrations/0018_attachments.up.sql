@@ -0,0 +1,17 @@
+-- attachments: documents linked to purchase entries. Files are stored once under
+-- attachments/<first two hex digits>/<sha256> next to app.db.
+CREATE TABLE IF NOT EXISTS attachments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  sha256 TEXT NOT NULL,
+  file_name TEXT NOT NULL, -- original name, used when opening
+  kind TEXT NOT NULL CHECK (kind IN ('invoice', 'eway_bill', 'grn', 'other')) DEFAULT 'invoice',
+  mime_type TEXT,
+  size_bytes INTEGER NOT NULL,
+  created_by INTEGER, -- users.id
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (purchase_id, sha256)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_purchase ON attachments(purchase_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_sha256 ON attachments(sha256);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_purchase_allocations_dimension;
+DROP INDEX IF EXISTS idx_purchase_allocations_item;
+DROP INDEX IF EXISTS idx_purchase_allocations_purchase;
+DROP TABLE IF EXISTS purchase_allocations;
+DROP TABLE IF EXISTS dimensions;
@@ -0,0 +1,25 @@
+-- dimensions: cost centers, departments and projects that spend is reported against
+CREATE TABLE IF NOT EXISTS dimensions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind TEXT NOT NULL CHECK (kind IN ('cost_center', 'department', 'project')),
+  code TEXT NOT NULL COLLATE NOCASE,
+  name TEXT NOT NULL,
+  active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (kind, code)
+);
+
+-- purchase_allocations: percentage splits of an entry (item_id NULL) or of one line
+-- across dimensions. Per target and kind the splits add up to 100; a line's own
+-- splits take precedence over the entry's for that line's amount.
+CREATE TABLE IF NOT EXISTS purchase_allocations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  item_id INTEGER REFERENCES purchase_items(id) ON DELETE CASCADE,
+  dimension_id INTEGER NOT NULL REFERENCES dimensions(id),
+  percent REAL NOT NULL CHECK (percent > 0 AND percent <= 100)
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_allocations_purchase ON purchase_allocations(purchase_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_allocations_item ON purchase_allocations(item_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_allocations_dimension ON purchase_allocations(dimension_id);
@@ -0,0 +1,4 @@
+ALTER TABLE items DROP COLUMN custom_fields;
+ALTER TABLE purchase_entries DROP COLUMN custom_fields;
+ALTER TABLE suppliers DROP COLUMN custom_fields;
+DROP TABLE IF EXISTS custom_field_definitions;
@@ -0,0 +1,20 @@
+-- custom_field_definitions: admin-defined extra fields on suppliers, purchases and items.
+-- Values live in a JSON object column on each record, keyed by `key`.
+CREATE TABLE IF NOT EXISTS custom_field_definitions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  entity TEXT NOT NULL CHECK (entity IN ('supplier', 'purchase', 'item')),
+  key TEXT NOT NULL, -- lower-case identifier used in the JSON values
+  label TEXT NOT NULL,
+  field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'enum')),
+  options_json TEXT NOT NULL DEFAULT '[]', -- allowed values of an enum field
+  required INTEGER NOT NULL DEFAULT 0,
+  active INTEGER NOT NULL DEFAULT 1,
+  position INTEGER NOT NULL DEFAULT 0, -- display order within the entity
+  created_by INTEGER, -- users.id
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (entity, key)
+);
+
+ALTER TABLE suppliers ADD COLUMN custom_fields TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE purchase_entries ADD COLUMN custom_fields TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE items ADD COLUMN custom_fields TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,102 @@
+-- purchase_entries: rebuilt to widen the status lifecycle, since SQLite can't alter a CHECK.
+-- 'uploaded' is the posted state. Migrations run with foreign keys off, so dropping the old
+-- table leaves the rows of child tables alone.
+CREATE TABLE purchase_entries_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL,
+  invoice_no TEXT NOT NULL,
+  date TEXT NOT NULL, -- ISO8601 date string (YYYY-MM-DD)
+  gst_rate REAL NOT NULL DEFAULT 0,
+  basic_value REAL NOT NULL DEFAULT 0,
+  sgst REAL NOT NULL DEFAULT 0,
+  cgst REAL NOT NULL DEFAULT 0,
+  igst REAL NOT NULL DEFAULT 0,
+  invoice_value REAL NOT NULL DEFAULT 0,
+  tds_value REAL NOT NULL DEFAULT 0,
+  narration TEXT,
+  status TEXT NOT NULL
+    CHECK (status IN ('draft', 'pending', 'verified', 'approved', 'uploaded', 'cancelled'))
+    DEFAULT 'pending',
+  entry_date TEXT NOT NULL DEFAULT (datetime('now')),
+  deleted_at TEXT,
+  deleted_by INTEGER, -- users.id
+  custom_fields TEXT NOT NULL DEFAULT '{}',
+  UNIQUE (supplier_id, invoice_no),
+  FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON UPDATE CASCADE ON DELETE RESTRICT
+);
+
+INSERT INTO purchase_entries_new (id, supplier_id, invoice_no, date, gst_rate, basic_value, sgst, cgst, igst,
+  invoice_value, tds_value, narration, status, entry_date, deleted_at, deleted_by, custom_fields)
+SELECT id, supplier_id, invoice_no, date, gst_rate, basic_value, sgst, cgst, igst,
+  invoice_value, tds_value, narration, status, entry_date, deleted_at, deleted_by, custom_fields
+FROM purchase_entries;
+
+-- Keep ids of purged entries from being handed out again
+UPDATE sqlite_sequence
+SET seq = (SELECT MAX(seq) FROM sqlite_sequence WHERE name IN ('purchase_entries', 'purchase_entries_new'))
+WHERE name = 'purchase_entries_new';
+
+DROP TABLE purchase_entries;
+ALTER TABLE purchase_entries_new RENAME TO purchase_entries;
+
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_date ON purchase_entries(date);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_supplier ON purchase_entries(supplier_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_gst_rate ON purchase_entries(gst_rate);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_deleted_at ON purchase_entries(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_entry_date_id ON purchase_entries(entry_date, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_date_id ON purchase_entries(date, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_invoice_value_id ON purchase_entries(invoice_value, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_gst_rate_id ON purchase_entries(gst_rate, id);
+CREATE INDEX IF NOT EXISTS idx_purchase_entries_status_id ON purchase_entries(status, id);
+
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_ai AFTER INSERT ON purchase_entries BEGIN
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 2, 'purchase', new.id, new.id, new.invoice_no, COALESCE(new.narration, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_au AFTER UPDATE OF invoice_no, narration ON purchase_entries BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+  INSERT INTO search_index (rowid, kind, ref_id, purchase_id, title, body)
+  VALUES (new.id * 4 + 2, 'purchase', new.id, new.id, new.invoice_no, COALESCE(new.narration, ''));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_search_purchases_ad AFTER DELETE ON purchase_entries BEGIN
+  DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+END;
+
+-- purchase_status_transitions: the moves allowed between statuses and who may make them
+CREATE TABLE IF NOT EXISTS purchase_status_transitions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  from_status TEXT NOT NULL,
+  to_status TEXT NOT NULL,
+  required_role TEXT NOT NULL CHECK (required_role IN ('admin', 'user')) DEFAULT 'user',
+  requires_reason INTEGER NOT NULL DEFAULT 0,
+  CHECK (from_status <> to_status),
+  UNIQUE (from_status, to_status)
+);
+
+INSERT OR IGNORE INTO purchase_status_transitions (from_status, to_status, required_role, requires_reason) VALUES
+  ('draft', 'pending', 'user', 0),
+  ('draft', 'cancelled', 'user', 1),
+  ('pending', 'draft', 'user', 0),
+  ('pending', 'verified', 'user', 0),
+  ('pending', 'cancelled', 'user', 1),
+  ('verified', 'pending', 'user', 1),
+  ('verified', 'approved', 'admin', 0),
+  ('verified', 'cancelled', 'admin', 1),
+  ('approved', 'verified', 'admin', 1),
+  ('approved', 'uploaded', 'user', 0),
+  ('approved', 'cancelled', 'admin', 1),
+  ('uploaded', 'approved', 'admin', 1),
+  ('cancelled', 'draft', 'admin', 1);
+
+-- purchase_status_history: every status an entry has been in, who moved it and why
+CREATE TABLE IF NOT EXISTS purchase_status_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  from_status TEXT, -- NULL for the status an entry was created in
+  to_status TEXT NOT NULL,
+  reason TEXT,
+  changed_by INTEGER, -- users.id
+  changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_status_history_purchase ON purchase_status_history(purchase_id);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_purchase_approvals_approver;
+DROP INDEX IF EXISTS idx_purchase_approvals_purchase;
+DROP INDEX IF EXISTS idx_approval_rules_dimension;
+DROP INDEX IF EXISTS idx_approval_rules_supplier;
+DROP TABLE IF EXISTS purchase_approvals;
+DROP TABLE IF EXISTS approval_rule_approvers;
+DROP TABLE IF EXISTS approval_rules;
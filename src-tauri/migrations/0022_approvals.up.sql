@@ -0,0 +1,44 @@
+-- approval_rules: entries whose invoice value falls in [min_amount, max_amount) and that match
+-- the optional supplier and dimension (cost center) need sign-off from the rule's approvers
+CREATE TABLE IF NOT EXISTS approval_rules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  min_amount REAL NOT NULL DEFAULT 0 CHECK (min_amount >= 0),
+  max_amount REAL CHECK (max_amount IS NULL OR max_amount > min_amount),
+  supplier_id INTEGER REFERENCES suppliers(id) ON DELETE CASCADE,
+  dimension_id INTEGER REFERENCES dimensions(id),
+  active INTEGER NOT NULL DEFAULT 1,
+  position INTEGER NOT NULL DEFAULT 0,
+  created_by INTEGER, -- users.id
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- approval_rule_approvers: who signs off under a rule, in order
+CREATE TABLE IF NOT EXISTS approval_rule_approvers (
+  rule_id INTEGER NOT NULL REFERENCES approval_rules(id) ON DELETE CASCADE,
+  step_no INTEGER NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  PRIMARY KEY (rule_id, step_no)
+);
+
+-- purchase_approvals: sign-offs requested for an entry. Each submission for approval starts
+-- a new round; earlier rounds stay as the trail.
+CREATE TABLE IF NOT EXISTS purchase_approvals (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  round INTEGER NOT NULL,
+  step_no INTEGER NOT NULL,
+  rule_id INTEGER REFERENCES approval_rules(id) ON DELETE SET NULL,
+  approver_id INTEGER NOT NULL, -- users.id
+  status TEXT NOT NULL CHECK (status IN ('pending', 'approved', 'rejected', 'cancelled')) DEFAULT 'pending',
+  comment TEXT,
+  amount REAL NOT NULL, -- invoice value when requested
+  requested_at TEXT NOT NULL DEFAULT (datetime('now')),
+  decided_at TEXT,
+  UNIQUE (purchase_id, round, step_no)
+);
+
+CREATE INDEX IF NOT EXISTS idx_approval_rules_supplier ON approval_rules(supplier_id);
+CREATE INDEX IF NOT EXISTS idx_approval_rules_dimension ON approval_rules(dimension_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_approvals_purchase ON purchase_approvals(purchase_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_approvals_approver ON purchase_approvals(approver_id, status);
@@ -0,0 +1,10 @@
+DROP TABLE IF EXISTS match_exceptions;
+DROP TABLE IF EXISTS match_settings;
+DROP INDEX IF EXISTS idx_purchase_items_grn_line;
+DROP INDEX IF EXISTS idx_purchase_items_po_line;
+ALTER TABLE purchase_items DROP COLUMN grn_line_id;
+ALTER TABLE purchase_items DROP COLUMN po_line_id;
+DROP TABLE IF EXISTS goods_receipt_lines;
+DROP TABLE IF EXISTS goods_receipts;
+DROP TABLE IF EXISTS purchase_order_lines;
+DROP TABLE IF EXISTS purchase_orders;
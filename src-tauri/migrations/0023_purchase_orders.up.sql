@@ -0,0 +1,85 @@
+-- purchase_orders: what was ordered from a supplier and at what price
+CREATE TABLE IF NOT EXISTS purchase_orders (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  po_no TEXT NOT NULL COLLATE NOCASE UNIQUE,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id),
+  po_date TEXT NOT NULL,
+  expected_date TEXT,
+  status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'closed', 'cancelled')),
+  narration TEXT,
+  created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS purchase_order_lines (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  po_id INTEGER NOT NULL REFERENCES purchase_orders(id) ON DELETE CASCADE,
+  line_no INTEGER NOT NULL,
+  part_no TEXT NOT NULL,
+  description TEXT NOT NULL,
+  qty REAL NOT NULL CHECK (qty > 0),
+  unit TEXT,
+  price REAL NOT NULL CHECK (price >= 0), -- agreed price per unit
+  UNIQUE (po_id, line_no)
+);
+
+-- goods_receipts: GRNs recording what actually arrived
+CREATE TABLE IF NOT EXISTS goods_receipts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  grn_no TEXT NOT NULL COLLATE NOCASE UNIQUE,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id),
+  po_id INTEGER REFERENCES purchase_orders(id),
+  received_date TEXT NOT NULL,
+  narration TEXT,
+  created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS goods_receipt_lines (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  grn_id INTEGER NOT NULL REFERENCES goods_receipts(id) ON DELETE CASCADE,
+  line_no INTEGER NOT NULL,
+  po_line_id INTEGER REFERENCES purchase_order_lines(id),
+  part_no TEXT NOT NULL,
+  description TEXT NOT NULL,
+  qty REAL NOT NULL CHECK (qty > 0), -- accepted quantity
+  unit TEXT,
+  UNIQUE (grn_id, line_no)
+);
+
+-- invoice lines point at the PO and GRN lines they bill
+ALTER TABLE purchase_items ADD COLUMN po_line_id INTEGER REFERENCES purchase_order_lines(id) ON DELETE SET NULL;
+ALTER TABLE purchase_items ADD COLUMN grn_line_id INTEGER REFERENCES goods_receipt_lines(id) ON DELETE SET NULL;
+
+-- match_settings: single-row tolerances for three-way matching
+CREATE TABLE IF NOT EXISTS match_settings (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  qty_tolerance_pct REAL NOT NULL DEFAULT 0 CHECK (qty_tolerance_pct >= 0),
+  price_tolerance_pct REAL NOT NULL DEFAULT 1 CHECK (price_tolerance_pct >= 0)
+);
+
+INSERT OR IGNORE INTO match_settings (id) VALUES (1);
+
+-- match_exceptions: invoice lines off from their PO or GRN when the purchase was last saved
+CREATE TABLE IF NOT EXISTS match_exceptions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  line_no INTEGER NOT NULL, -- 1-based position among the entry's items
+  part_no TEXT,
+  kind TEXT NOT NULL CHECK (kind IN ('price', 'qty_ordered', 'qty_received')),
+  expected REAL NOT NULL, -- PO price, ordered qty or received qty
+  actual REAL NOT NULL,   -- invoice price, or qty billed so far including this entry
+  deviation_pct REAL,     -- NULL when nothing was expected
+  tolerance_pct REAL NOT NULL,
+  po_line_id INTEGER,
+  grn_line_id INTEGER,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_orders_supplier ON purchase_orders(supplier_id, status);
+CREATE INDEX IF NOT EXISTS idx_goods_receipts_supplier ON goods_receipts(supplier_id);
+CREATE INDEX IF NOT EXISTS idx_goods_receipts_po ON goods_receipts(po_id);
+CREATE INDEX IF NOT EXISTS idx_goods_receipt_lines_po_line ON goods_receipt_lines(po_line_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_items_po_line ON purchase_items(po_line_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_items_grn_line ON purchase_items(grn_line_id);
+CREATE INDEX IF NOT EXISTS idx_match_exceptions_purchase ON match_exceptions(purchase_id);
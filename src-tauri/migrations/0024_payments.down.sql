@@ -0,0 +1,9 @@
+DROP TRIGGER IF EXISTS trg_payment_allocations_ad;
+DROP TRIGGER IF EXISTS trg_payment_allocations_au;
+DROP TRIGGER IF EXISTS trg_payment_allocations_ai;
+DROP TRIGGER IF EXISTS trg_purchase_entries_outstanding_au;
+DROP TRIGGER IF EXISTS trg_purchase_entries_outstanding_ai;
+ALTER TABLE purchase_entries DROP COLUMN outstanding;
+ALTER TABLE purchase_entries DROP COLUMN paid_amount;
+DROP TABLE IF EXISTS payment_allocations;
+DROP TABLE IF EXISTS supplier_payments;
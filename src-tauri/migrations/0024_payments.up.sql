@@ -0,0 +1,64 @@
+-- supplier_payments: money paid to a supplier, with TDS deducted at payment
+CREATE TABLE IF NOT EXISTS supplier_payments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id),
+  payment_date TEXT NOT NULL,
+  mode TEXT NOT NULL CHECK (mode IN ('cash', 'cheque', 'neft', 'rtgs', 'imps', 'upi', 'card', 'other')),
+  reference TEXT, -- cheque no, UTR and the like
+  amount REAL NOT NULL CHECK (amount >= 0),
+  tds_amount REAL NOT NULL DEFAULT 0 CHECK (tds_amount >= 0),
+  narration TEXT,
+  created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  CHECK (amount + tds_amount > 0)
+);
+
+-- payment_allocations: how much of a payment (amount + TDS) settles each entry; the rest is an advance
+CREATE TABLE IF NOT EXISTS payment_allocations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  payment_id INTEGER NOT NULL REFERENCES supplier_payments(id) ON DELETE CASCADE,
+  purchase_id INTEGER NOT NULL REFERENCES purchase_entries(id) ON DELETE CASCADE,
+  amount REAL NOT NULL CHECK (amount > 0),
+  UNIQUE (payment_id, purchase_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_payments_supplier ON supplier_payments(supplier_id, payment_date);
+CREATE INDEX IF NOT EXISTS idx_payment_allocations_purchase ON payment_allocations(purchase_id);
+
+-- Settled and outstanding amounts kept on the entry by the triggers below; outstanding is net of
+-- TDS booked on the bill. A plain column rather than a generated one: SQLite hands virtual REAL
+-- columns back as integers when they are also filtered or sorted on.
+ALTER TABLE purchase_entries ADD COLUMN paid_amount REAL NOT NULL DEFAULT 0;
+ALTER TABLE purchase_entries ADD COLUMN outstanding REAL NOT NULL DEFAULT 0;
+UPDATE purchase_entries SET outstanding = ROUND(invoice_value - tds_value, 2);
+
+CREATE TRIGGER IF NOT EXISTS trg_purchase_entries_outstanding_ai AFTER INSERT ON purchase_entries BEGIN
+  UPDATE purchase_entries
+  SET outstanding = ROUND(NEW.invoice_value - NEW.tds_value - NEW.paid_amount, 2)
+  WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_purchase_entries_outstanding_au
+AFTER UPDATE OF invoice_value, tds_value, paid_amount ON purchase_entries BEGIN
+  UPDATE purchase_entries
+  SET outstanding = ROUND(NEW.invoice_value - NEW.tds_value - NEW.paid_amount, 2)
+  WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_payment_allocations_ai AFTER INSERT ON payment_allocations BEGIN
+  UPDATE purchase_entries
+  SET paid_amount = (SELECT COALESCE(SUM(amount), 0) FROM payment_allocations WHERE purchase_id = NEW.purchase_id)
+  WHERE id = NEW.purchase_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_payment_allocations_au AFTER UPDATE ON payment_allocations BEGIN
+  UPDATE purchase_entries
+  SET paid_amount = (SELECT COALESCE(SUM(amount), 0) FROM payment_allocations WHERE purchase_id = purchase_entries.id)
+  WHERE id IN (OLD.purchase_id, NEW.purchase_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_payment_allocations_ad AFTER DELETE ON payment_allocations BEGIN
+  UPDATE purchase_entries
+  SET paid_amount = (SELECT COALESCE(SUM(amount), 0) FROM payment_allocations WHERE purchase_id = OLD.purchase_id)
+  WHERE id = OLD.purchase_id;
+END;
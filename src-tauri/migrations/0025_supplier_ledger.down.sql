@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_supplier_notes_supplier;
+DROP TABLE IF EXISTS supplier_notes;
+DROP TABLE IF EXISTS supplier_opening_balances;
@@ -0,0 +1,32 @@
+-- supplier_opening_balances: balance carried into a financial year (April to March), owed to the
+-- supplier when positive, an advance with them when negative. It replaces everything before the year
+-- in the supplier's ledger.
+CREATE TABLE IF NOT EXISTS supplier_opening_balances (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+  fy_start TEXT NOT NULL, -- YYYY-04-01
+  amount REAL NOT NULL,
+  narration TEXT,
+  created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (supplier_id, fy_start)
+);
+
+-- supplier_notes: debit notes (returns, short supply, rate differences) reduce what is owed to the
+-- supplier, credit notes add to it
+CREATE TABLE IF NOT EXISTS supplier_notes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  supplier_id INTEGER NOT NULL REFERENCES suppliers(id),
+  kind TEXT NOT NULL CHECK (kind IN ('debit', 'credit')),
+  note_no TEXT NOT NULL COLLATE NOCASE,
+  note_date TEXT NOT NULL,
+  purchase_id INTEGER REFERENCES purchase_entries(id) ON DELETE SET NULL, -- the invoice it adjusts, if any
+  amount REAL NOT NULL CHECK (amount > 0),
+  reason TEXT,
+  created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (supplier_id, kind, note_no)
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_notes_supplier ON supplier_notes(supplier_id, note_date);
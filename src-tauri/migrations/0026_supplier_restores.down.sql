@@ -0,0 +1,2 @@
+ALTER TABLE suppliers DROP COLUMN restored_by;
+ALTER TABLE suppliers DROP COLUMN restored_at;
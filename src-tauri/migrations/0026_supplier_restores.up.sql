@@ -0,0 +1,3 @@
+-- who brought a supplier back from the recycle bin
+ALTER TABLE suppliers ADD COLUMN restored_at TEXT;
+ALTER TABLE suppliers ADD COLUMN restored_by INTEGER; -- users.id
@@ -0,0 +1 @@
+-- Backfilled items stay: they can't be told apart from ones added since
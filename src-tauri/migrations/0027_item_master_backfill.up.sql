@@ -0,0 +1,6 @@
+-- Part numbers already used on purchase lines join the item master so those lines keep saving
+INSERT OR IGNORE INTO items (part_no, description)
+SELECT TRIM(part_no), MAX(description) FROM purchase_items
+WHERE TRIM(COALESCE(part_no, '')) <> ''
+GROUP BY TRIM(part_no) COLLATE NOCASE
+ORDER BY MIN(id);
@@ -0,0 +1,12 @@
+DROP TRIGGER IF EXISTS trg_supplier_notes_ad;
+DROP TRIGGER IF EXISTS trg_supplier_notes_au;
+DROP TRIGGER IF EXISTS trg_supplier_notes_ai;
+DROP TRIGGER IF EXISTS trg_purchase_entries_outstanding_au;
+CREATE TRIGGER IF NOT EXISTS trg_purchase_entries_outstanding_au
+AFTER UPDATE OF invoice_value, tds_value, paid_amount ON purchase_entries BEGIN
+  UPDATE purchase_entries
+  SET outstanding = ROUND(NEW.invoice_value - NEW.tds_value - NEW.paid_amount, 2)
+  WHERE id = NEW.id;
+END;
+UPDATE purchase_entries SET outstanding = ROUND(invoice_value - tds_value - paid_amount, 2);
+ALTER TABLE purchase_entries DROP COLUMN noted_amount;
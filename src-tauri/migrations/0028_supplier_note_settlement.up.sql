@@ -0,0 +1,40 @@
+-- Notes against an invoice settle it like payments do: debit notes less credit notes, kept on the
+-- entry by the triggers below and taken off its outstanding amount.
+ALTER TABLE purchase_entries ADD COLUMN noted_amount REAL NOT NULL DEFAULT 0;
+
+DROP TRIGGER IF EXISTS trg_purchase_entries_outstanding_au;
+CREATE TRIGGER IF NOT EXISTS trg_purchase_entries_outstanding_au
+AFTER UPDATE OF invoice_value, tds_value, paid_amount, noted_amount ON purchase_entries BEGIN
+  UPDATE purchase_entries
+  SET outstanding = ROUND(NEW.invoice_value - NEW.tds_value - NEW.paid_amount - NEW.noted_amount, 2)
+  WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_supplier_notes_ai AFTER INSERT ON supplier_notes
+WHEN NEW.purchase_id IS NOT NULL BEGIN
+  UPDATE purchase_entries
+  SET noted_amount = (SELECT COALESCE(SUM(CASE kind WHEN 'debit' THEN amount ELSE -amount END), 0)
+                      FROM supplier_notes WHERE purchase_id = NEW.purchase_id)
+  WHERE id = NEW.purchase_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_supplier_notes_au AFTER UPDATE ON supplier_notes BEGIN
+  UPDATE purchase_entries
+  SET noted_amount = (SELECT COALESCE(SUM(CASE kind WHEN 'debit' THEN amount ELSE -amount END), 0)
+                      FROM supplier_notes WHERE purchase_id = purchase_entries.id)
+  WHERE id IN (OLD.purchase_id, NEW.purchase_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_supplier_notes_ad AFTER DELETE ON supplier_notes
+WHEN OLD.purchase_id IS NOT NULL BEGIN
+  UPDATE purchase_entries
+  SET noted_amount = (SELECT COALESCE(SUM(CASE kind WHEN 'debit' THEN amount ELSE -amount END), 0)
+                      FROM supplier_notes WHERE purchase_id = OLD.purchase_id)
+  WHERE id = OLD.purchase_id;
+END;
+
+-- Notes recorded before this migration
+UPDATE purchase_entries
+SET noted_amount = (SELECT COALESCE(SUM(CASE kind WHEN 'debit' THEN amount ELSE -amount END), 0)
+                    FROM supplier_notes WHERE purchase_id = purchase_entries.id)
+WHERE id IN (SELECT purchase_id FROM supplier_notes WHERE purchase_id IS NOT NULL);
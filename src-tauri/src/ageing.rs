@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use sqlx::SqliteConnection;
+
+use crate::models::{AgeingBucket, AgeingInvoice, AgeingReport, AgeingSupplierRow};
+
+/// Bucket limits used when none are given: 0-30, 31-60, 61-90 and 90+ days.
+pub const DEFAULT_BUCKET_DAYS: &[i64] = &[30, 60, 90];
+
+/// The ageing date, today when not given.
+pub fn as_of(value: Option<String>) -> Result<String, String> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .map_err(|_| format!("Ageing date '{}' must be YYYY-MM-DD", v)),
+        None => Ok(chrono::Local::now().format("%Y-%m-%d").to_string()),
+    }
+}
+
+/// Turn the upper limits of the closed buckets into columns; one open-ended bucket follows
+/// the last limit. Limits must be positive and increasing.
+pub fn buckets(bucket_days: Option<Vec<i64>>) -> Result<Vec<AgeingBucket>, String> {
+    let limits = bucket_days.unwrap_or_else(|| DEFAULT_BUCKET_DAYS.to_vec());
+    if limits.is_empty() {
+        return Err("Give at least one ageing bucket limit".to_string());
+    }
+    if limits[0] < 1 || limits.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("Ageing bucket limits must be positive and increasing".to_string());
+    }
+
+    let mut out = Vec::with_capacity(limits.len() + 1);
+    let mut from = 0;
+    for &to in &limits {
+        out.push(AgeingBucket { label: format!("{}-{}", from, to), from_days: from, to_days: Some(to) });
+        from = to + 1;
+    }
+    let last = limits[limits.len() - 1];
+    out.push(AgeingBucket { label: format!("{}+", last), from_days: last + 1, to_days: None });
+    Ok(out)
+}
+
+fn bucket_of(buckets: &[AgeingBucket], days: i64) -> Option<usize> {
+    if days < 0 {
+        return None;
+    }
+    buckets.iter().position(|b| b.to_days.map_or(true, |to| days <= to))
+}
+
+/// Invoices of live, uncancelled entries dated by `as_of` with something left to pay on that
+/// date, counting only payments made and debit/credit notes raised by then. Ageing runs from
+/// the due date for suppliers with payment terms and from the invoice date otherwise. Oldest
+/// first within each supplier.
+pub async fn invoices(
+    conn: &mut SqliteConnection,
+    as_of: &str,
+    supplier_id: Option<i64>,
+    buckets: &[AgeingBucket],
+) -> Result<Vec<AgeingInvoice>, String> {
+    let mut rows = sqlx::query_as::<_, AgeingInvoice>(
+        "SELECT purchase_id, supplier_id, supplier_name, payment_terms_days, invoice_no, date, due_date,
+                CAST(julianday(?1) - julianday(COALESCE(due_date, date)) AS INTEGER) AS days,
+                payable, paid, noted, ROUND(payable - paid - noted, 2) AS outstanding
+         FROM (
+            SELECT pe.id AS purchase_id, pe.supplier_id, s.name AS supplier_name, s.payment_terms_days,
+                   pe.invoice_no, pe.date,
+                   CASE WHEN s.payment_terms_days IS NOT NULL
+                        THEN date(pe.date, '+' || s.payment_terms_days || ' days') END AS due_date,
+                   pe.invoice_value - pe.tds_value AS payable,
+                   COALESCE((SELECT SUM(pa.amount) FROM payment_allocations pa
+                             JOIN supplier_payments sp ON sp.id = pa.payment_id
+                             WHERE pa.purchase_id = pe.id AND sp.payment_date <= ?1), 0.0) AS paid,
+                   COALESCE((SELECT SUM(CASE n.kind WHEN 'debit' THEN n.amount ELSE -n.amount END)
+                             FROM supplier_notes n
+                             WHERE n.purchase_id = pe.id AND n.note_date <= ?1), 0.0) AS noted
+            FROM purchase_entries pe JOIN suppliers s ON s.id = pe.supplier_id
+            WHERE pe.deleted_at IS NULL AND pe.status <> 'cancelled' AND pe.date <= ?1
+              AND (?2 IS NULL OR pe.supplier_id = ?2)
+         )
+         WHERE payable - paid - noted >= 0.005
+         ORDER BY supplier_name, supplier_id, COALESCE(due_date, date), date, purchase_id",
+    )
+    .bind(as_of)
+    .bind(supplier_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for r in &mut rows {
+        r.bucket = bucket_of(buckets, r.days);
+    }
+    Ok(rows)
+}
+
+/// Outstanding per supplier and bucket as of `as_of`, with what each supplier holds as advance
+/// and the notes not set against those bills on that date. Largest balance first.
+pub async fn report(
+    conn: &mut SqliteConnection,
+    as_of: &str,
+    supplier_id: Option<i64>,
+    buckets: Vec<AgeingBucket>,
+) -> Result<AgeingReport, String> {
+    let open = invoices(conn, as_of, supplier_id, &buckets).await?;
+
+    // Paid or deducted by the ageing date, less what had been set against bills dated by then
+    let advances: Vec<(i64, String, Option<i64>, f64)> = sqlx::query_as(
+        "SELECT s.id, s.name, s.payment_terms_days,
+                ROUND(SUM(sp.amount + sp.tds_amount - COALESCE((
+                    SELECT SUM(pa.amount) FROM payment_allocations pa
+                    JOIN purchase_entries pe ON pe.id = pa.purchase_id
+                    WHERE pa.payment_id = sp.id AND pe.date <= ?1), 0.0)), 2) AS advance
+         FROM supplier_payments sp JOIN suppliers s ON s.id = sp.supplier_id
+         WHERE sp.payment_date <= ?1 AND (?2 IS NULL OR sp.supplier_id = ?2)
+         GROUP BY s.id
+         HAVING advance >= 0.005",
+    )
+    .bind(as_of)
+    .bind(supplier_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Debit less credit notes raised by then that adjust no live bill dated by then
+    let notes: Vec<(i64, String, Option<i64>, f64)> = sqlx::query_as(
+        "SELECT s.id, s.name, s.payment_terms_days,
+                ROUND(SUM(CASE n.kind WHEN 'debit' THEN n.amount ELSE -n.amount END), 2) AS unapplied
+         FROM supplier_notes n JOIN suppliers s ON s.id = n.supplier_id
+         WHERE n.note_date <= ?1 AND (?2 IS NULL OR n.supplier_id = ?2)
+           AND NOT EXISTS (SELECT 1 FROM purchase_entries pe
+                           WHERE pe.id = n.purchase_id AND pe.deleted_at IS NULL
+                             AND pe.status <> 'cancelled' AND pe.date <= ?1)
+         GROUP BY s.id
+         HAVING ABS(unapplied) >= 0.005",
+    )
+    .bind(as_of)
+    .bind(supplier_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut rows: BTreeMap<i64, AgeingSupplierRow> = BTreeMap::new();
+    let blank = |id: i64, name: &str, payment_terms_days: Option<i64>| AgeingSupplierRow {
+        supplier_id: id,
+        supplier_name: name.to_string(),
+        payment_terms_days,
+        invoices: 0,
+        not_due: 0.0,
+        buckets: vec![0.0; buckets.len()],
+        outstanding: 0.0,
+        advance: 0.0,
+        unapplied_notes: 0.0,
+        net_balance: 0.0,
+    };
+    for inv in &open {
+        let row = rows
+            .entry(inv.supplier_id)
+            .or_insert_with(|| blank(inv.supplier_id, &inv.supplier_name, inv.payment_terms_days));
+        row.invoices += 1;
+        match inv.bucket {
+            Some(b) => row.buckets[b] += inv.outstanding,
+            None => row.not_due += inv.outstanding,
+        }
+        row.outstanding += inv.outstanding;
+    }
+    for (id, name, payment_terms_days, advance) in advances {
+        rows.entry(id).or_insert_with(|| blank(id, &name, payment_terms_days)).advance = advance;
+    }
+    for (id, name, payment_terms_days, unapplied) in notes {
+        rows.entry(id).or_insert_with(|| blank(id, &name, payment_terms_days)).unapplied_notes = unapplied;
+    }
+
+    let round = |v: f64| (v * 100.0).round() / 100.0;
+    let mut rows: Vec<AgeingSupplierRow> = rows
+        .into_values()
+        .map(|mut r| {
+            r.not_due = round(r.not_due);
+            r.buckets.iter_mut().for_each(|v| *v = round(*v));
+            r.outstanding = round(r.outstanding);
+            r.net_balance = round(r.outstanding - r.advance - r.unapplied_notes);
+            r
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.net_balance
+            .total_cmp(&a.net_balance)
+            .then_with(|| a.supplier_name.cmp(&b.supplier_name))
+    });
+
+    let sum = |f: &dyn Fn(&AgeingSupplierRow) -> f64| round(rows.iter().map(f).sum());
+    let totals = (0..buckets.len()).map(|b| sum(&|r| r.buckets[b])).collect();
+    Ok(AgeingReport {
+        as_of: as_of.to_string(),
+        not_due: sum(&|r| r.not_due),
+        totals,
+        outstanding: sum(&|r| r.outstanding),
+        advance: sum(&|r| r.advance),
+        unapplied_notes: sum(&|r| r.unapplied_notes),
+        net_balance: sum(&|r| r.net_balance),
+        buckets,
+        rows,
+    })
+}
@@ -0,0 +1,322 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::SqliteConnection;
+
+use crate::models::{ApprovalRule, Approver};
+use crate::workflow;
+
+pub const RULE_SELECT: &str = "SELECT r.id, r.name, r.min_amount, r.max_amount, r.supplier_id, r.dimension_id,
+        r.active, r.position, r.created_by, r.created_at,
+        COALESCE((
+          SELECT json_group_array(json_object('user_id', a.user_id, 'username', a.username))
+          FROM (SELECT ra.user_id, u.username FROM approval_rule_approvers ra JOIN users u ON u.id = ra.user_id
+                WHERE ra.rule_id = r.id ORDER BY ra.step_no) a
+        ), '[]') AS approvers_json
+     FROM approval_rules r";
+
+#[derive(sqlx::FromRow)]
+pub struct ApprovalRuleRow {
+    id: i64,
+    name: String,
+    min_amount: f64,
+    max_amount: Option<f64>,
+    supplier_id: Option<i64>,
+    dimension_id: Option<i64>,
+    active: i64,
+    position: i64,
+    created_by: Option<i64>,
+    created_at: String,
+    approvers_json: String,
+}
+
+impl TryFrom<ApprovalRuleRow> for ApprovalRule {
+    type Error = String;
+
+    fn try_from(r: ApprovalRuleRow) -> Result<Self, String> {
+        let approvers: Vec<Approver> = serde_json::from_str(&r.approvers_json)
+            .map_err(|e| format!("Approval rule '{}' has unreadable approvers: {}", r.name, e))?;
+        Ok(ApprovalRule {
+            id: r.id,
+            name: r.name,
+            min_amount: r.min_amount,
+            max_amount: r.max_amount,
+            supplier_id: r.supplier_id,
+            dimension_id: r.dimension_id,
+            active: r.active != 0,
+            position: r.position,
+            approvers,
+            created_by: r.created_by,
+            created_at: r.created_at,
+        })
+    }
+}
+
+/// Check an amount band: a non-negative lower bound and, if given, a higher upper bound.
+pub fn check_band(min_amount: f64, max_amount: Option<f64>) -> Result<(), String> {
+    if min_amount.is_nan() || min_amount < 0.0 {
+        return Err("The minimum amount can't be negative".to_string());
+    }
+    if max_amount.is_some_and(|max| max.is_nan() || max <= min_amount) {
+        return Err("The maximum amount must be above the minimum".to_string());
+    }
+    Ok(())
+}
+
+/// Replace the approvers of a rule, signing in the given order.
+pub async fn set_approvers(conn: &mut SqliteConnection, rule_id: i64, approver_ids: &[i64]) -> Result<(), String> {
+    if approver_ids.is_empty() {
+        return Err("An approval rule needs at least one approver".to_string());
+    }
+    let mut seen = HashSet::new();
+    for id in approver_ids {
+        if !seen.insert(*id) {
+            return Err("Each approver can appear only once in a rule".to_string());
+        }
+        let (exists,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM users WHERE id = ?1)")
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("User {} not found", id));
+        }
+    }
+    sqlx::query("DELETE FROM approval_rule_approvers WHERE rule_id = ?1")
+        .bind(rule_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (i, id) in approver_ids.iter().enumerate() {
+        sqlx::query("INSERT INTO approval_rule_approvers (rule_id, step_no, user_id) VALUES (?1, ?2, ?3)")
+            .bind(rule_id)
+            .bind(i as i64 + 1)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Approvers the active rules ask for on an entry as it stands, in signing order: rule by
+/// rule, then step by step. Someone named by several rules signs once, at their first place.
+async fn required(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Vec<(i64, i64)>, String> {
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT r.id, ra.user_id
+         FROM purchase_entries pe
+         JOIN approval_rules r ON r.active = 1
+            AND pe.invoice_value >= r.min_amount
+            AND (r.max_amount IS NULL OR pe.invoice_value < r.max_amount)
+            AND (r.supplier_id IS NULL OR r.supplier_id = pe.supplier_id)
+            AND (r.dimension_id IS NULL OR EXISTS (
+                SELECT 1 FROM purchase_allocations pa WHERE pa.purchase_id = pe.id AND pa.dimension_id = r.dimension_id))
+         JOIN approval_rule_approvers ra ON ra.rule_id = r.id
+         WHERE pe.id = ?1
+         ORDER BY r.position, r.id, ra.step_no",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut seen = HashSet::new();
+    Ok(rows.into_iter().filter(|(_, user)| seen.insert(*user)).collect())
+}
+
+/// Withdraw sign-offs still outstanding on an entry.
+pub async fn cancel_pending(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE purchase_approvals SET status = 'cancelled', decided_at = datetime('now')
+         WHERE purchase_id = ?1 AND status = 'pending'",
+    )
+    .bind(purchase_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Start a new round of sign-offs for an entry from the rules that match it now.
+/// Returns how many approvers were asked.
+pub async fn request(conn: &mut SqliteConnection, purchase_id: i64) -> Result<usize, String> {
+    cancel_pending(conn, purchase_id).await?;
+    let steps = required(conn, purchase_id).await?;
+    if steps.is_empty() {
+        return Ok(0);
+    }
+    let (round,): (i64,) =
+        sqlx::query_as("SELECT COALESCE(MAX(round), 0) + 1 FROM purchase_approvals WHERE purchase_id = ?1")
+            .bind(purchase_id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    for (i, (rule_id, approver_id)) in steps.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO purchase_approvals (purchase_id, round, step_no, rule_id, approver_id, amount)
+             SELECT ?1, ?2, ?3, ?4, ?5, invoice_value FROM purchase_entries WHERE id = ?1",
+        )
+        .bind(purchase_id)
+        .bind(round)
+        .bind(i as i64 + 1)
+        .bind(rule_id)
+        .bind(approver_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(steps.len())
+}
+
+/// What an approval covers on an entry: supplier, amounts, lines and splits. Lines are
+/// counted by position so replacing them with the same values doesn't read as a change.
+/// None if the entry doesn't exist.
+pub async fn fingerprint(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Option<String>, String> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT json_array(pe.supplier_id, pe.basic_value, pe.sgst, pe.cgst, pe.igst, pe.invoice_value, pe.tds_value,
+            (SELECT json_group_array(json_array(i.part_no, i.qty, i.price, i.amount))
+             FROM (SELECT part_no, qty, price, amount FROM purchase_items WHERE purchase_id = pe.id ORDER BY id) i),
+            (SELECT json_group_array(json_array(a.line, a.dimension_id, a.percent))
+             FROM (SELECT (SELECT COUNT(*) FROM purchase_items i WHERE i.purchase_id = pa.purchase_id AND i.id <= pa.item_id) AS line,
+                          pa.dimension_id, pa.percent
+                   FROM purchase_allocations pa WHERE pa.purchase_id = pe.id
+                   ORDER BY line, pa.dimension_id, pa.percent) a))
+         FROM purchase_entries pe WHERE pe.id = ?1",
+    )
+    .bind(purchase_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(row.map(|(f,)| f))
+}
+
+/// Send a verified or approved entry back to pending when an edit changed what its approval
+/// covers, withdrawing sign-offs still outstanding. `before` is its fingerprint ahead of the edit.
+pub async fn reopen_if_changed(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    before: Option<String>,
+    user_id: Option<i64>,
+) -> Result<(), String> {
+    let status = match workflow::current(conn, purchase_id).await? {
+        Some(s) if s == "verified" || s == "approved" => s,
+        _ => return Ok(()),
+    };
+    if fingerprint(conn, purchase_id).await? == before {
+        return Ok(());
+    }
+    cancel_pending(conn, purchase_id).await?;
+    let reason = "Amounts, supplier, lines or splits changed after verification; verify it again for approval";
+    workflow::apply(conn, purchase_id, &status, "pending", Some(reason), user_id).await
+}
+
+/// The sign-off an entry is waiting on: the first pending step of its latest round.
+/// Returns the approval id, approver id and approver name.
+async fn waiting_on(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Option<(i64, i64, String)>, String> {
+    sqlx::query_as(
+        "SELECT pa.id, pa.approver_id, COALESCE(u.username, '#' || pa.approver_id)
+         FROM purchase_approvals pa LEFT JOIN users u ON u.id = pa.approver_id
+         WHERE pa.purchase_id = ?1 AND pa.status = 'pending'
+           AND pa.round = (SELECT MAX(round) FROM purchase_approvals WHERE purchase_id = ?1)
+         ORDER BY pa.step_no
+         LIMIT 1",
+    )
+    .bind(purchase_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Fail unless everyone the rules ask for has approved the entry in its latest round.
+pub async fn check_complete(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    if let Some((_, _, name)) = waiting_on(conn, purchase_id).await? {
+        return Err(format!("This purchase is waiting for approval by {}", name));
+    }
+    let approved: HashMap<i64, f64> = sqlx::query_as::<_, (i64, f64)>(
+        "SELECT approver_id, amount FROM purchase_approvals
+         WHERE purchase_id = ?1 AND status = 'approved'
+           AND round = (SELECT MAX(round) FROM purchase_approvals WHERE purchase_id = ?1)",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .collect();
+    let (invoice_value,): (f64,) = sqlx::query_as("SELECT invoice_value FROM purchase_entries WHERE id = ?1")
+        .bind(purchase_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(amount) = approved.values().find(|a| (*a - invoice_value).abs() >= 0.005) {
+        return Err(format!(
+            "This purchase was approved at {:.2} but now comes to {:.2}; verify it again to request approval",
+            amount, invoice_value
+        ));
+    }
+    let mut missing = Vec::new();
+    for (_, user) in required(conn, purchase_id).await? {
+        if !approved.contains_key(&user) {
+            let (name,): (String,) = sqlx::query_as("SELECT COALESCE((SELECT username FROM users WHERE id = ?1), '#' || ?1)")
+                .bind(user)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            missing.push(name);
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "This purchase needs approval by {}; verify it again to request it",
+            missing.join(", ")
+        ))
+    }
+}
+
+/// Record `user_id`'s decision on the step an entry is waiting on. A rejection withdraws the
+/// remaining steps and sends the entry back to pending; the last approval moves it to approved
+/// under the verified-to-approved transition rule, so the last approver needs its role.
+/// Returns the entry's new status when it changed.
+pub async fn decide(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    user_id: i64,
+    approve: bool,
+    comment: Option<String>,
+) -> Result<Option<String>, String> {
+    let comment = comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    if !approve && comment.is_none() {
+        return Err("Give a reason for the rejection".to_string());
+    }
+    let status = workflow::current(conn, purchase_id)
+        .await?
+        .ok_or_else(|| "Purchase not found or deleted".to_string())?;
+    let step = match waiting_on(conn, purchase_id).await? {
+        Some(step) if status == "verified" => step,
+        _ => return Err("This purchase isn't waiting for approval".to_string()),
+    };
+    let (approval_id, approver_id, approver_name) = step;
+    if approver_id != user_id {
+        return Err(format!("This purchase is waiting for approval by {}", approver_name));
+    }
+
+    sqlx::query("UPDATE purchase_approvals SET status = ?2, comment = ?3, decided_at = datetime('now') WHERE id = ?1")
+        .bind(approval_id)
+        .bind(if approve { "approved" } else { "rejected" })
+        .bind(&comment)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !approve {
+        cancel_pending(conn, purchase_id).await?;
+        let reason = format!("Rejected: {}", comment.unwrap_or_default());
+        workflow::apply(conn, purchase_id, &status, "pending", Some(&reason), Some(user_id)).await?;
+        return Ok(Some("pending".to_string()));
+    }
+    if waiting_on(conn, purchase_id).await?.is_none() {
+        workflow::transition(conn, purchase_id, "approved", comment, user_id).await?;
+        return Ok(Some("approved".to_string()));
+    }
+    Ok(None)
+}
@@ -0,0 +1,402 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use sha2::{Digest, Sha256};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::attachments;
+use crate::backup;
+use crate::db::{self, Db};
+use crate::models::{ArchiveFile, ArchiveManifest, BackupInfo};
+
+/// File header of an encrypted purchase-entry archive sealed in one piece (read only).
+const MAGIC_V1: &[u8; 8] = b"PEARCH01";
+/// File header of an archive sealed in chunks, so neither side holds it in memory.
+const MAGIC: &[u8; 8] = b"PEARCH02";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Random part of each chunk nonce; the rest is a 4-byte chunk counter and a last-chunk flag.
+const NONCE_PREFIX_LEN: usize = 7;
+const HEADER_LEN_V1: usize = MAGIC_V1.len() + SALT_LEN + NONCE_LEN;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_PREFIX_LEN;
+const CHUNK_LEN: usize = 1024 * 1024;
+const TAG_LEN: usize = 16;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DB_NAME: &str = "app.db";
+const ATTACHMENTS_PREFIX: &str = "attachments/";
+
+pub const FORMAT_VERSION: i64 = 1;
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// A scratch file that is removed when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(dir: &Path, ext: &str) -> Self {
+        TempFile(dir.join(format!(".archive-{}.{}", uuid::Uuid::new_v4(), ext)))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn chunk_nonce(prefix: &[u8], counter: u32, last: bool) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[NONCE_LEN - 1] = last as u8;
+    nonce
+}
+
+/// Fill `buf` as far as the reader allows; fewer bytes than its length means end of input.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, String> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]).map_err(|e| e.to_string())? {
+            0 => break,
+            read => n += read,
+        }
+    }
+    Ok(n)
+}
+
+fn at_end(reader: &mut impl BufRead) -> Result<bool, String> {
+    Ok(reader.fill_buf().map_err(|e| e.to_string())?.is_empty())
+}
+
+/// Encrypt with AES-256-GCM under an Argon2id key, chunk by chunk. Each chunk's nonce
+/// carries its position and whether it is the last, so chunks cannot be reordered,
+/// dropped or cut off; the header is authenticated as AAD.
+fn encrypt(password: &str, input: &mut impl BufRead, out: &mut impl Write) -> Result<(), String> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    header.extend_from_slice(&salt);
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    OsRng.fill_bytes(&mut prefix);
+    header.extend_from_slice(&prefix);
+    out.write_all(&header).map_err(|e| e.to_string())?;
+
+    let key = derive_key(password, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let mut buf = vec![0u8; CHUNK_LEN];
+    let mut counter: u32 = 0;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        let last = at_end(input)?;
+        let nonce = chunk_nonce(&prefix, counter, last);
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &buf[..n], aad: &header })
+            .map_err(|_| "encryption failed".to_string())?;
+        out.write_all(&sealed).map_err(|e| e.to_string())?;
+        if last {
+            return Ok(());
+        }
+        counter = counter.checked_add(1).ok_or_else(|| "archive is too large".to_string())?;
+    }
+}
+
+fn decrypt(password: &str, input: &mut impl BufRead, out: &mut impl Write) -> Result<(), String> {
+    let mut magic = [0u8; 8];
+    if read_full(input, &mut magic)? < magic.len() {
+        return Err("not an encrypted backup archive".to_string());
+    }
+    if &magic == MAGIC_V1 {
+        return decrypt_v1(password, input, out);
+    }
+    if &magic != MAGIC {
+        return Err("not an encrypted backup archive".to_string());
+    }
+    let mut header = [0u8; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(&magic);
+    if read_full(input, &mut header[MAGIC.len()..])? < HEADER_LEN - MAGIC.len() {
+        return Err("not an encrypted backup archive".to_string());
+    }
+    let salt = &header[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let prefix = &header[MAGIC.len() + SALT_LEN..];
+
+    let key = derive_key(password, salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let mut buf = vec![0u8; CHUNK_LEN + TAG_LEN];
+    let mut counter: u32 = 0;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        let last = at_end(input)?;
+        let nonce = chunk_nonce(prefix, counter, last);
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &buf[..n], aad: &header })
+            .map_err(|_| "wrong password or archive is corrupted".to_string())?;
+        out.write_all(&plain).map_err(|e| e.to_string())?;
+        if last {
+            return Ok(());
+        }
+        counter = counter.checked_add(1).ok_or_else(|| "archive is damaged".to_string())?;
+    }
+}
+
+/// Archives sealed in one piece by earlier versions; the rest of the header follows `MAGIC_V1`.
+fn decrypt_v1(password: &str, input: &mut impl Read, out: &mut impl Write) -> Result<(), String> {
+    let mut data = MAGIC_V1.to_vec();
+    input.read_to_end(&mut data).map_err(|e| e.to_string())?;
+    if data.len() < HEADER_LEN_V1 {
+        return Err("not an encrypted backup archive".to_string());
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN_V1);
+    let salt = &header[MAGIC_V1.len()..MAGIC_V1.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&header[MAGIC_V1.len() + SALT_LEN..]);
+
+    let key = derive_key(password, salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let plain = cipher
+        .decrypt(nonce, Payload { msg: ciphertext, aad: header })
+        .map_err(|_| "wrong password or archive is corrupted".to_string())?;
+    out.write_all(&plain).map_err(|e| e.to_string())
+}
+
+/// Copy `from` into `to`, returning the SHA-256 and size of what passed through.
+fn copy_hashed(from: &mut impl Read, to: &mut impl Write) -> Result<(String, i64), String> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size = 0i64;
+    loop {
+        let n = from.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        to.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        size += n as i64;
+    }
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+/// Snapshot the database and attachments into a password-encrypted archive at `dest`.
+pub async fn export(db: &Db, dest: &Path, password: &str) -> Result<ArchiveManifest, String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!("Archive password must be at least {} characters", MIN_PASSWORD_LEN));
+    }
+
+    let snapshot = TempFile::new(&db.data_dir(), "db");
+    backup::online_backup(db, &snapshot.0).await?;
+
+    let (migration_version,): (i64,) =
+        sqlx::query_as("SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(&db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let data_dir = db.data_dir();
+    let dest = dest.to_path_buf();
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || write_archive(&data_dir, &snapshot.0, migration_version, &dest, &password))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Zip the snapshot and attachment blobs into a scratch file, then seal it into `dest`.
+fn write_archive(
+    data_dir: &Path,
+    snapshot: &Path,
+    migration_version: i64,
+    dest: &Path,
+    password: &str,
+) -> Result<ArchiveManifest, String> {
+    let root = attachments::root(data_dir);
+    let mut blobs = attachments::blobs(&root)?;
+    blobs.sort();
+
+    let plain = TempFile::new(data_dir, "zip");
+    let mut zip = ZipWriter::new(File::create(&plain.0).map_err(|e| e.to_string())?);
+    let opts = FileOptions::default().compression_method(CompressionMethod::Deflated).large_file(true);
+
+    zip.start_file(DB_NAME, opts).map_err(|e| e.to_string())?;
+    let mut db_file = File::open(snapshot).map_err(|e| e.to_string())?;
+    let (db_sha256, db_size_bytes) = copy_hashed(&mut db_file, &mut zip)?;
+
+    let mut files = Vec::with_capacity(blobs.len());
+    for sha256 in blobs {
+        let name = format!("{}{}/{}", ATTACHMENTS_PREFIX, &sha256[..2], sha256);
+        zip.start_file(name.as_str(), opts).map_err(|e| e.to_string())?;
+        let mut blob = File::open(attachments::blob_path(&root, &sha256)).map_err(|e| e.to_string())?;
+        let (actual, size_bytes) = copy_hashed(&mut blob, &mut zip)?;
+        if actual != sha256 {
+            return Err(format!("attachment file {} is damaged", sha256));
+        }
+        files.push(ArchiveFile { path: name, size_bytes, sha256 });
+    }
+
+    let manifest = ArchiveManifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        migration_version,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        db_sha256,
+        db_size_bytes,
+        files,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_NAME, opts).map_err(|e| e.to_string())?;
+    zip.write_all(&manifest_json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Seal under a temporary name so a failed export never leaves a truncated archive
+    let sealed = TempFile::new(dest.parent().unwrap_or(Path::new(".")), "part");
+    {
+        let mut input = BufReader::new(File::open(&plain.0).map_err(|e| e.to_string())?);
+        let mut out = BufWriter::new(File::create(&sealed.0).map_err(|e| e.to_string())?);
+        encrypt(password, &mut input, &mut out)?;
+        out.flush().map_err(|e| e.to_string())?;
+    }
+    std::fs::rename(&sealed.0, dest).map_err(|e| e.to_string())?;
+    Ok(manifest)
+}
+
+/// A decrypted and checksum-verified archive, unpacked into scratch files.
+struct Opened {
+    manifest: ArchiveManifest,
+    zip: TempFile,
+    db: TempFile,
+}
+
+/// Only the store's own layout is accepted, `attachments/<first two of sha256>/<sha256>`,
+/// so no name in an archive can point outside the store or at another blob.
+fn is_blob_path(path: &str, sha256: &str) -> bool {
+    attachments::is_sha256(sha256)
+        && path
+            .strip_prefix(ATTACHMENTS_PREFIX)
+            .and_then(|rel| rel.split_once('/'))
+            .is_some_and(|(fan, name)| fan == &sha256[..2] && name == sha256)
+}
+
+/// Copy the entry `name` into `out`, returning its SHA-256.
+fn read_entry(zip: &mut ZipArchive<File>, name: &str, out: &mut impl Write) -> Result<String, String> {
+    let mut file = zip.by_name(name).map_err(|_| format!("archive is missing {}", name))?;
+    copy_hashed(&mut file, out).map(|(sha256, _)| sha256)
+}
+
+/// Decrypt `source` into scratch files under `work_dir` and verify every entry.
+fn open(source: &Path, password: &str, work_dir: &Path) -> Result<Opened, String> {
+    let zip_file = TempFile::new(work_dir, "zip");
+    {
+        let mut input = BufReader::new(File::open(source).map_err(|e| e.to_string())?);
+        let mut out = BufWriter::new(File::create(&zip_file.0).map_err(|e| e.to_string())?);
+        decrypt(password, &mut input, &mut out)?;
+        out.flush().map_err(|e| e.to_string())?;
+    }
+    let file = File::open(&zip_file.0).map_err(|e| e.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("archive is damaged: {}", e))?;
+
+    let mut manifest_json = Vec::new();
+    read_entry(&mut zip, MANIFEST_NAME, &mut manifest_json)?;
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(&manifest_json).map_err(|e| format!("invalid manifest: {}", e))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!("archive format {} is newer than this app supports", manifest.format_version));
+    }
+    let latest = db::latest_migration_version();
+    if manifest.migration_version > latest {
+        return Err(format!(
+            "archive was made by app {} (migration {}), this build only knows migration {}",
+            manifest.app_version, manifest.migration_version, latest
+        ));
+    }
+
+    let db_file = TempFile::new(work_dir, "db");
+    {
+        let mut out = BufWriter::new(File::create(&db_file.0).map_err(|e| e.to_string())?);
+        if read_entry(&mut zip, DB_NAME, &mut out)? != manifest.db_sha256 {
+            return Err("database checksum mismatch".to_string());
+        }
+        out.flush().map_err(|e| e.to_string())?;
+    }
+
+    for f in &manifest.files {
+        if !is_blob_path(&f.path, &f.sha256) {
+            return Err(format!("unsafe path in archive: {}", f.path));
+        }
+        if read_entry(&mut zip, &f.path, &mut std::io::sink())? != f.sha256 {
+            return Err(format!("checksum mismatch for {}", f.path));
+        }
+    }
+
+    Ok(Opened { manifest, zip: zip_file, db: db_file })
+}
+
+/// Decrypt and verify an archive without touching the live data. Scratch files go
+/// in `work_dir`, which should be the data folder: they hold the decrypted data.
+pub async fn inspect(work_dir: &Path, source: &Path, password: &str) -> Result<ArchiveManifest, String> {
+    let (work_dir, source, password) = (work_dir.to_path_buf(), source.to_path_buf(), password.to_string());
+    tokio::task::spawn_blocking(move || open(&source, &password, &work_dir).map(|o| o.manifest))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Decrypt, verify and restore an archive. Attachments are merged into the
+/// attachments folder; the database goes through the regular verified restore.
+pub async fn import(db: &Db, source: &Path, password: &str) -> Result<BackupInfo, String> {
+    let (work_dir, source, password) = (db.data_dir(), source.to_path_buf(), password.to_string());
+    let opened = tokio::task::spawn_blocking(move || open(&source, &password, &work_dir))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let safety = backup::restore(db, &opened.db.0).await?;
+
+    let root = attachments::root(&db.data_dir());
+    tokio::task::spawn_blocking(move || unpack_attachments(&opened, &root))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(safety)
+}
+
+/// Write the archive's blobs into the store. Blobs are named by content, so one
+/// already in the store is left as it is.
+fn unpack_attachments(opened: &Opened, root: &Path) -> Result<(), String> {
+    let file = File::open(&opened.zip.0).map_err(|e| e.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("archive is damaged: {}", e))?;
+    for f in &opened.manifest.files {
+        let target = attachments::blob_path(root, &f.sha256);
+        if target.exists() {
+            continue;
+        }
+        let dir = target.parent().unwrap_or(root);
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let tmp = dir.join(format!(".{}.{}", f.sha256, uuid::Uuid::new_v4()));
+        let written = File::create(&tmp)
+            .map_err(|e| e.to_string())
+            .and_then(|mut out| read_entry(&mut zip, &f.path, &mut out));
+        match written {
+            Ok(sha256) if sha256 == f.sha256 => std::fs::rename(&tmp, &target).map_err(|e| e.to_string())?,
+            Ok(_) => {
+                std::fs::remove_file(&tmp).ok();
+                return Err(format!("checksum mismatch for {}", f.path));
+            }
+            Err(e) => {
+                std::fs::remove_file(&tmp).ok();
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
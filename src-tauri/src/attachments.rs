@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::db::DbPool;
+
+/// Largest document accepted as an attachment.
+pub const MAX_ATTACHMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Attachment kinds, matching the CHECK constraint on `attachments.kind`.
+pub const KINDS: &[&str] = &["invoice", "eway_bill", "grn", "other"];
+
+/// Content store for attachments, next to `app.db`. Archives pick up this folder as is.
+pub fn root(data_dir: &Path) -> PathBuf {
+    data_dir.join("attachments")
+}
+
+/// Where the blob with `sha256` lives under `root`; two-character fan-out keeps folders small.
+pub fn blob_path(root: &Path, sha256: &str) -> PathBuf {
+    root.join(&sha256[..2]).join(sha256)
+}
+
+pub fn is_sha256(name: &str) -> bool {
+    name.len() == 64 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// MIME type for the document formats we accept, from the file extension.
+pub fn mime_type(file_name: &str) -> Option<&'static str> {
+    let ext = Path::new(file_name).extension()?.to_string_lossy().to_lowercase();
+    Some(match ext.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "tif" | "tiff" => "image/tiff",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "heic" => "image/heic",
+        _ => return None,
+    })
+}
+
+/// Copy `source` into the store under its content hash. Storing the same file twice is a no-op.
+/// Returns the hash and size.
+pub fn store(root: &Path, source: &Path) -> Result<(String, i64), String> {
+    let size = std::fs::metadata(source).map_err(|e| format!("cannot read {}: {}", source.display(), e))?.len();
+    if size > MAX_ATTACHMENT_BYTES {
+        return Err(format!("File is larger than {} MB", MAX_ATTACHMENT_BYTES / (1024 * 1024)));
+    }
+    let bytes = std::fs::read(source).map_err(|e| format!("cannot read {}: {}", source.display(), e))?;
+    let sha256 = format!("{:x}", Sha256::digest(&bytes));
+
+    let dest = blob_path(root, &sha256);
+    if !dest.exists() {
+        let dir = dest.parent().ok_or_else(|| "invalid attachment path".to_string())?;
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        // Write under a temporary name so a crash never leaves a truncated blob behind
+        let tmp = dir.join(format!(".{}.{}", sha256, uuid::Uuid::new_v4()));
+        std::fs::write(&tmp, &bytes).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &dest).map_err(|e| e.to_string())?;
+    }
+    Ok((sha256, bytes.len() as i64))
+}
+
+/// Hashes of all blobs present under `root`.
+pub fn blobs(root: &Path) -> Result<Vec<String>, String> {
+    let mut out = Vec::new();
+    if !root.exists() {
+        return Ok(out);
+    }
+    for fan in std::fs::read_dir(root).map_err(|e| e.to_string())? {
+        let fan = fan.map_err(|e| e.to_string())?.path();
+        if !fan.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&fan).map_err(|e| e.to_string())? {
+            let name = entry.map_err(|e| e.to_string())?.file_name().to_string_lossy().into_owned();
+            if is_sha256(&name) {
+                out.push(name);
+            }
+        }
+    }
+    Ok(out)
+}
+
+async fn referenced(pool: &DbPool) -> Result<HashSet<String>, String> {
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT sha256 FROM attachments")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(|(h,)| h).collect())
+}
+
+/// Delete the blobs of unlinked attachments that no attachment row points at any more. Only
+/// the given hashes are looked at: a blob that `store` has just written for an attachment
+/// about to be inserted must not be swept up. Returns how many were removed.
+pub async fn remove_unreferenced(pool: &DbPool, root: &Path, hashes: &[String]) -> Result<usize, String> {
+    let mut removed = 0;
+    for hash in hashes.iter().collect::<HashSet<_>>() {
+        let (used,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM attachments WHERE sha256 = ?1)")
+            .bind(hash)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let path = blob_path(root, hash);
+        if !used && path.exists() {
+            std::fs::remove_file(path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Copy blobs missing from `to` over from `from`. Blobs never change, so existing ones are skipped.
+pub fn mirror(from: &Path, to: &Path) -> Result<usize, String> {
+    let mut copied = 0;
+    for hash in blobs(from)? {
+        let dest = blob_path(to, &hash);
+        if dest.exists() {
+            continue;
+        }
+        std::fs::create_dir_all(dest.parent().unwrap_or(to)).map_err(|e| e.to_string())?;
+        std::fs::copy(blob_path(from, &hash), &dest).map_err(|e| e.to_string())?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// After a restore, fetch blobs the restored database refers to but the live store lacks
+/// from the given backup stores. Returns the hashes still missing.
+pub async fn recover(pool: &DbPool, root: &Path, sources: &[PathBuf]) -> Result<Vec<String>, String> {
+    let mut missing = Vec::new();
+    for hash in referenced(pool).await? {
+        let dest = blob_path(root, &hash);
+        if dest.exists() {
+            continue;
+        }
+        let found = sources.iter().map(|s| blob_path(s, &hash)).find(|p| p.exists());
+        match found {
+            Some(src) => {
+                std::fs::create_dir_all(dest.parent().unwrap_or(root)).map_err(|e| e.to_string())?;
+                std::fs::copy(src, &dest).map_err(|e| e.to_string())?;
+            }
+            None => missing.push(hash),
+        }
+    }
+    Ok(missing)
+}
+
+/// Copy a blob out under its original file name so the system viewer can recognise it.
+pub fn materialize(root: &Path, sha256: &str, id: i64, file_name: &str) -> Result<PathBuf, String> {
+    let src = blob_path(root, sha256);
+    if !src.exists() {
+        return Err(format!("Attachment file is missing from {}", root.display()));
+    }
+    // Keep only the final path component of the stored name
+    let name = Path::new(file_name)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| sha256.to_string());
+    let dir = std::env::temp_dir().join("purchase-attachments").join(id.to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dest = dir.join(name);
+    std::fs::copy(&src, &dest).map_err(|e| e.to_string())?;
+    Ok(dest)
+}
+
+/// Open `path` with the system's default application.
+pub fn launch(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", "start", ""]).arg(path);
+        c
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("open");
+        c.arg(path);
+        c
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = {
+        let mut c = std::process::Command::new("xdg-open");
+        c.arg(path);
+        c
+    };
+    cmd.spawn().map(|_| ()).map_err(|e| format!("cannot open {}: {}", path.display(), e))
+}
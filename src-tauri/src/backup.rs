@@ -0,0 +1,381 @@
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::Duration;
+
+use libsqlite3_sys as ffi;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use tauri::{AppHandle, Manager};
+
+use crate::attachments;
+use crate::db::{self, Db, DbPool};
+use crate::models::{BackupInfo, BackupSettings};
+
+/// How often the scheduler wakes up to check whether a backup is due.
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
+pub const KIND_AUTO: &str = "auto";
+pub const KIND_MANUAL: &str = "manual";
+pub const KIND_PRE_RESTORE: &str = "pre-restore";
+
+/// Backups live next to `app.db` in a `backups` folder.
+pub fn backups_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .map(|p| p.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+/// File name for a new backup of the given kind, e.g. `auto-20240501-093000.db`.
+pub fn backup_file_name(kind: &str) -> String {
+    format!("{}-{}.db", kind, chrono::Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Free path in `dir` for a new backup; a second backup within the same second
+/// gets a `-2`, `-3`, ... suffix instead of failing.
+fn next_backup_path(dir: &Path, kind: &str) -> PathBuf {
+    let name = backup_file_name(kind);
+    let first = dir.join(&name);
+    if !first.exists() {
+        return first;
+    }
+    let stem = name.trim_end_matches(".db");
+    (2..)
+        .map(|n| dir.join(format!("{}-{}.db", stem, n)))
+        .find(|p| !p.exists())
+        .unwrap_or(first)
+}
+
+/// Copy the live database into `dest` using SQLite's online backup API.
+/// Safe to run while the pool is serving other commands: the copy reads the file
+/// through its own connection on a blocking thread, not a pooled one.
+pub async fn online_backup(db: &Db, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if dest.exists() {
+        return Err(format!("backup target already exists: {}", dest.display()));
+    }
+    copy_file(db.path(), dest).await
+}
+
+/// Copy the database at `source` over `dest` page by page. Connections already open
+/// on `dest` stay usable and see the new content once the copy commits.
+async fn copy_file(source: &Path, dest: &Path) -> Result<(), String> {
+    let src_c = CString::new(source.to_string_lossy().as_bytes()).map_err(|e| e.to_string())?;
+    let dest_c = CString::new(dest.to_string_lossy().as_bytes()).map_err(|e| e.to_string())?;
+
+    tokio::task::spawn_blocking(move || unsafe { copy_database(&src_c, &dest_c) })
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+unsafe fn copy_database(source: &CString, dest: &CString) -> Result<(), String> {
+    let main = c"main".as_ptr();
+
+    let mut src: *mut ffi::sqlite3 = ptr::null_mut();
+    let rc = ffi::sqlite3_open_v2(source.as_ptr(), &mut src, ffi::SQLITE_OPEN_READONLY, ptr::null());
+    if rc != ffi::SQLITE_OK {
+        let err = errmsg(src);
+        ffi::sqlite3_close(src);
+        return Err(format!("failed to open database: {}", err));
+    }
+
+    let mut dst: *mut ffi::sqlite3 = ptr::null_mut();
+    let rc = ffi::sqlite3_open_v2(
+        dest.as_ptr(),
+        &mut dst,
+        ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+        ptr::null(),
+    );
+    if rc != ffi::SQLITE_OK {
+        let err = errmsg(dst);
+        ffi::sqlite3_close(dst);
+        ffi::sqlite3_close(src);
+        return Err(format!("failed to open backup target: {}", err));
+    }
+
+    let backup = ffi::sqlite3_backup_init(dst, main, src, main);
+    if backup.is_null() {
+        let err = errmsg(dst);
+        ffi::sqlite3_close(dst);
+        ffi::sqlite3_close(src);
+        return Err(format!("failed to start backup: {}", err));
+    }
+
+    let mut rc;
+    loop {
+        rc = ffi::sqlite3_backup_step(backup, -1);
+        match rc {
+            ffi::SQLITE_DONE => break,
+            ffi::SQLITE_OK | ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => {
+                ffi::sqlite3_sleep(50);
+            }
+            _ => break,
+        }
+    }
+    ffi::sqlite3_backup_finish(backup);
+
+    let result = if rc == ffi::SQLITE_DONE {
+        Ok(())
+    } else {
+        Err(format!("backup failed: {}", errmsg(dst)))
+    };
+    ffi::sqlite3_close(dst);
+    ffi::sqlite3_close(src);
+    result
+}
+
+unsafe fn errmsg(conn: *mut ffi::sqlite3) -> String {
+    if conn.is_null() {
+        return "out of memory".to_string();
+    }
+    std::ffi::CStr::from_ptr(ffi::sqlite3_errmsg(conn))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Attachment blobs shared by all backups in the backups folder.
+fn attachments_store(db_path: &Path) -> PathBuf {
+    attachments::root(&backups_dir(db_path))
+}
+
+/// Take a backup of the given kind into the backups folder. Attachment files are
+/// mirrored into the folder's shared store so every backup can be restored whole.
+pub async fn create(db: &Db, kind: &str) -> Result<BackupInfo, String> {
+    let dest = next_backup_path(&backups_dir(db.path()), kind);
+    online_backup(db, &dest).await?;
+    attachments::mirror(&attachments::root(&db.data_dir()), &attachments_store(db.path()))?;
+    describe(&dest).ok_or_else(|| format!("backup written but not readable: {}", dest.display()))
+}
+
+/// Take a backup into `dest`, a file of the user's choosing. Attachment files are mirrored
+/// into an `attachments` folder next to it, where a restore from that file looks for them.
+pub async fn create_at(db: &Db, dest: &Path) -> Result<BackupInfo, String> {
+    online_backup(db, dest).await?;
+    let dir = dest.parent().unwrap_or_else(|| Path::new("."));
+    attachments::mirror(&attachments::root(&db.data_dir()), &attachments::root(dir))?;
+    describe(dest).ok_or_else(|| format!("backup written but not readable: {}", dest.display()))
+}
+
+/// Metadata for a backup file; `None` if it cannot be read.
+pub fn describe(path: &Path) -> Option<BackupInfo> {
+    let meta = std::fs::metadata(path).ok()?;
+    let file_name = path.file_name()?.to_string_lossy().into_owned();
+    let kind = [KIND_PRE_RESTORE, KIND_AUTO, KIND_MANUAL]
+        .iter()
+        .find(|k| file_name.starts_with(&format!("{}-", k)))
+        .map(|k| k.to_string())
+        .unwrap_or_else(|| "external".to_string());
+    let created_at = meta
+        .modified()
+        .ok()
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    Some(BackupInfo {
+        file_name,
+        path: path.to_string_lossy().into_owned(),
+        kind,
+        size_bytes: meta.len() as i64,
+        created_at,
+    })
+}
+
+/// All `.db` files in the backups folder, newest first.
+pub fn list(db_path: &Path) -> Result<Vec<BackupInfo>, String> {
+    let dir = backups_dir(db_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out: Vec<BackupInfo> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|x| x == "db").unwrap_or(false))
+        .filter_map(|p| describe(&p))
+        .collect();
+    // Names embed a sortable timestamp; fall back to mtime across kinds.
+    out.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.file_name.cmp(&a.file_name)));
+    Ok(out)
+}
+
+/// Apply retention to scheduled backups: the newest `keep_last` are always kept,
+/// older ones are removed once they are more than `keep_days` old.
+/// Manual and pre-restore backups are never rotated.
+pub fn prune(db_path: &Path, settings: &BackupSettings) -> Result<usize, String> {
+    let cutoff = (chrono::Local::now() - chrono::Duration::days(settings.keep_days))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let mut removed = 0;
+    let autos = list(db_path)?.into_iter().filter(|b| b.kind == KIND_AUTO);
+    for b in autos.skip(settings.keep_last.max(1) as usize) {
+        if b.created_at < cutoff {
+            std::fs::remove_file(&b.path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Integrity and migration checks run against a candidate database before it is restored.
+/// Returns the candidate's migration version.
+pub async fn verify_candidate(path: &Path) -> Result<i64, String> {
+    let opts = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(false);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(opts)
+        .await
+        .map_err(|e| format!("cannot open backup: {}", e))?;
+
+    let result = check_pool(&pool).await;
+    pool.close().await;
+    result
+}
+
+async fn check_pool(pool: &DbPool) -> Result<i64, String> {
+    let integrity: Vec<(String,)> = sqlx::query_as("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("integrity check failed: {}", e))?;
+    if integrity.len() != 1 || integrity[0].0 != "ok" {
+        let details: Vec<String> = integrity.into_iter().take(5).map(|r| r.0).collect();
+        return Err(format!("backup is corrupt: {}", details.join("; ")));
+    }
+
+    let applied: Vec<(i64, Vec<u8>)> = sqlx::query_as(
+        "SELECT version, checksum FROM _sqlx_migrations WHERE success = 1 ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|_| "file is not a purchase-entry database (no migration history)".to_string())?;
+
+    let latest = db::latest_migration_version();
+    for (version, checksum) in &applied {
+        if *version > latest {
+            return Err(format!(
+                "backup was made by a newer app version (migration {} > {})",
+                version, latest
+            ));
+        }
+        let known = db::MIGRATOR
+            .iter()
+            .find(|m| m.version == *version && !m.migration_type.is_down_migration());
+        match known {
+            Some(m) if m.checksum.as_ref() == checksum.as_slice() => {}
+            Some(_) => return Err(format!("migration {} in backup does not match this build", version)),
+            None => return Err(format!("backup contains unknown migration {}", version)),
+        }
+    }
+
+    applied
+        .last()
+        .map(|(v, _)| *v)
+        .ok_or_else(|| "backup has no applied migrations".to_string())
+}
+
+fn remove_sidecars(path: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let mut p = path.as_os_str().to_owned();
+        p.push(suffix);
+        std::fs::remove_file(PathBuf::from(p)).ok();
+    }
+}
+
+/// Verify `source`, take a safety backup of the live database, then copy the
+/// candidate in and re-initialize the pool (running any newer migrations).
+/// Returns the safety backup so the user can roll back.
+pub async fn restore(db: &Db, source: &Path) -> Result<BackupInfo, String> {
+    let live = db.path().to_path_buf();
+    let staged = live.with_extension("db.restore");
+    remove_sidecars(&staged);
+    std::fs::copy(source, &staged).map_err(|e| format!("cannot stage backup: {}", e))?;
+
+    if let Err(e) = verify_candidate(&staged).await {
+        std::fs::remove_file(&staged).ok();
+        remove_sidecars(&staged);
+        return Err(e);
+    }
+
+    let safety = create(db, KIND_PRE_RESTORE).await?;
+
+    // The old pool keeps serving commands through the copy; it is closed only
+    // after the new one is installed, so no command is handed a closed pool.
+    let copied = copy_file(&staged, &live).await;
+    std::fs::remove_file(&staged).ok();
+    remove_sidecars(&staged);
+    copied.map_err(|e| format!("cannot replace database: {}", e))?;
+
+    match db::init(&live).await {
+        Ok(pool) => {
+            db.replace(pool).close().await;
+            // Bring back attachment files the restored entries refer to
+            let mut stores = vec![attachments_store(&live)];
+            if let Some(dir) = source.parent() {
+                stores.push(attachments::root(dir));
+            }
+            match attachments::recover(&db.pool(), &attachments::root(&db.data_dir()), &stores).await {
+                Ok(missing) if !missing.is_empty() => {
+                    eprintln!("[Backup] {} attachment file(s) not found in any backup store", missing.len());
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("[Backup] could not recover attachments: {}", e),
+            }
+            Ok(safety)
+        }
+        Err(err) => {
+            // Put the previous database back under the pool still installed.
+            copy_file(Path::new(&safety.path), &live).await?;
+            Err(format!("restored database failed to open, previous data kept: {}", err))
+        }
+    }
+}
+
+pub async fn load_settings(pool: &DbPool) -> Result<BackupSettings, String> {
+    sqlx::query_as::<_, BackupSettings>(
+        "SELECT enabled, interval_hours, keep_last, keep_days, last_backup_at FROM backup_settings WHERE id = 1",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn run_scheduled(db: &Db) -> Result<(), String> {
+    let pool = db.pool();
+    let (due,): (bool,) = sqlx::query_as(
+        "SELECT enabled = 1 AND (last_backup_at IS NULL
+            OR datetime(last_backup_at, '+' || interval_hours || ' hours') <= datetime('now'))
+         FROM backup_settings WHERE id = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if !due {
+        return Ok(());
+    }
+
+    let info = create(db, KIND_AUTO).await?;
+    sqlx::query("UPDATE backup_settings SET last_backup_at = datetime('now') WHERE id = 1")
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let settings = load_settings(&pool).await?;
+    let removed = prune(db.path(), &settings)?;
+    eprintln!("[Backup] wrote {} (pruned {})", info.file_name, removed);
+    Ok(())
+}
+
+/// Background task that takes scheduled backups according to `backup_settings`.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Some(db) = app.try_state::<Db>() {
+                if let Err(e) = run_scheduled(db.inner()).await {
+                    eprintln!("[Backup] scheduled backup failed: {}", e);
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    });
+}
@@ -1,15 +1,53 @@
 use tauri::State;
-use sqlx::{QueryBuilder, Sqlite};
+use sqlx::types::Json;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
 
 use crate::db::Db;
 use crate::models::{
     Page,
-    Supplier, SupplierCreate, SupplierUpdate,
+    Supplier, SupplierCreate, SupplierUpdate, SupplierDetails, SupplierDeleteError,
+    SupplierAddress, SupplierAddressCreate, SupplierAddressUpdate,
+    SupplierBankAccount, SupplierBankAccountCreate, SupplierBankAccountUpdate,
+    SupplierContact, SupplierContactCreate, SupplierContactUpdate,
+    DuplicateInvoiceGroup, DuplicateInvoiceSuspect,
+    SupplierMatch, SupplierDuplicatePair, SupplierMerge, SupplierMergeResult, InvoiceRename,
     PurchaseEntry, PurchaseCreate, PurchaseUpdate, PurchaseFilters, PurchaseItem, PurchaseItemPayload,
+    PurchaseStatusChange, PurchaseStatusTransition, PurchaseStatusTransitionInput,
+    ApprovalRule, ApprovalRuleCreate, ApprovalRuleUpdate, ApprovalInboxItem, PurchaseApproval,
+    PurchaseOrder, PurchaseOrderCreate, PurchaseOrderDetail, PurchaseOrderLine,
+    GoodsReceipt, GoodsReceiptCreate, GoodsReceiptDetail, GoodsReceiptLine,
+    MatchException, MatchExceptionRow, MatchSettings, MatchSettingsUpdate, OpenPoLine, PendingGrnLine,
+    AgeingInvoice, AgeingReport,
+    SupplierOpeningBalance, OpeningBalanceSet, SupplierNote, SupplierNoteCreate, SupplierNoteUpdate, SupplierLedger,
+    SupplierPayment, PaymentAllocation, PaymentDetail, PaymentCreate, PaymentUpdate, PurchasePayment, SupplierOutstanding,
+    Attachment, AttachmentAdded, AttachmentUse,
+    CustomField, CustomFieldCreate, CustomFieldFilter, CustomFieldUpdate, CustomValues,
+    Dimension, DimensionCreate, DimensionUpdate, DimensionSpend, AllocationInput, PurchaseAllocation,
     User, UserCreate, LoginPayload, LoginResponse, ReportSummary, PurchasesBySupplier,
+    BackupInfo, BackupSettings, BackupSettingsUpdate, ArchiveManifest,
+    SavedView, SavedViewCreate, SavedViewUpdate,
+    Item, ItemCreate, ItemUpdate, ItemImportResult, ItemSuggestion, LegacyItem,
+    Uom, UomCreate, UomUpdate, UomAlias, UomConversion, UomConversionInput, UnitMapping, UnitNormalization,
+    QuantitySummary,
+    PriceHistoryEntry, PriceVariance, PriceVarianceException, PriceVarianceSettings, PriceVarianceSettingsUpdate,
+    RecycleBinEntry, PurgeResult,
+    PurchaseRevision, PurchaseRevisionDetail, RevisionDiff, SearchHit, SearchResults,
 };
+use crate::{ageing, approvals, archive, attachments, backup, custom_fields, dedupe, dimensions, ledger, matching, payments, pricing, revisions, suggest, uom, validation, workflow};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use calamine::{Reader, Xlsx, open_workbook};
-use rust_xlsxwriter::{Workbook, Worksheet};
+use rust_xlsxwriter::{Format, Workbook};
+
+const SUPPLIER_COLUMNS: &str =
+    "id, name, gst_no, state_code, tds_flag, tds_rate, contact, email, deleted_at, deleted_by, \
+     pan, msme_no, msme_category, payment_terms_days, payment_terms, merged_into, \
+     active, deactivated_at, deactivated_by, custom_fields";
+pub(crate) const PURCHASE_COLUMNS: &str =
+    "id, supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status, deleted_at, deleted_by, custom_fields, \
+     paid_amount, noted_amount, outstanding";
+
+/// Deleted rows stay in the recycle bin at least this long before they can be purged.
+const RECYCLE_BIN_RETENTION_DAYS: i64 = 30;
 
 #[tauri::command]
 pub async fn get_suppliers(
@@ -17,6 +55,9 @@ pub async fn get_suppliers(
     page: Option<i64>,
     page_size: Option<i64>,
     name_filter: Option<String>,
+    include_deleted: Option<bool>,
+    include_inactive: Option<bool>,
+    custom_fields: Option<Vec<CustomFieldFilter>>,
 ) -> Result<Page<Supplier>, String> {
     let page = page.unwrap_or(1).max(1);
     let page_size = page_size.unwrap_or(20).clamp(1, 200);
@@ -24,148 +65,582 @@ pub async fn get_suppliers(
 
     let filter = name_filter.unwrap_or_default();
     let like = if filter.is_empty() { "%".to_string() } else { format!("%{}%", filter) };
+    let include_deleted = include_deleted.unwrap_or(false);
+    // Pickers only offer active suppliers; the supplier screen asks for all of them
+    let include_inactive = include_inactive.unwrap_or(false);
+    let custom: Vec<&CustomFieldFilter> = custom_fields.iter().flatten().collect();
+    let push_where = |qb: &mut QueryBuilder<'_, Sqlite>| {
+        qb.push(" FROM suppliers WHERE name LIKE ").push_bind(like.clone());
+        if !include_deleted {
+            qb.push(" AND deleted_at IS NULL");
+        }
+        if !include_inactive {
+            qb.push(" AND active = 1");
+        }
+        custom_fields::push_filters(qb, "custom_fields", &custom);
+    };
 
-    let total: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) as cnt FROM suppliers WHERE name LIKE ?1",
-    )
-    .bind(&like)
-    .fetch_one(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
+    let mut count_q = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) as cnt");
+    push_where(&mut count_q);
+    let total: (i64,) = count_q
+        .build_query_as()
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    let rows: Vec<Supplier> = sqlx::query_as::<_, Supplier>(
-        "SELECT id, name, gst_no, state_code, tds_flag, tds_rate, contact, email
-         FROM suppliers
-         WHERE name LIKE ?1
-         ORDER BY name ASC
-         LIMIT ?2 OFFSET ?3",
-    )
-    .bind(&like)
-    .bind(page_size)
-    .bind(offset)
-    .fetch_all(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
+    let mut q = QueryBuilder::<Sqlite>::new(format!("SELECT {SUPPLIER_COLUMNS}"));
+    push_where(&mut q);
+    q.push(" ORDER BY name ASC LIMIT ").push_bind(page_size);
+    q.push(" OFFSET ").push_bind(offset);
+    let rows: Vec<Supplier> = q
+        .build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    Ok(Page { data: rows, total: total.0, page, page_size })
+    Ok(Page { data: rows, total: Some(total.0), page, page_size, next_cursor: None })
 }
 
 // Purchases & Items
 
+/// Sortable columns for `get_purchases`, keyed by the name the frontend sends.
+const PURCHASE_SORTS: &[(&str, &str)] = &[
+    ("entry_date", "pe.entry_date"),
+    ("date", "pe.date"),
+    ("invoice_value", "pe.invoice_value"),
+    ("supplier_name", "s.name"),
+    ("gst_rate", "pe.gst_rate"),
+    ("status", "pe.status"),
+    ("outstanding", "pe.outstanding"),
+];
+
+/// Validate a requested sort against `PURCHASE_SORTS`. Returns the sort name,
+/// its SQL column and the direction keyword (newest first by default).
+fn resolve_purchase_sort(
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+) -> Result<(String, &'static str, &'static str), String> {
+    let sort_by = sort_by.unwrap_or_else(|| "entry_date".to_string());
+    let sort_col = PURCHASE_SORTS
+        .iter()
+        .find(|(name, _)| *name == sort_by)
+        .map(|(_, col)| *col)
+        .ok_or_else(|| format!("Cannot sort purchases by '{}'", sort_by))?;
+    let sort_dir = match sort_dir.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => return Err(format!("Invalid sort direction '{}'", other)),
+    };
+    Ok((sort_by, sort_col, sort_dir))
+}
+
+/// Position after the last row of a page: the sort it belongs to plus that row's sort key and id.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PurchaseCursor {
+    sort: String,
+    dir: String,
+    value: serde_json::Value,
+    id: i64,
+}
+
+impl PurchaseCursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(token: &str) -> Result<Self, String> {
+        URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| "Invalid page cursor".to_string())
+    }
+}
+
+/// Prefix every column in a comma separated list with a table alias.
+fn qualified(columns: &str, alias: &str) -> String {
+    columns
+        .split(',')
+        .map(|c| format!("{}.{}", alias, c.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// FROM/WHERE shared by every filtered purchase query: `pe` is the entry, `s` its supplier.
+/// Ends in an open WHERE so `push_purchase_filters` can append `AND` conditions.
+const PURCHASE_FROM: &str = " FROM purchase_entries pe JOIN suppliers s ON s.id = pe.supplier_id WHERE 1 = 1 ";
+
+fn push_in<'a, T>(qb: &mut QueryBuilder<'a, Sqlite>, column: &str, values: &[T])
+where
+    T: 'a + Clone + Send + sqlx::Encode<'a, Sqlite> + sqlx::Type<Sqlite>,
+{
+    qb.push(format!(" AND {} IN (", column));
+    let mut list = qb.separated(", ");
+    for v in values {
+        list.push_bind(v.clone());
+    }
+    list.push_unseparated(")");
+}
+
+fn push_range(qb: &mut QueryBuilder<'_, Sqlite>, expr: &str, min: Option<f64>, max: Option<f64>) {
+    if let Some(min) = min {
+        qb.push(format!(" AND {} >= ", expr)).push_bind(min);
+    }
+    if let Some(max) = max {
+        qb.push(format!(" AND {} <= ", expr)).push_bind(max);
+    }
+}
+
+fn push_presence(qb: &mut QueryBuilder<'_, Sqlite>, flag: Option<bool>, present: &str, absent: &str) {
+    match flag {
+        Some(true) => qb.push(format!(" AND {}", present)),
+        Some(false) => qb.push(format!(" AND {}", absent)),
+        None => qb,
+    };
+}
+
+/// How deep `any_of` groups may nest, and how many there may be in one filter.
+const MAX_FILTER_DEPTH: usize = 4;
+const MAX_FILTER_GROUPS: usize = 50;
+
+/// Append the conditions of one filter group. `any_of` groups are OR-ed together
+/// and the result AND-ed with the rest of the group. `groups` counts the `any_of`
+/// groups seen so far across the whole filter.
+fn push_filter_conditions(
+    qb: &mut QueryBuilder<'_, Sqlite>,
+    f: &PurchaseFilters,
+    depth: usize,
+    groups: &mut usize,
+) -> Result<(), String> {
+    if let Some(supplier_id) = f.supplier_id {
+        qb.push(" AND pe.supplier_id = ").push_bind(supplier_id);
+    }
+    if let Some(df) = &f.date_from {
+        qb.push(" AND pe.date >= ").push_bind(df.clone());
+    }
+    if let Some(dt) = &f.date_to {
+        qb.push(" AND pe.date <= ").push_bind(dt.clone());
+    }
+    if let Some(g) = f.gst_rate {
+        qb.push(" AND pe.gst_rate = ").push_bind(g);
+    }
+    if let Some(rates) = f.gst_rates.as_deref().filter(|r| !r.is_empty()) {
+        push_in(qb, "pe.gst_rate", rates);
+    }
+    if let Some(inv) = &f.invoice_no {
+        qb.push(" AND pe.invoice_no LIKE ").push_bind(format!("%{}%", inv));
+    }
+    if let Some(st) = &f.status {
+        qb.push(" AND pe.status = ").push_bind(st.clone());
+    }
+    if let Some(statuses) = f.statuses.as_deref().filter(|s| !s.is_empty()) {
+        push_in(qb, "pe.status", statuses);
+    }
+
+    push_range(qb, "pe.basic_value", f.basic_value_min, f.basic_value_max);
+    push_range(qb, "pe.invoice_value", f.invoice_value_min, f.invoice_value_max);
+    push_range(qb, "(pe.sgst + pe.cgst + pe.igst)", f.tax_value_min, f.tax_value_max);
+
+    if let Some(name) = &f.supplier_name {
+        qb.push(" AND s.name LIKE ").push_bind(format!("%{}%", name));
+    }
+    if let Some(gst) = &f.supplier_gst_no {
+        qb.push(" AND s.gst_no LIKE ").push_bind(format!("%{}%", gst));
+    }
+    if let Some(state) = &f.supplier_state_code {
+        qb.push(" AND s.state_code = ").push_bind(state.clone());
+    }
+
+    push_presence(qb, f.has_tds, "pe.tds_value > 0", "pe.tds_value = 0");
+    push_presence(qb, f.has_outstanding, "pe.outstanding > 0", "pe.outstanding <= 0");
+    push_presence(
+        qb,
+        f.has_narration,
+        "TRIM(COALESCE(pe.narration, '')) <> ''",
+        "TRIM(COALESCE(pe.narration, '')) = ''",
+    );
+    push_presence(
+        qb,
+        f.has_items,
+        "EXISTS (SELECT 1 FROM purchase_items pi WHERE pi.purchase_id = pe.id)",
+        "NOT EXISTS (SELECT 1 FROM purchase_items pi WHERE pi.purchase_id = pe.id)",
+    );
+    if let Some(part) = &f.part_no {
+        qb.push(" AND EXISTS (SELECT 1 FROM purchase_items pi WHERE pi.purchase_id = pe.id AND pi.part_no LIKE ")
+            .push_bind(format!("%{}%", part))
+            .push(")");
+    }
+    for id in f.dimension_ids.iter().flatten() {
+        qb.push(" AND EXISTS (SELECT 1 FROM purchase_allocations pa WHERE pa.purchase_id = pe.id AND pa.dimension_id = ")
+            .push_bind(*id)
+            .push(")");
+    }
+    if let Some(filters) = f.custom_fields.as_deref() {
+        let (supplier, purchase): (Vec<_>, Vec<_>) =
+            filters.iter().partition(|c| c.entity.as_deref() == Some("supplier"));
+        custom_fields::push_filters(qb, "pe.custom_fields", &purchase);
+        custom_fields::push_filters(qb, "s.custom_fields", &supplier);
+    }
+    if let Some(kind) = &f.unallocated_kind {
+        qb.push(
+            " AND NOT EXISTS (SELECT 1 FROM purchase_allocations pa JOIN dimensions d ON d.id = pa.dimension_id
+               WHERE pa.purchase_id = pe.id AND pa.item_id IS NULL AND d.kind = ",
+        )
+        .push_bind(kind.clone())
+        .push(")");
+    }
+
+    if let Some(any_of) = f.any_of.as_deref().filter(|g| !g.is_empty()) {
+        if depth >= MAX_FILTER_DEPTH {
+            return Err(format!("Filter groups can be nested at most {} deep", MAX_FILTER_DEPTH));
+        }
+        *groups += any_of.len();
+        if *groups > MAX_FILTER_GROUPS {
+            return Err(format!("A filter can have at most {} groups", MAX_FILTER_GROUPS));
+        }
+        qb.push(" AND (");
+        for (i, group) in any_of.iter().enumerate() {
+            if i > 0 {
+                qb.push(" OR ");
+            }
+            qb.push("(1 = 1");
+            push_filter_conditions(qb, group, depth + 1, groups)?;
+            qb.push(")");
+        }
+        qb.push(")");
+    }
+    Ok(())
+}
+
+/// Append `PurchaseFilters` as `AND` conditions after `PURCHASE_FROM`.
+/// Recycle-bin rows are excluded unless `include_deleted` is set at the top level.
+fn push_purchase_filters(qb: &mut QueryBuilder<'_, Sqlite>, f: &PurchaseFilters) -> Result<(), String> {
+    push_filter_conditions(qb, f, 0, &mut 0)?;
+    if !f.include_deleted.unwrap_or(false) {
+        qb.push(" AND pe.deleted_at IS NULL");
+    }
+    Ok(())
+}
+
+/// Report/export commands accept a date range and an optional full filter; explicit dates win.
+fn merge_date_range(filters: Option<PurchaseFilters>, date_from: Option<String>, date_to: Option<String>) -> PurchaseFilters {
+    let mut f = filters.unwrap_or_default();
+    if date_from.is_some() {
+        f.date_from = date_from;
+    }
+    if date_to.is_some() {
+        f.date_to = date_to;
+    }
+    f
+}
+
+/// List purchases newest first, or by any column in `PURCHASE_SORTS`.
+///
+/// Pass the previous page's `next_cursor` as `cursor` for keyset pagination;
+/// without a cursor `page` falls back to OFFSET paging. `include_total: false`
+/// skips the COUNT(*) query. With `view_id`, the saved view supplies the filters,
+/// sort and page size for any of those arguments left out.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_purchases(
     db: State<'_, Db>,
     filters: Option<PurchaseFilters>,
     page: Option<i64>,
     page_size: Option<i64>,
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+    cursor: Option<String>,
+    include_total: Option<bool>,
+    view_id: Option<i64>,
+    user_id: Option<i64>,
 ) -> Result<Page<PurchaseEntry>, String> {
+    let view = match view_id {
+        Some(id) => Some(fetch_saved_view(&db, id, user_id).await?),
+        None => None,
+    };
+    let (filters, page_size, sort_by, sort_dir) = match view {
+        Some(v) => (
+            filters.or(Some(v.filters)),
+            page_size.or(v.page_size),
+            sort_by.or(v.sort_by),
+            sort_dir.or(v.sort_dir),
+        ),
+        None => (filters, page_size, sort_by, sort_dir),
+    };
+
     let page = page.unwrap_or(1).max(1);
     let page_size = page_size.unwrap_or(20).clamp(1, 200);
     let offset = (page - 1) * page_size;
 
-    let f = filters.unwrap_or(PurchaseFilters {
-        supplier_id: None,
-        date_from: None,
-        date_to: None,
-        gst_rate: None,
-        invoice_no: None,
-        status: None,
-    });
-
-    // Build WHERE clause dynamically using QueryBuilder
-    let mut where_added = false;
-    let mut count_q = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM purchase_entries ");
-    let mut sel_q = QueryBuilder::<Sqlite>::new(
-        "SELECT id, supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status FROM purchase_entries ",
-    );
+    let f = filters.unwrap_or_default();
 
-    let mut push_filter = |qb: &mut QueryBuilder<Sqlite>, cond: &str| {
-        if !where_added {
-            qb.push(" WHERE ");
-            where_added = true;
-        } else {
-            qb.push(" AND ");
+    let (sort_by, sort_col, sort_dir) = resolve_purchase_sort(sort_by, sort_dir)?;
+    let after = cursor.as_deref().map(PurchaseCursor::decode).transpose()?;
+    if let Some(c) = &after {
+        if c.sort != sort_by || c.dir != sort_dir {
+            return Err("Page cursor does not match the requested sort order".to_string());
         }
-        qb.push(cond);
+    }
+
+    let total = if include_total.unwrap_or(true) {
+        let mut count_q = QueryBuilder::<Sqlite>::new(format!("SELECT COUNT(*){PURCHASE_FROM}"));
+        push_purchase_filters(&mut count_q, &f)?;
+        let total: (i64,) = count_q
+            .build_query_as()
+            .fetch_one(&db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(total.0)
+    } else {
+        None
     };
 
-    if let Some(supplier_id) = f.supplier_id {
-        push_filter(&mut count_q, "supplier_id = ");
-        count_q.push_bind(supplier_id);
-        push_filter(&mut sel_q, "supplier_id = ");
-        sel_q.push_bind(supplier_id);
-    }
-    if let Some(df) = f.date_from {
-        push_filter(&mut count_q, "date >= ");
-        count_q.push_bind(df.clone());
-        push_filter(&mut sel_q, "date >= ");
-        sel_q.push_bind(df);
-    }
-    if let Some(dt) = f.date_to {
-        push_filter(&mut count_q, "date <= ");
-        count_q.push_bind(dt.clone());
-        push_filter(&mut sel_q, "date <= ");
-        sel_q.push_bind(dt);
-    }
-    if let Some(g) = f.gst_rate {
-        push_filter(&mut count_q, "gst_rate = ");
-        count_q.push_bind(g);
-        push_filter(&mut sel_q, "gst_rate = ");
-        sel_q.push_bind(g);
-    }
-    if let Some(inv) = f.invoice_no {
-        push_filter(&mut count_q, "invoice_no LIKE ");
-        count_q.push_bind(format!("%{}%", inv));
-        push_filter(&mut sel_q, "invoice_no LIKE ");
-        sel_q.push_bind(format!("%{}%", inv));
-    }
-    if let Some(st) = f.status {
-        push_filter(&mut count_q, "status = ");
-        count_q.push_bind(st.clone());
-        push_filter(&mut sel_q, "status = ");
-        sel_q.push_bind(st);
-    }
+    let mut sel_q = QueryBuilder::<Sqlite>::new(format!("SELECT {}{PURCHASE_FROM}", qualified(PURCHASE_COLUMNS, "pe")));
+    push_purchase_filters(&mut sel_q, &f)?;
 
-    let total: (i64,) = count_q
-        .build_query_as()
-        .fetch_one(&db.0)
-        .await
-        .map_err(|e| e.to_string())?;
+    // Keyset: continue strictly after the cursor row; id breaks ties between equal sort keys
+    if let Some(c) = &after {
+        let cmp = if sort_dir == "DESC" { "<" } else { ">" };
+        sel_q.push(format!(" AND ({}, pe.id) {} (", sort_col, cmp));
+        match &c.value {
+            serde_json::Value::Number(n) => sel_q.push_bind(n.as_f64().unwrap_or_default()),
+            serde_json::Value::String(v) => sel_q.push_bind(v.clone()),
+            _ => return Err("Invalid page cursor".to_string()),
+        };
+        sel_q.push(", ").push_bind(c.id).push(")");
+    }
 
-    sel_q.push(" ORDER BY entry_date DESC, date DESC, id DESC ");
+    sel_q.push(format!(" ORDER BY {} {}, pe.id {} ", sort_col, sort_dir, sort_dir));
     sel_q.push(" LIMIT ");
     sel_q.push_bind(page_size);
-    sel_q.push(" OFFSET ");
-    sel_q.push_bind(offset);
+    if after.is_none() {
+        sel_q.push(" OFFSET ");
+        sel_q.push_bind(offset);
+    }
 
     let rows: Vec<PurchaseEntry> = sel_q
         .build_query_as()
-        .fetch_all(&db.0)
+        .fetch_all(&db.pool())
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(Page { data: rows, total: total.0, page, page_size })
+    let next_cursor = match rows.last() {
+        Some(last) if rows.len() as i64 == page_size => {
+            let value = match sort_by.as_str() {
+                "date" => serde_json::json!(last.date),
+                "invoice_value" => serde_json::json!(last.invoice_value),
+                "outstanding" => serde_json::json!(last.outstanding),
+                "gst_rate" => serde_json::json!(last.gst_rate),
+                "status" => serde_json::json!(last.status),
+                "supplier_name" => {
+                    let (name,): (String,) = sqlx::query_as("SELECT name FROM suppliers WHERE id = ?1")
+                        .bind(last.supplier_id)
+                        .fetch_one(&db.pool())
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    serde_json::json!(name)
+                }
+                _ => serde_json::json!(last.entry_date),
+            };
+            let c = PurchaseCursor { sort: sort_by, dir: sort_dir.to_string(), value, id: last.id };
+            Some(c.encode())
+        }
+        _ => None,
+    };
+
+    Ok(Page { data: rows, total, page, page_size, next_cursor })
 }
 
 #[tauri::command]
 pub async fn get_items_by_purchase(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchaseItem>, String> {
     let rows: Vec<PurchaseItem> = sqlx::query_as::<_, PurchaseItem>(
-        "SELECT id, purchase_id, part_no, description, qty, unit, price, amount FROM purchase_items WHERE purchase_id = ?1 ORDER BY id ASC",
+        "SELECT id, purchase_id, part_no, description, qty, unit, price, amount, po_line_id, grn_line_id
+         FROM purchase_items WHERE purchase_id = ?1 ORDER BY id ASC",
     )
     .bind(purchase_id)
-    .fetch_all(&db.0)
+    .fetch_all(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
     Ok(rows)
 }
 
+/// What existing purchase lines carry. Saving a line again with one of these values is
+/// allowed even after the master has dropped or deactivated it.
+#[derive(Default)]
+struct KeptLines {
+    part_nos: std::collections::HashSet<String>,
+    units: std::collections::HashSet<String>,
+}
+
+/// The values on the lines of a purchase, or on just one of them when `item_id` is given.
+async fn kept_lines(conn: &mut SqliteConnection, purchase_id: i64, item_id: Option<i64>) -> Result<KeptLines, String> {
+    let rows: Vec<(Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT part_no, TRIM(unit) FROM purchase_items WHERE purchase_id = ?1 AND (?2 IS NULL OR id = ?2)",
+    )
+    .bind(purchase_id)
+    .bind(item_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut kept = KeptLines::default();
+    for (part_no, unit) in rows {
+        kept.part_nos.extend(part_no);
+        kept.units.extend(unit);
+    }
+    Ok(kept)
+}
+
+/// Look up `part_no` in the item master and return it spelled the way the master has it.
+/// Blank part numbers are stored as NULL; unknown or inactive ones are refused unless a
+/// line already carries them (`kept`).
+async fn resolve_part_no(
+    conn: &mut SqliteConnection,
+    part_no: Option<String>,
+    kept: &KeptLines,
+) -> Result<Option<String>, String> {
+    let part_no = match part_no.as_deref().map(str::trim) {
+        Some(p) if !p.is_empty() => p.to_string(),
+        _ => return Ok(None),
+    };
+    let found: Option<(String, i64)> = sqlx::query_as("SELECT part_no, active FROM items WHERE part_no = ?1")
+        .bind(&part_no)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some((canonical, 1)) = found {
+        return Ok(Some(canonical));
+    }
+    if let Some(k) = kept.part_nos.iter().find(|k| k.eq_ignore_ascii_case(&part_no)) {
+        return Ok(Some(k.clone()));
+    }
+    match found {
+        Some(_) => Err(format!("Part number '{}' is inactive in the item master", part_no)),
+        None => Err(format!("Part number '{}' is not in the item master", part_no)),
+    }
+}
+
+/// Check the PO and GRN lines a purchase line bills against the entry's supplier; a linked
+/// line without a part number takes the linked one.
+async fn resolve_links(conn: &mut SqliteConnection, purchase_id: i64, it: &mut PurchaseItemPayload) -> Result<(), String> {
+    if it.po_line_id.is_none() && it.grn_line_id.is_none() {
+        return Ok(());
+    }
+    let (supplier_id,): (i64,) = sqlx::query_as("SELECT supplier_id FROM purchase_entries WHERE id = ?1")
+        .bind(purchase_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let link = matching::resolve(conn, supplier_id, it.part_no.as_deref(), it.po_line_id, it.grn_line_id).await?;
+    it.po_line_id = link.po_line_id;
+    it.grn_line_id = link.grn_line_id;
+    if it.part_no.as_deref().map_or(true, |p| p.trim().is_empty()) {
+        it.part_no = link.part_no;
+    }
+    Ok(())
+}
+
+/// Insert purchase lines after checking their part numbers against the item master,
+/// their units against the units master and their PO and GRN links. Values in `kept`
+/// came from lines being replaced and pass as they are.
+async fn insert_items(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    items: Vec<PurchaseItemPayload>,
+    kept: &KeptLines,
+) -> Result<(), String> {
+    let mut checked = Vec::with_capacity(items.len());
+    for mut it in items {
+        resolve_links(conn, purchase_id, &mut it).await?;
+        it.part_no = resolve_part_no(conn, it.part_no, kept).await?;
+        it.unit = uom::resolve_kept(conn, it.unit, &kept.units).await?;
+        checked.push(it);
+    }
+    insert_item_rows(conn, purchase_id, checked).await
+}
+
+/// Insert purchase lines as given. Only for restoring lines that were valid when saved;
+/// links to PO or GRN lines deleted since are dropped.
+async fn insert_item_rows(conn: &mut SqliteConnection, purchase_id: i64, items: Vec<PurchaseItemPayload>) -> Result<(), String> {
+    for it in items {
+        let amount = it.amount.unwrap_or(it.qty * it.price);
+        let res = sqlx::query(
+            "INSERT INTO purchase_items (purchase_id, part_no, description, qty, unit, price, amount, po_line_id, grn_line_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7,
+                     (SELECT id FROM purchase_order_lines WHERE id = ?8),
+                     (SELECT id FROM goods_receipt_lines WHERE id = ?9))",
+        )
+        .bind(purchase_id)
+        .bind(it.part_no)
+        .bind(it.description)
+        .bind(it.qty)
+        .bind(it.unit)
+        .bind(it.price)
+        .bind(amount)
+        .bind(it.po_line_id)
+        .bind(it.grn_line_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Some(splits) = it.allocations.filter(|a| !a.is_empty()) {
+            dimensions::replace(conn, purchase_id, Some(res.last_insert_rowid()), &splits).await?;
+        }
+    }
+    Ok(())
+}
+
+/// New purchases need a live, active supplier.
+async fn check_supplier_active(conn: &mut SqliteConnection, supplier_id: i64) -> Result<(), String> {
+    let found: Option<(String, i64, bool)> = sqlx::query_as(
+        "SELECT name, active, deleted_at IS NOT NULL FROM suppliers WHERE id = ?1",
+    )
+    .bind(supplier_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    match found {
+        Some((_, 1, false)) => Ok(()),
+        Some((name, _, true)) => Err(format!("Supplier '{}' is deleted", name)),
+        Some((name, _, false)) => Err(format!("Supplier '{}' is inactive", name)),
+        None => Err("Supplier not found".to_string()),
+    }
+}
+
+/// Add a purchase. Likely duplicates of an existing bill from the same party are refused
+/// unless `allow_duplicate` is set (see `check_purchase_duplicates`).
 #[tauri::command]
-pub async fn add_purchase(db: State<'_, Db>, payload: PurchaseCreate) -> Result<PurchaseEntry, String> {
-    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+pub async fn add_purchase(
+    db: State<'_, Db>,
+    payload: PurchaseCreate,
+    user_id: Option<i64>,
+    allow_duplicate: Option<bool>,
+) -> Result<PurchaseEntry, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    check_supplier_active(&mut tx, payload.supplier_id).await?;
+    if !allow_duplicate.unwrap_or(false) {
+        let suspects = find_duplicate_invoices(
+            &mut tx,
+            payload.supplier_id,
+            &payload.invoice_no,
+            &payload.date,
+            payload.invoice_value,
+            None,
+        )
+        .await?;
+        if !suspects.is_empty() {
+            let list: Vec<String> = suspects
+                .iter()
+                .map(|d| format!("{} from {} dated {} for {:.2}", d.invoice_no, d.supplier_name, d.date, d.invoice_value))
+                .collect();
+            return Err(format!("Possible duplicate of {}. Confirm to save anyway.", list.join("; ")));
+        }
+    }
+
+    let status = workflow::initial(&payload.status)?;
+    let custom = custom_fields::check(&mut tx, "purchase", payload.custom_fields, None).await?;
 
     let rec: (i64,) = sqlx::query_as(
-        "INSERT INTO purchase_entries (supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status)
-         VALUES (?1, ?2, ?3, COALESCE(?4, datetime('now')), ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        "INSERT INTO purchase_entries (supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status, custom_fields)
+         VALUES (?1, ?2, ?3, COALESCE(?4, datetime('now')), ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
          RETURNING id",
     )
     .bind(payload.supplier_id)
@@ -180,47 +655,70 @@ pub async fn add_purchase(db: State<'_, Db>, payload: PurchaseCreate) -> Result<
     .bind(payload.invoice_value)
     .bind(payload.tds_value)
     .bind(&payload.narration)
-    .bind(&payload.status)
+    .bind(&status)
+    .bind(Json(custom))
     .fetch_one(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
     let pid = rec.0;
-    for it in payload.items {
-        let amount = it.amount.unwrap_or(it.qty * it.price);
-        sqlx::query(
-            "INSERT INTO purchase_items (purchase_id, part_no, description, qty, unit, price, amount)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        )
-        .bind(pid)
-        .bind(it.part_no)
-        .bind(it.description)
-        .bind(it.qty)
-        .bind(it.unit)
-        .bind(it.price)
-        .bind(amount)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
+    insert_items(&mut tx, pid, payload.items, &KeptLines::default()).await?;
+    if !payload.allocations.is_empty() {
+        dimensions::replace(&mut tx, pid, None, &payload.allocations).await?;
     }
+    workflow::log(&mut tx, pid, None, &status, None, user_id).await?;
+    revisions::record(&mut tx, pid, "create", None, user_id).await?;
+    pricing::record(&mut tx, pid).await?;
+    matching::record(&mut tx, pid).await?;
 
     tx.commit().await.map_err(|e| e.to_string())?;
 
-    let entry = sqlx::query_as::<_, PurchaseEntry>(
-        "SELECT id, supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status FROM purchase_entries WHERE id = ?1",
-    )
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
     .bind(pid)
-    .fetch_one(&db.0)
+    .fetch_one(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
     Ok(entry)
 }
 
 #[tauri::command]
-pub async fn update_purchase(db: State<'_, Db>, payload: PurchaseUpdate) -> Result<PurchaseEntry, String> {
-    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+pub async fn update_purchase(db: State<'_, Db>, payload: PurchaseUpdate, user_id: Option<i64>) -> Result<PurchaseEntry, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    workflow::check_editable(&mut tx, payload.id).await?;
+    let before = approvals::fingerprint(&mut tx, payload.id).await?;
+    if let (Some(wanted), Some(current)) = (payload.status.as_deref(), workflow::current(&mut tx, payload.id).await?) {
+        if workflow::status(wanted)? != current {
+            return Err("Change the status of a purchase through its workflow transitions".to_string());
+        }
+    }
+    if let Some(supplier_id) = payload.supplier_id {
+        // Entries may stay with a supplier that was deactivated later, but not move to one
+        let current: Option<(i64,)> = sqlx::query_as("SELECT supplier_id FROM purchase_entries WHERE id = ?1")
+            .bind(payload.id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        if current.is_some_and(|(c,)| c != supplier_id) {
+            check_supplier_active(&mut tx, supplier_id).await?;
+        }
+    }
+    let custom = match payload.custom_fields {
+        Some(given) => {
+            let stored: Option<(Json<CustomValues>,)> =
+                sqlx::query_as("SELECT custom_fields FROM purchase_entries WHERE id = ?1")
+                    .bind(payload.id)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let existing = stored.map(|(c,)| c.0);
+            Some(Json(custom_fields::check(&mut tx, "purchase", Some(given), existing).await?))
+        }
+        None => None,
+    };
 
-    sqlx::query(
+    let res = sqlx::query(
         "UPDATE purchase_entries SET
             supplier_id = COALESCE(?2, supplier_id),
             invoice_no = COALESCE(?3, invoice_no),
@@ -234,8 +732,8 @@ pub async fn update_purchase(db: State<'_, Db>, payload: PurchaseUpdate) -> Resu
             invoice_value = COALESCE(?11, invoice_value),
             tds_value = COALESCE(?12, tds_value),
             narration = COALESCE(?13, narration),
-            status = COALESCE(?14, status)
-         WHERE id = ?1",
+            custom_fields = COALESCE(?14, custom_fields)
+         WHERE id = ?1 AND deleted_at IS NULL",
     )
     .bind(payload.id)
     .bind(payload.supplier_id)
@@ -250,81 +748,98 @@ pub async fn update_purchase(db: State<'_, Db>, payload: PurchaseUpdate) -> Resu
     .bind(payload.invoice_value)
     .bind(payload.tds_value)
     .bind(payload.narration)
-    .bind(payload.status)
+    .bind(custom)
     .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Purchase not found or deleted".to_string());
+    }
 
     if let Some(items) = payload.items {
         // Replace strategy: delete existing and insert provided items
+        let kept = kept_lines(&mut tx, payload.id, None).await?;
         sqlx::query("DELETE FROM purchase_items WHERE purchase_id = ?1")
             .bind(payload.id)
             .execute(&mut *tx)
             .await
             .map_err(|e| e.to_string())?;
-        for it in items {
-            let amount = it.amount.unwrap_or(it.qty * it.price);
-            sqlx::query(
-                "INSERT INTO purchase_items (purchase_id, part_no, description, qty, unit, price, amount)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            )
-            .bind(payload.id)
-            .bind(it.part_no)
-            .bind(it.description)
-            .bind(it.qty)
-            .bind(it.unit)
-            .bind(it.price)
-            .bind(amount)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
+        insert_items(&mut tx, payload.id, items, &kept).await?;
+    }
+    if let Some(splits) = payload.allocations {
+        dimensions::replace(&mut tx, payload.id, None, &splits).await?;
     }
+    matching::check_supplier(&mut tx, payload.id).await?;
+    payments::check_supplier(&mut tx, payload.id).await?;
+    payments::check_overpaid(&mut tx, payload.id).await?;
+    approvals::reopen_if_changed(&mut tx, payload.id, before, user_id).await?;
+    revisions::record(&mut tx, payload.id, "update", None, user_id).await?;
+    pricing::record(&mut tx, payload.id).await?;
+    matching::record(&mut tx, payload.id).await?;
 
     tx.commit().await.map_err(|e| e.to_string())?;
 
-    let entry = sqlx::query_as::<_, PurchaseEntry>(
-        "SELECT id, supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status FROM purchase_entries WHERE id = ?1",
-    )
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
     .bind(payload.id)
-    .fetch_one(&db.0)
+    .fetch_one(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
     Ok(entry)
 }
 
+/// Move a purchase to the recycle bin. Items are kept so the entry can be restored intact.
+/// Posted and cancelled entries stay put.
 #[tauri::command]
-pub async fn delete_purchase(db: State<'_, Db>, id: i64) -> Result<bool, String> {
-    let res = sqlx::query("DELETE FROM purchase_entries WHERE id = ?1")
-        .bind(id)
-        .execute(&db.0)
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn delete_purchase(db: State<'_, Db>, id: i64, user_id: Option<i64>) -> Result<bool, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    workflow::check_editable(&mut conn, id).await?;
+    payments::check_unpaid(&mut conn, id).await?;
+    let res = sqlx::query(
+        "UPDATE purchase_entries SET deleted_at = datetime('now'), deleted_by = ?2
+         WHERE id = ?1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
     Ok(res.rows_affected() > 0)
 }
 
 #[tauri::command]
-pub async fn add_item(db: State<'_, Db>, purchase_id: i64, item: PurchaseItemPayload) -> Result<bool, String> {
-    let amount = item.amount.unwrap_or(item.qty * item.price);
-    sqlx::query(
-        "INSERT INTO purchase_items (purchase_id, part_no, description, qty, unit, price, amount)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-    )
-    .bind(purchase_id)
-    .bind(item.part_no)
-    .bind(item.description)
-    .bind(item.qty)
-    .bind(item.unit)
-    .bind(item.price)
-    .bind(amount)
-    .execute(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
+pub async fn add_item(db: State<'_, Db>, purchase_id: i64, item: PurchaseItemPayload, user_id: Option<i64>) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    workflow::check_editable(&mut tx, purchase_id).await?;
+    let before = approvals::fingerprint(&mut tx, purchase_id).await?;
+    insert_items(&mut tx, purchase_id, vec![item], &KeptLines::default()).await?;
+    approvals::reopen_if_changed(&mut tx, purchase_id, before, user_id).await?;
+    revisions::record(&mut tx, purchase_id, "items", None, user_id).await?;
+    pricing::record(&mut tx, purchase_id).await?;
+    matching::record(&mut tx, purchase_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
     Ok(true)
 }
 
 #[tauri::command]
-pub async fn update_item(db: State<'_, Db>, id: i64, item: PurchaseItemPayload) -> Result<bool, String> {
+pub async fn update_item(db: State<'_, Db>, id: i64, item: PurchaseItemPayload, user_id: Option<i64>) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+
+    let (purchase_id,): (i64,) = sqlx::query_as("SELECT purchase_id FROM purchase_items WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+    workflow::check_editable(&mut tx, purchase_id).await?;
+    let before = approvals::fingerprint(&mut tx, purchase_id).await?;
+    let mut item = item;
+    resolve_links(&mut tx, purchase_id, &mut item).await?;
+    let kept = kept_lines(&mut tx, purchase_id, Some(id)).await?;
+    let part_no = resolve_part_no(&mut tx, item.part_no, &kept).await?;
+    let unit = uom::resolve_kept(&mut tx, item.unit, &kept.units).await?;
+
     sqlx::query(
         "UPDATE purchase_items SET
             part_no = COALESCE(?2, part_no),
@@ -332,286 +847,5128 @@ pub async fn update_item(db: State<'_, Db>, id: i64, item: PurchaseItemPayload)
             qty = COALESCE(?4, qty),
             unit = COALESCE(?5, unit),
             price = COALESCE(?6, price),
-            amount = COALESCE(?7, amount)
+            amount = COALESCE(?7, amount),
+            po_line_id = COALESCE(?8, po_line_id),
+            grn_line_id = COALESCE(?9, grn_line_id)
          WHERE id = ?1",
     )
     .bind(id)
-    .bind(item.part_no)
+    .bind(part_no)
     .bind(Some(item.description))
     .bind(Some(item.qty))
-    .bind(item.unit)
+    .bind(unit)
     .bind(Some(item.price))
     .bind(item.amount)
-    .execute(&db.0)
+    .bind(item.po_line_id)
+    .bind(item.grn_line_id)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    if let Some(splits) = item.allocations {
+        dimensions::replace(&mut tx, purchase_id, Some(id), &splits).await?;
+    }
+    approvals::reopen_if_changed(&mut tx, purchase_id, before, user_id).await?;
+
+    revisions::record(&mut tx, purchase_id, "items", None, user_id).await?;
+    pricing::record(&mut tx, purchase_id).await?;
+    matching::record(&mut tx, purchase_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
     Ok(true)
 }
 
-#[tauri::command]
-pub async fn add_supplier(db: State<'_, Db>, payload: SupplierCreate) -> Result<Supplier, String> {
-    let tds_flag = if payload.tds_flag { 1_i64 } else { 0_i64 };
+struct SupplierMasterFields {
+    pan: Option<String>,
+    msme_no: Option<String>,
+    msme_category: Option<String>,
+}
 
-    let rec: (i64,) = sqlx::query_as(
-        "INSERT INTO suppliers (name, gst_no, state_code, tds_flag, tds_rate, contact, email)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-         RETURNING id",
+/// Validate and normalise the tax, MSME and payment fields shared by create and update.
+/// `gst_no` is the GSTIN the PAN will sit next to once the write lands.
+fn check_supplier_master(
+    pan: Option<String>,
+    gst_no: Option<&str>,
+    msme_no: Option<String>,
+    msme_category: Option<String>,
+    payment_terms_days: Option<i64>,
+) -> Result<SupplierMasterFields, String> {
+    let pan = validation::optional(pan, validation::pan)?;
+    if let (Some(p), Some(g)) = (&pan, gst_no) {
+        validation::pan_matches_gstin(p, g)?;
+    }
+    let msme_no = validation::optional(msme_no, validation::udyam)?;
+    let msme_category = validation::optional(msme_category, validation::msme_category)?;
+    if payment_terms_days.is_some_and(|d| d < 0) {
+        return Err("Payment terms cannot be negative".to_string());
+    }
+    Ok(SupplierMasterFields { pan, msme_no, msme_category })
+}
+
+fn supplier_child_error(e: sqlx::Error) -> String {
+    match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => {
+            "This bank account is already on the supplier".to_string()
+        }
+        sqlx::Error::Database(d) if d.message().contains("FOREIGN KEY") => "Supplier not found".to_string(),
+        _ => e.to_string(),
+    }
+}
+
+/// Insert a supplier and its addresses, bank accounts and contacts.
+async fn insert_supplier(conn: &mut SqliteConnection, payload: SupplierCreate) -> Result<i64, String> {
+    let name = required(&payload.name, "Supplier name")?;
+    let gst_no = validation::clean(payload.gst_no).map(|g| g.to_uppercase());
+    let checked = check_supplier_master(
+        payload.pan,
+        gst_no.as_deref(),
+        payload.msme_no,
+        payload.msme_category,
+        payload.payment_terms_days,
+    )?;
+    let contact = validation::clean(payload.contact);
+    let email = validation::clean(payload.email);
+    let custom = custom_fields::check(conn, "supplier", payload.custom_fields, None).await?;
+
+    let res = sqlx::query(
+        "INSERT INTO suppliers (name, gst_no, state_code, tds_flag, tds_rate, contact, email,
+                                pan, msme_no, msme_category, payment_terms_days, payment_terms, custom_fields)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
     )
-    .bind(&payload.name)
-    .bind(&payload.gst_no)
-    .bind(&payload.state_code)
-    .bind(tds_flag)
-    .bind(&payload.tds_rate)
-    .bind(&payload.contact)
-    .bind(&payload.email)
-    .fetch_one(&db.0)
+    .bind(&name)
+    .bind(&gst_no)
+    .bind(validation::clean(payload.state_code))
+    .bind(payload.tds_flag as i64)
+    .bind(payload.tds_rate)
+    .bind(&contact)
+    .bind(&email)
+    .bind(checked.pan)
+    .bind(checked.msme_no)
+    .bind(checked.msme_category)
+    .bind(payload.payment_terms_days)
+    .bind(validation::clean(payload.payment_terms))
+    .bind(Json(custom))
+    .execute(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
+    let id = res.last_insert_rowid();
 
-    let supplier = sqlx::query_as::<_, Supplier>(
-        "SELECT id, name, gst_no, state_code, tds_flag, tds_rate, contact, email FROM suppliers WHERE id = ?1",
-    )
-    .bind(rec.0)
-    .fetch_one(&db.0)
+    for address in payload.addresses {
+        insert_supplier_address(conn, id, address).await?;
+    }
+    for account in payload.bank_accounts {
+        insert_supplier_bank_account(conn, id, account).await?;
+    }
+    let mut contacts = payload.contacts;
+    // The single contact field doubles as the primary contact person
+    if contacts.is_empty() {
+        if let Some(name) = contact {
+            contacts.push(SupplierContactCreate { name, role: None, phone: None, email, is_primary: true });
+        }
+    }
+    for c in contacts {
+        insert_supplier_contact(conn, id, c).await?;
+    }
+    Ok(id)
+}
+
+async fn fetch_supplier(db: &Db, id: i64) -> Result<Supplier, String> {
+    sqlx::query_as::<_, Supplier>(&format!(
+        "SELECT {SUPPLIER_COLUMNS} FROM suppliers WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_optional(&db.pool())
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Supplier not found".to_string())
+}
 
-    Ok(supplier)
+/// Add a supplier. Likely duplicates of an active supplier are refused unless
+/// `allow_duplicate` is set (see `check_supplier_duplicates`).
+#[tauri::command]
+pub async fn add_supplier(
+    db: State<'_, Db>,
+    payload: SupplierCreate,
+    allow_duplicate: Option<bool>,
+) -> Result<Supplier, String> {
+    if !allow_duplicate.unwrap_or(false) {
+        let existing = active_suppliers(&db, None).await?;
+        let probe = dedupe::Probe {
+            name: &payload.name,
+            gst_no: payload.gst_no.as_deref(),
+            pan: payload.pan.as_deref(),
+        };
+        let found = dedupe::matches(&probe, existing);
+        if !found.is_empty() {
+            let list: Vec<String> = found.iter().map(dedupe::describe).collect();
+            return Err(format!("Possible duplicate of {}. Confirm to add it anyway.", list.join("; ")));
+        }
+    }
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let id = insert_supplier(&mut tx, payload).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier(&db, id).await
 }
 
 #[tauri::command]
 pub async fn update_supplier(db: State<'_, Db>, payload: SupplierUpdate) -> Result<Supplier, String> {
-    // Build dynamic update for optional fields
-    // For simplicity, we coalesce to existing values
-    sqlx::query(
+    let current = fetch_supplier(&db, payload.id).await?;
+    let name = payload.name.as_deref().map(|n| required(n, "Supplier name")).transpose()?;
+    // A blank GSTIN clears it, as before
+    let gst_no = payload.gst_no.as_ref().map(|g| validation::clean(Some(g.to_uppercase())));
+    // Check the PAN against whichever GSTIN the supplier ends up with
+    let pan = payload.pan.clone().or_else(|| if gst_no.is_some() { current.pan.clone() } else { None });
+    let gst_after = match &gst_no {
+        Some(given) => given.as_deref(),
+        None => current.gst_no.as_deref().filter(|g| !g.is_empty()),
+    };
+    let checked = check_supplier_master(
+        pan,
+        gst_after,
+        payload.msme_no.clone(),
+        payload.msme_category.clone(),
+        payload.payment_terms_days.flatten(),
+    )?;
+    let custom = match payload.custom_fields {
+        Some(given) => {
+            let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+            Some(Json(custom_fields::check(&mut conn, "supplier", Some(given), Some(current.custom_fields.0)).await?))
+        }
+        None => None,
+    };
+
+    // Fields given as blank (or null, for numbers) are cleared; the rest keep their values
+    let res = sqlx::query(
+        "UPDATE suppliers SET
+            name = COALESCE(?2, name),
+            gst_no = CASE WHEN ?3 THEN ?4 ELSE gst_no END,
+            state_code = CASE WHEN ?5 THEN ?6 ELSE state_code END,
+            tds_flag = COALESCE(?7, tds_flag),
+            tds_rate = CASE WHEN ?8 THEN ?9 ELSE tds_rate END,
+            contact = CASE WHEN ?10 THEN ?11 ELSE contact END,
+            email = CASE WHEN ?12 THEN ?13 ELSE email END,
+            pan = CASE WHEN ?14 THEN ?15 ELSE pan END,
+            msme_no = CASE WHEN ?16 THEN ?17 ELSE msme_no END,
+            msme_category = CASE WHEN ?18 THEN ?19 ELSE msme_category END,
+            payment_terms_days = CASE WHEN ?20 THEN ?21 ELSE payment_terms_days END,
+            payment_terms = CASE WHEN ?22 THEN ?23 ELSE payment_terms END,
+            custom_fields = COALESCE(?24, custom_fields)
+         WHERE id = ?1 AND deleted_at IS NULL",
+    )
+    .bind(payload.id)
+    .bind(name)
+    .bind(gst_no.is_some())
+    .bind(gst_no.flatten())
+    .bind(payload.state_code.is_some())
+    .bind(validation::clean(payload.state_code))
+    .bind(payload.tds_flag.map(|b| if b { 1_i64 } else { 0_i64 }))
+    .bind(payload.tds_rate.is_some())
+    .bind(payload.tds_rate.flatten())
+    .bind(payload.contact.is_some())
+    .bind(validation::clean(payload.contact))
+    .bind(payload.email.is_some())
+    .bind(validation::clean(payload.email))
+    .bind(payload.pan.is_some())
+    .bind(checked.pan)
+    .bind(payload.msme_no.is_some())
+    .bind(checked.msme_no)
+    .bind(payload.msme_category.is_some())
+    .bind(checked.msme_category)
+    .bind(payload.payment_terms_days.is_some())
+    .bind(payload.payment_terms_days.flatten())
+    .bind(payload.payment_terms.is_some())
+    .bind(validation::clean(payload.payment_terms))
+    .bind(custom)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Supplier not found or deleted".to_string());
+    }
+
+    fetch_supplier(&db, payload.id).await
+}
+
+async fn active_suppliers(db: &Db, exclude_id: Option<i64>) -> Result<Vec<Supplier>, String> {
+    sqlx::query_as::<_, Supplier>(&format!(
+        "SELECT {SUPPLIER_COLUMNS} FROM suppliers
+         WHERE deleted_at IS NULL AND (?1 IS NULL OR id <> ?1)
+         ORDER BY name",
+    ))
+    .bind(exclude_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Active suppliers that a new or edited supplier would likely duplicate (same GSTIN,
+/// same PAN, or a near-identical name once legal suffixes are ignored).
+#[tauri::command]
+pub async fn check_supplier_duplicates(
+    db: State<'_, Db>,
+    name: String,
+    gst_no: Option<String>,
+    pan: Option<String>,
+    exclude_id: Option<i64>,
+) -> Result<Vec<SupplierMatch>, String> {
+    let existing = active_suppliers(&db, exclude_id).await?;
+    let probe = dedupe::Probe { name: &name, gst_no: gst_no.as_deref(), pan: pan.as_deref() };
+    Ok(dedupe::matches(&probe, existing))
+}
+
+/// Every pair of active suppliers that look like the same party, best match first.
+#[tauri::command]
+pub async fn find_duplicate_suppliers(
+    db: State<'_, Db>,
+    min_score: Option<f64>,
+) -> Result<Vec<SupplierDuplicatePair>, String> {
+    let suppliers = active_suppliers(&db, None).await?;
+    Ok(dedupe::pairs(&suppliers, min_score.unwrap_or(dedupe::NAME_THRESHOLD)))
+}
+
+/// Next free invoice number for a purchase moving to `supplier_id`.
+async fn free_invoice_no(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    invoice_no: &str,
+    merged_id: i64,
+) -> Result<String, String> {
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 {
+            format!("{} (#{})", invoice_no, merged_id)
+        } else {
+            format!("{} (#{}-{})", invoice_no, merged_id, n)
+        };
+        let (taken,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM purchase_entries WHERE supplier_id IN (?1, ?2) AND invoice_no = ?3",
+        )
+        .bind(supplier_id)
+        .bind(merged_id)
+        .bind(&candidate)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        if taken == 0 {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Fold `merged_id` into `survivor`: move its purchases, item defaults, addresses,
+/// bank accounts and contacts, fill the survivor's blank fields and retire it.
+async fn merge_supplier_into(
+    conn: &mut SqliteConnection,
+    survivor: &Supplier,
+    merged_id: i64,
+    user_id: Option<i64>,
+) -> Result<i64, String> {
+    let merged = sqlx::query_as::<_, Supplier>(&format!(
+        "SELECT {SUPPLIER_COLUMNS} FROM suppliers WHERE id = ?1",
+    ))
+    .bind(merged_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Supplier {} not found", merged_id))?;
+    if merged.merged_into.is_some() {
+        return Err(format!("Supplier '{}' has already been merged", merged.name));
+    }
+
+    // Invoice numbers are unique per supplier; rename the merged side of any clash
+    let clashes: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT m.id, m.invoice_no FROM purchase_entries m
+         WHERE m.supplier_id = ?2
+           AND EXISTS (SELECT 1 FROM purchase_entries s WHERE s.supplier_id = ?1 AND s.invoice_no = m.invoice_no)
+         ORDER BY m.id",
+    )
+    .bind(survivor.id)
+    .bind(merged_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut renamed = Vec::with_capacity(clashes.len());
+    for (purchase_id, from) in clashes {
+        let to = free_invoice_no(conn, survivor.id, &from, merged_id).await?;
+        sqlx::query("UPDATE purchase_entries SET invoice_no = ?2 WHERE id = ?1")
+            .bind(purchase_id)
+            .bind(&to)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        renamed.push(InvoiceRename { purchase_id, from, to });
+    }
+
+    let moved: Vec<(i64,)> = sqlx::query_as("SELECT id FROM purchase_entries WHERE supplier_id = ?1 ORDER BY id")
+        .bind(merged_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE purchase_entries SET supplier_id = ?1 WHERE supplier_id = ?2")
+        .bind(survivor.id)
+        .bind(merged_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (purchase_id,) in &moved {
+        let mut note = format!("Supplier '{}' merged into '{}'", merged.name, survivor.name);
+        if let Some(r) = renamed.iter().find(|r| r.purchase_id == *purchase_id) {
+            note.push_str(&format!("; invoice renamed from {} to {}", r.from, r.to));
+        }
+        revisions::record(conn, *purchase_id, "merge", Some(note), user_id).await?;
+    }
+
+    let items = sqlx::query("UPDATE items SET supplier_id = ?1 WHERE supplier_id = ?2")
+        .bind(survivor.id)
+        .bind(merged_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let clash: Option<(String,)> = sqlx::query_as(
+        "SELECT n.note_no FROM supplier_notes n
+         WHERE n.supplier_id = ?2
+           AND EXISTS (SELECT 1 FROM supplier_notes o WHERE o.supplier_id = ?1 AND o.kind = n.kind AND o.note_no = n.note_no)
+         LIMIT 1",
+    )
+    .bind(survivor.id)
+    .bind(merged_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some((note_no,)) = clash {
+        return Err(format!("Both suppliers have a note numbered '{}'; renumber one before merging", note_no));
+    }
+    for table in ["approval_rules", "purchase_orders", "goods_receipts", "supplier_payments", "supplier_notes"] {
+        sqlx::query(&format!("UPDATE {table} SET supplier_id = ?1 WHERE supplier_id = ?2"))
+            .bind(survivor.id)
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Child records move over; the survivor's own primary/default entries win
+    let moves = [
+        "UPDATE supplier_addresses SET supplier_id = ?1,
+            is_default = CASE WHEN EXISTS (SELECT 1 FROM supplier_addresses o
+                WHERE o.supplier_id = ?1 AND o.kind = supplier_addresses.kind AND o.is_default = 1)
+            THEN 0 ELSE is_default END
+         WHERE supplier_id = ?2",
+        "UPDATE OR IGNORE supplier_bank_accounts SET supplier_id = ?1,
+            is_primary = CASE WHEN EXISTS (SELECT 1 FROM supplier_bank_accounts o
+                WHERE o.supplier_id = ?1 AND o.is_primary = 1)
+            THEN 0 ELSE is_primary END
+         WHERE supplier_id = ?2",
+        // accounts the survivor already has
+        "DELETE FROM supplier_bank_accounts WHERE supplier_id = ?2",
+        "UPDATE supplier_contacts SET supplier_id = ?1,
+            is_primary = CASE WHEN EXISTS (SELECT 1 FROM supplier_contacts o
+                WHERE o.supplier_id = ?1 AND o.is_primary = 1)
+            THEN 0 ELSE is_primary END
+         WHERE supplier_id = ?2",
         "UPDATE suppliers SET
+            gst_no = COALESCE(NULLIF(suppliers.gst_no, ''), m.gst_no),
+            state_code = COALESCE(NULLIF(suppliers.state_code, ''), m.state_code),
+            tds_rate = COALESCE(suppliers.tds_rate, m.tds_rate),
+            contact = COALESCE(NULLIF(suppliers.contact, ''), m.contact),
+            email = COALESCE(NULLIF(suppliers.email, ''), m.email),
+            pan = COALESCE(suppliers.pan, m.pan),
+            msme_no = COALESCE(suppliers.msme_no, m.msme_no),
+            msme_category = COALESCE(suppliers.msme_category, m.msme_category),
+            payment_terms_days = COALESCE(suppliers.payment_terms_days, m.payment_terms_days),
+            payment_terms = COALESCE(suppliers.payment_terms, m.payment_terms),
+            -- custom values the survivor lacks come from the merged supplier
+            custom_fields = json_patch(m.custom_fields, suppliers.custom_fields)
+         FROM suppliers m
+         WHERE suppliers.id = ?1 AND m.id = ?2",
+        // opening balances of the same year add up
+        "UPDATE supplier_opening_balances SET
+            amount = amount + (SELECT m.amount FROM supplier_opening_balances m
+                               WHERE m.supplier_id = ?2 AND m.fy_start = supplier_opening_balances.fy_start),
+            updated_at = datetime('now')
+         WHERE supplier_id = ?1 AND fy_start IN (SELECT fy_start FROM supplier_opening_balances WHERE supplier_id = ?2)",
+        "DELETE FROM supplier_opening_balances
+         WHERE supplier_id = ?2 AND fy_start IN (SELECT fy_start FROM supplier_opening_balances WHERE supplier_id = ?1)",
+        "UPDATE supplier_opening_balances SET supplier_id = ?1 WHERE supplier_id = ?2",
+        // earlier merges into the merged supplier now point at the survivor
+        "UPDATE suppliers SET merged_into = ?1 WHERE merged_into = ?2",
+    ];
+    for sql in moves {
+        sqlx::query(sql)
+            .bind(survivor.id)
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query(
+        "UPDATE suppliers SET merged_into = ?1,
+            deleted_at = COALESCE(deleted_at, datetime('now')),
+            deleted_by = COALESCE(deleted_by, ?3)
+         WHERE id = ?2",
+    )
+    .bind(survivor.id)
+    .bind(merged_id)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let renamed_json = serde_json::to_string(&renamed).map_err(|e| e.to_string())?;
+    let res = sqlx::query(
+        "INSERT INTO supplier_merges (survivor_id, merged_id, merged_name, merged_gst_no,
+                                      purchases_moved, items_moved, renamed_invoices, merged_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(survivor.id)
+    .bind(merged_id)
+    .bind(&merged.name)
+    .bind(&merged.gst_no)
+    .bind(moved.len() as i64)
+    .bind(items.rows_affected() as i64)
+    .bind(renamed_json)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(res.last_insert_rowid())
+}
+
+/// Merge duplicate suppliers into `survivor_id` in one transaction. Purchases whose
+/// invoice number the survivor already uses get a "(#<merged id>)" suffix.
+#[tauri::command]
+pub async fn merge_suppliers(
+    db: State<'_, Db>,
+    survivor_id: i64,
+    merged_ids: Vec<i64>,
+    user_id: Option<i64>,
+) -> Result<SupplierMergeResult, String> {
+    let mut ids: Vec<i64> = Vec::with_capacity(merged_ids.len());
+    for id in merged_ids {
+        if id == survivor_id {
+            return Err("A supplier cannot be merged into itself".to_string());
+        }
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() {
+        return Err("Choose at least one supplier to merge".to_string());
+    }
+    let survivor = fetch_supplier(&db, survivor_id).await?;
+    if survivor.deleted_at.is_some() {
+        return Err(format!("Supplier '{}' is deleted; restore it before merging into it", survivor.name));
+    }
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let mut merge_ids = Vec::with_capacity(ids.len());
+    for id in ids {
+        merge_ids.push(merge_supplier_into(&mut tx, &survivor, id, user_id).await?);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let mut merges = Vec::with_capacity(merge_ids.len());
+    for id in merge_ids {
+        let row = sqlx::query_as::<_, SupplierMergeRow>(&format!(
+            "SELECT {SUPPLIER_MERGE_COLUMNS} FROM supplier_merges WHERE id = ?1",
+        ))
+        .bind(id)
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+        merges.push(row.try_into()?);
+    }
+    Ok(SupplierMergeResult { survivor: fetch_supplier(&db, survivor_id).await?, merges })
+}
+
+const SUPPLIER_MERGE_COLUMNS: &str =
+    "id, survivor_id, merged_id, merged_name, merged_gst_no, purchases_moved, items_moved, renamed_invoices, merged_by, merged_at";
+
+#[derive(sqlx::FromRow)]
+struct SupplierMergeRow {
+    id: i64,
+    survivor_id: i64,
+    merged_id: i64,
+    merged_name: String,
+    merged_gst_no: Option<String>,
+    purchases_moved: i64,
+    items_moved: i64,
+    renamed_invoices: String,
+    merged_by: Option<i64>,
+    merged_at: String,
+}
+
+impl TryFrom<SupplierMergeRow> for SupplierMerge {
+    type Error = String;
+
+    fn try_from(r: SupplierMergeRow) -> Result<Self, String> {
+        let renamed_invoices = serde_json::from_str(&r.renamed_invoices)
+            .map_err(|e| format!("Merge {} has unreadable invoice renames: {}", r.id, e))?;
+        Ok(SupplierMerge {
+            id: r.id,
+            survivor_id: r.survivor_id,
+            merged_id: r.merged_id,
+            merged_name: r.merged_name,
+            merged_gst_no: r.merged_gst_no,
+            purchases_moved: r.purchases_moved,
+            items_moved: r.items_moved,
+            renamed_invoices,
+            merged_by: r.merged_by,
+            merged_at: r.merged_at,
+        })
+    }
+}
+
+/// Merge history, newest first; optionally only merges into `supplier_id`.
+#[tauri::command]
+pub async fn get_supplier_merges(db: State<'_, Db>, supplier_id: Option<i64>) -> Result<Vec<SupplierMerge>, String> {
+    let rows = sqlx::query_as::<_, SupplierMergeRow>(&format!(
+        "SELECT {SUPPLIER_MERGE_COLUMNS} FROM supplier_merges
+         WHERE (?1 IS NULL OR survivor_id = ?1)
+         ORDER BY merged_at DESC, id DESC",
+    ))
+    .bind(supplier_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    rows.into_iter().map(SupplierMerge::try_from).collect()
+}
+
+// Supplier addresses, bank accounts and contacts
+
+const SUPPLIER_ADDRESS_COLUMNS: &str =
+    "id, supplier_id, kind, line1, line2, city, state_code, pincode, country, is_default";
+const SUPPLIER_BANK_COLUMNS: &str =
+    "id, supplier_id, account_name, account_no, ifsc, bank_name, branch, verification_status, verified_at, is_primary";
+const SUPPLIER_CONTACT_COLUMNS: &str = "id, supplier_id, name, role, phone, email, is_primary";
+
+/// Keep at most one flagged row per supplier (per address kind for addresses).
+async fn clear_other_flags(
+    conn: &mut SqliteConnection,
+    table: &str,
+    flag: &str,
+    id: i64,
+) -> Result<(), String> {
+    let same_group = if table == "supplier_addresses" {
+        "o.supplier_id = t.supplier_id AND o.kind = t.kind"
+    } else {
+        "o.supplier_id = t.supplier_id"
+    };
+    sqlx::query(&format!(
+        "UPDATE {table} AS t SET {flag} = 0
+         WHERE t.id <> ?1 AND EXISTS (SELECT 1 FROM {table} o WHERE o.id = ?1 AND {same_group})",
+    ))
+    .bind(id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn insert_supplier_address(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    payload: SupplierAddressCreate,
+) -> Result<i64, String> {
+    let kind = validation::address_kind(&payload.kind)?;
+    let line1 = required(&payload.line1, "Address line 1")?;
+    let pincode = validation::optional(payload.pincode, validation::pincode)?;
+    let res = sqlx::query(
+        "INSERT INTO supplier_addresses (supplier_id, kind, line1, line2, city, state_code, pincode, country, is_default)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, 'India'), ?9)",
+    )
+    .bind(supplier_id)
+    .bind(kind)
+    .bind(line1)
+    .bind(validation::clean(payload.line2))
+    .bind(validation::clean(payload.city))
+    .bind(validation::clean(payload.state_code))
+    .bind(pincode)
+    .bind(validation::clean(payload.country))
+    .bind(payload.is_default as i64)
+    .execute(&mut *conn)
+    .await
+    .map_err(supplier_child_error)?;
+    let id = res.last_insert_rowid();
+    if payload.is_default {
+        clear_other_flags(conn, "supplier_addresses", "is_default", id).await?;
+    }
+    Ok(id)
+}
+
+async fn insert_supplier_bank_account(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    payload: SupplierBankAccountCreate,
+) -> Result<i64, String> {
+    let account_name = required(&payload.account_name, "Account holder name")?;
+    let account_no = validation::account_no(&payload.account_no)?;
+    let ifsc = validation::ifsc(&payload.ifsc)?;
+    let res = sqlx::query(
+        "INSERT INTO supplier_bank_accounts (supplier_id, account_name, account_no, ifsc, bank_name, branch, is_primary)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )
+    .bind(supplier_id)
+    .bind(account_name)
+    .bind(account_no)
+    .bind(ifsc)
+    .bind(validation::clean(payload.bank_name))
+    .bind(validation::clean(payload.branch))
+    .bind(payload.is_primary as i64)
+    .execute(&mut *conn)
+    .await
+    .map_err(supplier_child_error)?;
+    let id = res.last_insert_rowid();
+    if payload.is_primary {
+        clear_other_flags(conn, "supplier_bank_accounts", "is_primary", id).await?;
+    }
+    Ok(id)
+}
+
+async fn insert_supplier_contact(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    payload: SupplierContactCreate,
+) -> Result<i64, String> {
+    let name = required(&payload.name, "Contact name")?;
+    let res = sqlx::query(
+        "INSERT INTO supplier_contacts (supplier_id, name, role, phone, email, is_primary)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(supplier_id)
+    .bind(name)
+    .bind(validation::clean(payload.role))
+    .bind(validation::clean(payload.phone))
+    .bind(validation::clean(payload.email))
+    .bind(payload.is_primary as i64)
+    .execute(&mut *conn)
+    .await
+    .map_err(supplier_child_error)?;
+    let id = res.last_insert_rowid();
+    if payload.is_primary {
+        clear_other_flags(conn, "supplier_contacts", "is_primary", id).await?;
+    }
+    Ok(id)
+}
+
+async fn fetch_supplier_address(db: &Db, id: i64) -> Result<SupplierAddress, String> {
+    sqlx::query_as::<_, SupplierAddress>(&format!(
+        "SELECT {SUPPLIER_ADDRESS_COLUMNS} FROM supplier_addresses WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Address not found".to_string())
+}
+
+async fn fetch_supplier_bank_account(db: &Db, id: i64) -> Result<SupplierBankAccount, String> {
+    sqlx::query_as::<_, SupplierBankAccount>(&format!(
+        "SELECT {SUPPLIER_BANK_COLUMNS} FROM supplier_bank_accounts WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Bank account not found".to_string())
+}
+
+async fn fetch_supplier_contact(db: &Db, id: i64) -> Result<SupplierContact, String> {
+    sqlx::query_as::<_, SupplierContact>(&format!(
+        "SELECT {SUPPLIER_CONTACT_COLUMNS} FROM supplier_contacts WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Contact not found".to_string())
+}
+
+/// The supplier with its addresses, bank accounts and contact persons.
+#[tauri::command]
+pub async fn get_supplier_details(db: State<'_, Db>, id: i64) -> Result<SupplierDetails, String> {
+    let supplier = fetch_supplier(&db, id).await?;
+    let addresses = sqlx::query_as::<_, SupplierAddress>(&format!(
+        "SELECT {SUPPLIER_ADDRESS_COLUMNS} FROM supplier_addresses WHERE supplier_id = ?1
+         ORDER BY kind, is_default DESC, id",
+    ))
+    .bind(id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    let bank_accounts = sqlx::query_as::<_, SupplierBankAccount>(&format!(
+        "SELECT {SUPPLIER_BANK_COLUMNS} FROM supplier_bank_accounts WHERE supplier_id = ?1
+         ORDER BY is_primary DESC, id",
+    ))
+    .bind(id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    let contacts = sqlx::query_as::<_, SupplierContact>(&format!(
+        "SELECT {SUPPLIER_CONTACT_COLUMNS} FROM supplier_contacts WHERE supplier_id = ?1
+         ORDER BY is_primary DESC, name",
+    ))
+    .bind(id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(SupplierDetails { supplier, addresses, bank_accounts, contacts })
+}
+
+#[tauri::command]
+pub async fn add_supplier_address(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    payload: SupplierAddressCreate,
+) -> Result<SupplierAddress, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let id = insert_supplier_address(&mut tx, supplier_id, payload).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_address(&db, id).await
+}
+
+#[tauri::command]
+pub async fn update_supplier_address(
+    db: State<'_, Db>,
+    payload: SupplierAddressUpdate,
+) -> Result<SupplierAddress, String> {
+    let kind = payload.kind.as_deref().map(validation::address_kind).transpose()?;
+    let line1 = payload.line1.as_deref().map(|l| required(l, "Address line 1")).transpose()?;
+    let pincode_given = payload.pincode.is_some();
+    let pincode = validation::optional(payload.pincode, validation::pincode)?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let res = sqlx::query(
+        "UPDATE supplier_addresses SET
+            kind = COALESCE(?2, kind),
+            line1 = COALESCE(?3, line1),
+            line2 = CASE WHEN ?4 THEN ?5 ELSE line2 END,
+            city = CASE WHEN ?6 THEN ?7 ELSE city END,
+            state_code = CASE WHEN ?8 THEN ?9 ELSE state_code END,
+            pincode = CASE WHEN ?10 THEN ?11 ELSE pincode END,
+            country = COALESCE(?12, country),
+            is_default = COALESCE(?13, is_default)
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(kind)
+    .bind(line1)
+    .bind(payload.line2.is_some())
+    .bind(validation::clean(payload.line2))
+    .bind(payload.city.is_some())
+    .bind(validation::clean(payload.city))
+    .bind(payload.state_code.is_some())
+    .bind(validation::clean(payload.state_code))
+    .bind(pincode_given)
+    .bind(pincode)
+    .bind(validation::clean(payload.country))
+    .bind(payload.is_default.map(|b| b as i64))
+    .execute(&mut *tx)
+    .await
+    .map_err(supplier_child_error)?;
+    if res.rows_affected() == 0 {
+        return Err("Address not found".to_string());
+    }
+    if payload.is_default == Some(true) {
+        clear_other_flags(&mut tx, "supplier_addresses", "is_default", payload.id).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_address(&db, payload.id).await
+}
+
+#[tauri::command]
+pub async fn delete_supplier_address(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM supplier_addresses WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn add_supplier_bank_account(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    payload: SupplierBankAccountCreate,
+) -> Result<SupplierBankAccount, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let id = insert_supplier_bank_account(&mut tx, supplier_id, payload).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_bank_account(&db, id).await
+}
+
+/// Update a bank account. New account details have to be verified again.
+#[tauri::command]
+pub async fn update_supplier_bank_account(
+    db: State<'_, Db>,
+    payload: SupplierBankAccountUpdate,
+) -> Result<SupplierBankAccount, String> {
+    let account_name = payload.account_name.as_deref().map(|n| required(n, "Account holder name")).transpose()?;
+    let account_no = payload.account_no.as_deref().map(validation::account_no).transpose()?;
+    let ifsc = payload.ifsc.as_deref().map(validation::ifsc).transpose()?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let res = sqlx::query(
+        "UPDATE supplier_bank_accounts SET
+            verification_status = CASE
+                WHEN COALESCE(?3, account_no) <> account_no OR COALESCE(?4, ifsc) <> ifsc THEN 'unverified'
+                ELSE verification_status END,
+            verified_at = CASE
+                WHEN COALESCE(?3, account_no) <> account_no OR COALESCE(?4, ifsc) <> ifsc THEN NULL
+                ELSE verified_at END,
+            account_name = COALESCE(?2, account_name),
+            account_no = COALESCE(?3, account_no),
+            ifsc = COALESCE(?4, ifsc),
+            bank_name = CASE WHEN ?5 THEN ?6 ELSE bank_name END,
+            branch = CASE WHEN ?7 THEN ?8 ELSE branch END,
+            is_primary = COALESCE(?9, is_primary)
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(account_name)
+    .bind(account_no)
+    .bind(ifsc)
+    .bind(payload.bank_name.is_some())
+    .bind(validation::clean(payload.bank_name))
+    .bind(payload.branch.is_some())
+    .bind(validation::clean(payload.branch))
+    .bind(payload.is_primary.map(|b| b as i64))
+    .execute(&mut *tx)
+    .await
+    .map_err(supplier_child_error)?;
+    if res.rows_affected() == 0 {
+        return Err("Bank account not found".to_string());
+    }
+    if payload.is_primary == Some(true) {
+        clear_other_flags(&mut tx, "supplier_bank_accounts", "is_primary", payload.id).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_bank_account(&db, payload.id).await
+}
+
+/// Record the outcome of checking a bank account (penny drop, cancelled cheque, ...).
+#[tauri::command]
+pub async fn set_supplier_bank_account_verification(
+    db: State<'_, Db>,
+    id: i64,
+    status: String,
+) -> Result<SupplierBankAccount, String> {
+    let status = status.trim().to_lowercase();
+    if !matches!(status.as_str(), "unverified" | "verified" | "failed") {
+        return Err(format!("Unknown verification status '{}'", status));
+    }
+    let res = sqlx::query(
+        "UPDATE supplier_bank_accounts SET
+            verification_status = ?2,
+            verified_at = CASE WHEN ?2 = 'unverified' THEN NULL ELSE datetime('now') END
+         WHERE id = ?1",
+    )
+    .bind(id)
+    .bind(&status)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Bank account not found".to_string());
+    }
+    fetch_supplier_bank_account(&db, id).await
+}
+
+#[tauri::command]
+pub async fn delete_supplier_bank_account(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM supplier_bank_accounts WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn add_supplier_contact(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    payload: SupplierContactCreate,
+) -> Result<SupplierContact, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let id = insert_supplier_contact(&mut tx, supplier_id, payload).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_contact(&db, id).await
+}
+
+#[tauri::command]
+pub async fn update_supplier_contact(
+    db: State<'_, Db>,
+    payload: SupplierContactUpdate,
+) -> Result<SupplierContact, String> {
+    let name = payload.name.as_deref().map(|n| required(n, "Contact name")).transpose()?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let res = sqlx::query(
+        "UPDATE supplier_contacts SET
             name = COALESCE(?2, name),
-            gst_no = COALESCE(?3, gst_no),
-            state_code = COALESCE(?4, state_code),
-            tds_flag = COALESCE(?5, tds_flag),
-            tds_rate = COALESCE(?6, tds_rate),
-            contact = COALESCE(?7, contact),
-            email = COALESCE(?8, email)
+            role = CASE WHEN ?3 THEN ?4 ELSE role END,
+            phone = CASE WHEN ?5 THEN ?6 ELSE phone END,
+            email = CASE WHEN ?7 THEN ?8 ELSE email END,
+            is_primary = COALESCE(?9, is_primary)
          WHERE id = ?1",
     )
-    .bind(payload.id)
-    .bind(payload.name)
-    .bind(payload.gst_no)
-    .bind(payload.state_code)
-    .bind(payload.tds_flag.map(|b| if b { 1_i64 } else { 0_i64 }))
-    .bind(payload.tds_rate)
-    .bind(payload.contact)
-    .bind(payload.email)
-    .execute(&db.0)
+    .bind(payload.id)
+    .bind(name)
+    .bind(payload.role.is_some())
+    .bind(validation::clean(payload.role))
+    .bind(payload.phone.is_some())
+    .bind(validation::clean(payload.phone))
+    .bind(payload.email.is_some())
+    .bind(validation::clean(payload.email))
+    .bind(payload.is_primary.map(|b| b as i64))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Contact not found".to_string());
+    }
+    if payload.is_primary == Some(true) {
+        clear_other_flags(&mut tx, "supplier_contacts", "is_primary", payload.id).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_supplier_contact(&db, payload.id).await
+}
+
+#[tauri::command]
+pub async fn delete_supplier_contact(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM supplier_contacts WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+// Duplicate invoices
+
+/// Amounts within this many rupees count as the same bill value.
+const INVOICE_AMOUNT_TOLERANCE: f64 = 1.0;
+
+const INVOICE_SUSPECT_SELECT: &str =
+    "SELECT pe.id AS purchase_id, pe.supplier_id, s.name AS supplier_name, s.gst_no, s.pan,
+            pe.invoice_no, pe.date, pe.invoice_value
+     FROM purchase_entries pe JOIN suppliers s ON s.id = pe.supplier_id
+     WHERE pe.deleted_at IS NULL";
+
+fn suspect_party(s: &DuplicateInvoiceSuspect) -> String {
+    dedupe::party_key(s.supplier_id, s.gst_no.as_deref(), s.pan.as_deref())
+}
+
+/// Live purchases from the same party as `supplier_id` (same record, GSTIN or PAN) whose
+/// invoice number normalises to the same key and whose amount or date also match.
+async fn find_duplicate_invoices(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    invoice_no: &str,
+    date: &str,
+    invoice_value: f64,
+    exclude_id: Option<i64>,
+) -> Result<Vec<DuplicateInvoiceSuspect>, String> {
+    let supplier: Option<(Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT gst_no, pan FROM suppliers WHERE id = ?1")
+            .bind(supplier_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    let Some((gst_no, pan)) = supplier else {
+        return Err("Supplier not found".to_string());
+    };
+    let party = dedupe::party_key(supplier_id, gst_no.as_deref(), pan.as_deref());
+    let key = dedupe::invoice_key(invoice_no, date);
+
+    let candidates = sqlx::query_as::<_, DuplicateInvoiceSuspect>(&format!(
+        "{INVOICE_SUSPECT_SELECT}
+           AND (pe.date = ?1 OR ABS(pe.invoice_value - ?2) <= ?3)
+           AND (?4 IS NULL OR pe.id <> ?4)
+         ORDER BY pe.date DESC, pe.id DESC",
+    ))
+    .bind(date)
+    .bind(invoice_value)
+    .bind(INVOICE_AMOUNT_TOLERANCE)
+    .bind(exclude_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(candidates
+        .into_iter()
+        .filter(|c| suspect_party(c) == party && dedupe::invoice_key(&c.invoice_no, &c.date) == key)
+        .collect())
+}
+
+/// Purchases that a new entry would likely duplicate, for a warning before saving.
+#[tauri::command]
+pub async fn check_purchase_duplicates(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    invoice_no: String,
+    date: String,
+    invoice_value: f64,
+    exclude_id: Option<i64>,
+) -> Result<Vec<DuplicateInvoiceSuspect>, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    find_duplicate_invoices(&mut conn, supplier_id, &invoice_no, &date, invoice_value, exclude_id).await
+}
+
+/// Existing purchases that look like the same bill entered more than once, possibly
+/// under different invoice spellings or duplicate supplier records.
+#[tauri::command]
+pub async fn get_duplicate_invoice_report(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<DuplicateInvoiceGroup>, String> {
+    let rows = sqlx::query_as::<_, DuplicateInvoiceSuspect>(&format!(
+        "{INVOICE_SUSPECT_SELECT}
+           AND (?1 IS NULL OR pe.date >= ?1)
+           AND (?2 IS NULL OR pe.date <= ?2)
+         ORDER BY pe.date, pe.id",
+    ))
+    .bind(date_from)
+    .bind(date_to)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut by_key: std::collections::BTreeMap<(String, String), Vec<DuplicateInvoiceSuspect>> =
+        std::collections::BTreeMap::new();
+    for row in rows {
+        let key = (suspect_party(&row), dedupe::invoice_key(&row.invoice_no, &row.date));
+        by_key.entry(key).or_default().push(row);
+    }
+
+    let mut groups = Vec::new();
+    for ((_, invoice_key), rows) in by_key {
+        if rows.len() < 2 {
+            continue;
+        }
+        let (mut same_amount, mut same_date) = (false, false);
+        let mut keep = vec![false; rows.len()];
+        for i in 0..rows.len() {
+            for j in i + 1..rows.len() {
+                let amount = (rows[i].invoice_value - rows[j].invoice_value).abs() <= INVOICE_AMOUNT_TOLERANCE;
+                let date = rows[i].date == rows[j].date;
+                if amount || date {
+                    same_amount |= amount;
+                    same_date |= date;
+                    keep[i] = true;
+                    keep[j] = true;
+                }
+            }
+        }
+        let purchases: Vec<DuplicateInvoiceSuspect> =
+            rows.into_iter().zip(keep).filter(|(_, k)| *k).map(|(r, _)| r).collect();
+        if !purchases.is_empty() {
+            groups.push(DuplicateInvoiceGroup { invoice_key, same_amount, same_date, purchases });
+        }
+    }
+    // Most recent first
+    groups.sort_by(|a, b| {
+        let last = |g: &DuplicateInvoiceGroup| g.purchases.iter().map(|p| p.date.clone()).max();
+        last(b).cmp(&last(a))
+    });
+    Ok(groups)
+}
+
+// Purchase workflow
+
+/// Every move the workflow allows, grouped by the status it starts from.
+#[tauri::command]
+pub async fn get_status_transitions(db: State<'_, Db>) -> Result<Vec<PurchaseStatusTransition>, String> {
+    sqlx::query_as::<_, PurchaseStatusTransition>(&format!(
+        "SELECT {} FROM purchase_status_transitions ORDER BY from_status, id",
+        workflow::TRANSITION_COLUMNS,
+    ))
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Allow a move, or change who may make it. Admin only.
+#[tauri::command]
+pub async fn set_status_transition(
+    db: State<'_, Db>,
+    payload: PurchaseStatusTransitionInput,
+    user_id: i64,
+) -> Result<PurchaseStatusTransition, String> {
+    require_admin(&db, user_id).await?;
+    let from = workflow::status(&payload.from_status)?;
+    let to = workflow::status(&payload.to_status)?;
+    if from == to {
+        return Err("A transition must lead to a different status".to_string());
+    }
+    let role = workflow::role(payload.required_role.as_deref().unwrap_or("user"))?;
+    sqlx::query_as::<_, PurchaseStatusTransition>(&format!(
+        "INSERT INTO purchase_status_transitions (from_status, to_status, required_role, requires_reason)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (from_status, to_status) DO UPDATE SET
+            required_role = excluded.required_role,
+            requires_reason = excluded.requires_reason
+         RETURNING {}",
+        workflow::TRANSITION_COLUMNS,
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(role)
+    .bind(payload.requires_reason)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Stop allowing a move. Entries already moved keep their status. Admin only.
+#[tauri::command]
+pub async fn delete_status_transition(db: State<'_, Db>, id: i64, user_id: i64) -> Result<bool, String> {
+    require_admin(&db, user_id).await?;
+    let res = sqlx::query("DELETE FROM purchase_status_transitions WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Moves `user_id` may make on a purchase in its current status.
+#[tauri::command]
+pub async fn get_available_transitions(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    user_id: i64,
+) -> Result<Vec<PurchaseStatusTransition>, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    workflow::available(&mut conn, purchase_id, user_id).await
+}
+
+/// Revision note for a status change, e.g. "verified → pending: wrong rate".
+fn status_note(from: &str, to: &str, reason: Option<&str>) -> String {
+    match reason.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) => format!("{} → {}: {}", workflow::label(from), workflow::label(to), r),
+        None => format!("{} → {}", workflow::label(from), workflow::label(to)),
+    }
+}
+
+/// Move a purchase to `to_status`, recording who did it and why. The new status is also
+/// saved as a revision.
+#[tauri::command]
+pub async fn transition_purchase(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    to_status: String,
+    reason: Option<String>,
+    user_id: i64,
+) -> Result<PurchaseEntry, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let from = workflow::transition(&mut tx, purchase_id, &to_status, reason.clone(), user_id).await?;
+    let to = workflow::status(&to_status)?;
+    let note = status_note(&from, &to, reason.as_deref());
+    revisions::record(&mut tx, purchase_id, "status", Some(note), Some(user_id)).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
+    .bind(purchase_id)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Status changes of a purchase, oldest first.
+#[tauri::command]
+pub async fn get_purchase_status_history(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchaseStatusChange>, String> {
+    sqlx::query_as::<_, PurchaseStatusChange>(
+        "SELECT h.id, h.purchase_id, h.from_status, h.to_status, h.reason, h.changed_by,
+                u.username AS changed_by_name, h.changed_at
+         FROM purchase_status_history h LEFT JOIN users u ON u.id = h.changed_by
+         WHERE h.purchase_id = ?1
+         ORDER BY h.id",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Approvals
+
+async fn fetch_approval_rule(db: &Db, id: i64) -> Result<ApprovalRule, String> {
+    let row: approvals::ApprovalRuleRow = sqlx::query_as(&format!("{} WHERE r.id = ?1", approvals::RULE_SELECT))
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Approval rule not found".to_string())?;
+    ApprovalRule::try_from(row)
+}
+
+/// Approval rules in the order their approvers sign.
+#[tauri::command]
+pub async fn get_approval_rules(db: State<'_, Db>, include_inactive: Option<bool>) -> Result<Vec<ApprovalRule>, String> {
+    let rows: Vec<approvals::ApprovalRuleRow> = sqlx::query_as(&format!(
+        "{} WHERE (?1 OR r.active = 1) ORDER BY r.position, r.id",
+        approvals::RULE_SELECT,
+    ))
+    .bind(include_inactive.unwrap_or(false))
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    rows.into_iter().map(ApprovalRule::try_from).collect()
+}
+
+/// A rule can only be scoped to an active supplier and an existing dimension.
+async fn check_rule_scope(
+    conn: &mut SqliteConnection,
+    supplier_id: Option<i64>,
+    dimension_id: Option<i64>,
+) -> Result<(), String> {
+    if let Some(supplier_id) = supplier_id {
+        check_supplier_active(conn, supplier_id).await?;
+    }
+    if let Some(dimension_id) = dimension_id {
+        let (exists,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM dimensions WHERE id = ?1)")
+            .bind(dimension_id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("Dimension {} not found", dimension_id));
+        }
+    }
+    Ok(())
+}
+
+/// Admin only. New rules sign after the existing ones unless given a position.
+#[tauri::command]
+pub async fn add_approval_rule(db: State<'_, Db>, payload: ApprovalRuleCreate, user_id: i64) -> Result<ApprovalRule, String> {
+    require_admin(&db, user_id).await?;
+    let name = required(&payload.name, "Rule name")?;
+    approvals::check_band(payload.min_amount, payload.max_amount)?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    check_rule_scope(&mut tx, payload.supplier_id, payload.dimension_id).await?;
+    let res = sqlx::query(
+        "INSERT INTO approval_rules (name, min_amount, max_amount, supplier_id, dimension_id, position, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, (SELECT COALESCE(MAX(position), 0) + 1 FROM approval_rules)), ?7)",
+    )
+    .bind(name)
+    .bind(payload.min_amount)
+    .bind(payload.max_amount)
+    .bind(payload.supplier_id)
+    .bind(payload.dimension_id)
+    .bind(payload.position)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let id = res.last_insert_rowid();
+    approvals::set_approvers(&mut tx, id, &payload.approver_ids).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_approval_rule(&db, id).await
+}
+
+/// Admin only. Sign-offs already requested keep their approvers.
+#[tauri::command]
+pub async fn update_approval_rule(db: State<'_, Db>, payload: ApprovalRuleUpdate, user_id: i64) -> Result<ApprovalRule, String> {
+    require_admin(&db, user_id).await?;
+    let current = fetch_approval_rule(&db, payload.id).await?;
+    let name = payload.name.as_deref().map(|n| required(n, "Rule name")).transpose()?;
+    approvals::check_band(
+        payload.min_amount.unwrap_or(current.min_amount),
+        payload.max_amount.unwrap_or(current.max_amount),
+    )?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    // Only newly given scope is checked, so a rule on a since-deactivated supplier can still be renamed
+    check_rule_scope(
+        &mut tx,
+        payload.supplier_id.flatten().filter(|&id| Some(id) != current.supplier_id),
+        payload.dimension_id.flatten().filter(|&id| Some(id) != current.dimension_id),
+    )
+    .await?;
+    sqlx::query(
+        "UPDATE approval_rules SET
+            name = COALESCE(?2, name),
+            min_amount = COALESCE(?3, min_amount),
+            max_amount = CASE WHEN ?4 THEN ?5 ELSE max_amount END,
+            supplier_id = CASE WHEN ?6 THEN ?7 ELSE supplier_id END,
+            dimension_id = CASE WHEN ?8 THEN ?9 ELSE dimension_id END,
+            active = COALESCE(?10, active),
+            position = COALESCE(?11, position)
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(name)
+    .bind(payload.min_amount)
+    .bind(payload.max_amount.is_some())
+    .bind(payload.max_amount.flatten())
+    .bind(payload.supplier_id.is_some())
+    .bind(payload.supplier_id.flatten())
+    .bind(payload.dimension_id.is_some())
+    .bind(payload.dimension_id.flatten())
+    .bind(payload.active)
+    .bind(payload.position)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some(ids) = &payload.approver_ids {
+        approvals::set_approvers(&mut tx, payload.id, ids).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    fetch_approval_rule(&db, payload.id).await
+}
+
+/// Admin only. The trail of sign-offs made under the rule stays.
+#[tauri::command]
+pub async fn delete_approval_rule(db: State<'_, Db>, id: i64, user_id: i64) -> Result<bool, String> {
+    require_admin(&db, user_id).await?;
+    let res = sqlx::query("DELETE FROM approval_rules WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Entries whose current sign-off is `user_id`'s, oldest request first.
+#[tauri::command]
+pub async fn get_approval_inbox(db: State<'_, Db>, user_id: i64) -> Result<Vec<ApprovalInboxItem>, String> {
+    sqlx::query_as::<_, ApprovalInboxItem>(
+        "SELECT pa.id AS approval_id, pe.id AS purchase_id, pe.supplier_id, s.name AS supplier_name,
+                pe.invoice_no, pe.date, pe.invoice_value, pa.step_no,
+                (SELECT COUNT(*) FROM purchase_approvals x WHERE x.purchase_id = pa.purchase_id AND x.round = pa.round) AS steps,
+                r.name AS rule_name, pa.requested_at
+         FROM purchase_approvals pa
+         JOIN purchase_entries pe ON pe.id = pa.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         LEFT JOIN approval_rules r ON r.id = pa.rule_id
+         WHERE pa.approver_id = ?1 AND pa.status = 'pending'
+           AND pe.status = 'verified' AND pe.deleted_at IS NULL
+           AND pa.round = (SELECT MAX(round) FROM purchase_approvals WHERE purchase_id = pa.purchase_id)
+           AND NOT EXISTS (
+               SELECT 1 FROM purchase_approvals e
+               WHERE e.purchase_id = pa.purchase_id AND e.round = pa.round
+                 AND e.step_no < pa.step_no AND e.status <> 'approved')
+         ORDER BY pa.requested_at, pa.id",
+    )
+    .bind(user_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn decide_purchase(
+    db: &Db,
+    purchase_id: i64,
+    user_id: i64,
+    approve: bool,
+    comment: Option<String>,
+) -> Result<PurchaseEntry, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let reason = comment.clone();
+    if let Some(to) = approvals::decide(&mut tx, purchase_id, user_id, approve, comment).await? {
+        let reason = if approve { reason } else { reason.map(|r| format!("Rejected: {}", r.trim())) };
+        let note = status_note("verified", &to, reason.as_deref());
+        revisions::record(&mut tx, purchase_id, "status", Some(note), Some(user_id)).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
+    .bind(purchase_id)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Sign off the step a purchase is waiting on. The last sign-off moves it to approved, which
+/// needs the role the verified-to-approved transition asks for.
+#[tauri::command]
+pub async fn approve_purchase(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    comment: Option<String>,
+    user_id: i64,
+) -> Result<PurchaseEntry, String> {
+    decide_purchase(&db, purchase_id, user_id, true, comment).await
+}
+
+/// Turn a purchase down at the caller's step, sending it back to pending. A comment is required.
+#[tauri::command]
+pub async fn reject_purchase(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    comment: String,
+    user_id: i64,
+) -> Result<PurchaseEntry, String> {
+    decide_purchase(&db, purchase_id, user_id, false, Some(comment)).await
+}
+
+/// Every sign-off requested for a purchase, round by round.
+#[tauri::command]
+pub async fn get_purchase_approvals(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchaseApproval>, String> {
+    sqlx::query_as::<_, PurchaseApproval>(
+        "SELECT pa.id, pa.purchase_id, pa.round, pa.step_no, pa.rule_id, r.name AS rule_name,
+                pa.approver_id, u.username AS approver_name, pa.status, pa.comment, pa.amount,
+                pa.requested_at, pa.decided_at
+         FROM purchase_approvals pa
+         LEFT JOIN approval_rules r ON r.id = pa.rule_id
+         LEFT JOIN users u ON u.id = pa.approver_id
+         WHERE pa.purchase_id = ?1
+         ORDER BY pa.round, pa.step_no",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Purchase orders
+
+const PO_SELECT: &str = "SELECT po.id, po.po_no, po.supplier_id, s.name AS supplier_name, po.po_date, po.expected_date,
+        po.status, po.narration,
+        COALESCE((SELECT SUM(l.qty * l.price) FROM purchase_order_lines l WHERE l.po_id = po.id), 0.0) AS total_value,
+        po.created_by, po.created_at
+     FROM purchase_orders po JOIN suppliers s ON s.id = po.supplier_id";
+
+/// Billed quantity per line counts live entries that aren't cancelled.
+const LIVE_BILLED: &str = "JOIN purchase_entries pe ON pe.id = pi.purchase_id AND pe.deleted_at IS NULL AND pe.status <> 'cancelled'";
+
+async fn fetch_purchase_order(conn: &mut SqliteConnection, id: i64) -> Result<PurchaseOrder, String> {
+    sqlx::query_as::<_, PurchaseOrder>(&format!("{PO_SELECT} WHERE po.id = ?1"))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Purchase order not found".to_string())
+}
+
+/// Purchase orders, newest first.
+#[tauri::command]
+pub async fn get_purchase_orders(
+    db: State<'_, Db>,
+    supplier_id: Option<i64>,
+    status: Option<String>,
+) -> Result<Vec<PurchaseOrder>, String> {
+    let status = status.as_deref().map(matching::po_status).transpose()?;
+    sqlx::query_as::<_, PurchaseOrder>(&format!(
+        "{PO_SELECT} WHERE (?1 IS NULL OR po.supplier_id = ?1) AND (?2 IS NULL OR po.status = ?2)
+         ORDER BY po.po_date DESC, po.id DESC",
+    ))
+    .bind(supplier_id)
+    .bind(status)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// A purchase order with what has been received and billed on each line.
+#[tauri::command]
+pub async fn get_purchase_order(db: State<'_, Db>, id: i64) -> Result<PurchaseOrderDetail, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let order = fetch_purchase_order(&mut conn, id).await?;
+    let lines = sqlx::query_as::<_, PurchaseOrderLine>(&format!(
+        "SELECT l.id, l.po_id, l.line_no, l.part_no, l.description, l.qty, l.unit, l.price,
+                COALESCE((SELECT SUM(gl.qty) FROM goods_receipt_lines gl WHERE gl.po_line_id = l.id), 0.0) AS received_qty,
+                COALESCE((SELECT SUM(pi.qty) FROM purchase_items pi {LIVE_BILLED} WHERE pi.po_line_id = l.id), 0.0) AS invoiced_qty
+         FROM purchase_order_lines l WHERE l.po_id = ?1 ORDER BY l.line_no",
+    ))
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(PurchaseOrderDetail { order, lines })
+}
+
+/// Raise a purchase order. Lines must be for parts in the item master.
+#[tauri::command]
+pub async fn add_purchase_order(
+    db: State<'_, Db>,
+    payload: PurchaseOrderCreate,
+    user_id: Option<i64>,
+) -> Result<PurchaseOrderDetail, String> {
+    let po_no = required(&payload.po_no, "PO number")?;
+    let po_date = required(&payload.po_date, "PO date")?;
+    if payload.lines.is_empty() {
+        return Err("A purchase order needs at least one line".to_string());
+    }
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    check_supplier_active(&mut tx, payload.supplier_id).await?;
+    let (taken,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM purchase_orders WHERE po_no = ?1)")
+        .bind(&po_no)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!("PO number '{}' is already used", po_no));
+    }
+
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO purchase_orders (po_no, supplier_id, po_date, expected_date, narration, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+    )
+    .bind(&po_no)
+    .bind(payload.supplier_id)
+    .bind(po_date)
+    .bind(validation::clean(payload.expected_date))
+    .bind(validation::clean(payload.narration))
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (i, line) in payload.lines.into_iter().enumerate() {
+        let line_no = i as i64 + 1;
+        if line.qty <= 0.0 {
+            return Err(format!("Line {}: quantity must be above zero", line_no));
+        }
+        if line.price < 0.0 {
+            return Err(format!("Line {}: price can't be negative", line_no));
+        }
+        let part_no = resolve_part_no(&mut tx, Some(line.part_no), &KeptLines::default())
+            .await?
+            .ok_or_else(|| format!("Line {}: part number is required", line_no))?;
+        let unit = uom::resolve(&mut tx, line.unit).await?;
+        sqlx::query(
+            "INSERT INTO purchase_order_lines (po_id, line_no, part_no, description, qty, unit, price)
+             VALUES (?1, ?2, ?3, COALESCE(?4, (SELECT description FROM items WHERE part_no = ?3)), ?5, ?6, ?7)",
+        )
+        .bind(id)
+        .bind(line_no)
+        .bind(part_no)
+        .bind(validation::clean(line.description))
+        .bind(line.qty)
+        .bind(unit)
+        .bind(line.price)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_purchase_order(db, id).await
+}
+
+/// Close an order that won't receive more goods, reopen it, or cancel one nothing was received
+/// or billed against.
+#[tauri::command]
+pub async fn set_purchase_order_status(db: State<'_, Db>, id: i64, status: String) -> Result<PurchaseOrder, String> {
+    let status = matching::po_status(&status)?;
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let order = fetch_purchase_order(&mut tx, id).await?;
+    if order.status == "cancelled" && status != "cancelled" {
+        return Err(format!("PO {} is cancelled; raise a new order instead", order.po_no));
+    }
+    if status == "cancelled" {
+        let (used,): (bool,) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM goods_receipt_lines gl JOIN purchase_order_lines l ON l.id = gl.po_line_id WHERE l.po_id = ?1)
+                 OR EXISTS (SELECT 1 FROM purchase_items pi JOIN purchase_order_lines l ON l.id = pi.po_line_id WHERE l.po_id = ?1)",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if used {
+            return Err(format!("Goods were received or billed against PO {}; close it instead", order.po_no));
+        }
+    }
+    sqlx::query("UPDATE purchase_orders SET status = ?2 WHERE id = ?1")
+        .bind(id)
+        .bind(&status)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let order = fetch_purchase_order(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(order)
+}
+
+/// Remove an order that has no GRNs or invoice lines against it.
+#[tauri::command]
+pub async fn delete_purchase_order(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let (receipts, billed): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM goods_receipts WHERE po_id = ?1),
+                (SELECT COUNT(*) FROM purchase_items pi JOIN purchase_order_lines l ON l.id = pi.po_line_id WHERE l.po_id = ?1)",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if receipts > 0 || billed > 0 {
+        return Err(format!(
+            "This order has {} GRN(s) and {} invoice line(s) against it; cancel or close it instead",
+            receipts, billed
+        ));
+    }
+    let res = sqlx::query("DELETE FROM purchase_orders WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+// Goods receipts
+
+const GRN_SELECT: &str = "SELECT g.id, g.grn_no, g.supplier_id, s.name AS supplier_name, g.po_id, po.po_no,
+        g.received_date, g.narration, g.created_by, g.created_at
+     FROM goods_receipts g
+     JOIN suppliers s ON s.id = g.supplier_id
+     LEFT JOIN purchase_orders po ON po.id = g.po_id";
+
+/// Goods receipts, newest first.
+#[tauri::command]
+pub async fn get_goods_receipts(
+    db: State<'_, Db>,
+    supplier_id: Option<i64>,
+    po_id: Option<i64>,
+) -> Result<Vec<GoodsReceipt>, String> {
+    sqlx::query_as::<_, GoodsReceipt>(&format!(
+        "{GRN_SELECT} WHERE (?1 IS NULL OR g.supplier_id = ?1) AND (?2 IS NULL OR g.po_id = ?2)
+         ORDER BY g.received_date DESC, g.id DESC",
+    ))
+    .bind(supplier_id)
+    .bind(po_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// A goods receipt with what has been billed on each line.
+#[tauri::command]
+pub async fn get_goods_receipt(db: State<'_, Db>, id: i64) -> Result<GoodsReceiptDetail, String> {
+    let receipt = sqlx::query_as::<_, GoodsReceipt>(&format!("{GRN_SELECT} WHERE g.id = ?1"))
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Goods receipt not found".to_string())?;
+    let lines = sqlx::query_as::<_, GoodsReceiptLine>(&format!(
+        "SELECT gl.id, gl.grn_id, gl.line_no, gl.po_line_id, gl.part_no, gl.description, gl.qty, gl.unit,
+                COALESCE((SELECT SUM(pi.qty) FROM purchase_items pi {LIVE_BILLED} WHERE pi.grn_line_id = gl.id), 0.0) AS invoiced_qty
+         FROM goods_receipt_lines gl WHERE gl.grn_id = ?1 ORDER BY gl.line_no",
+    ))
+    .bind(id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(GoodsReceiptDetail { receipt, lines })
+}
+
+/// Record goods received, against an open order or on their own. Lines on an order take its
+/// part numbers and can't take the received quantity past what was ordered, give or take the
+/// quantity tolerance. Entries already billing those order lines are matched again.
+#[tauri::command]
+pub async fn add_goods_receipt(
+    db: State<'_, Db>,
+    payload: GoodsReceiptCreate,
+    user_id: Option<i64>,
+) -> Result<GoodsReceiptDetail, String> {
+    let grn_no = required(&payload.grn_no, "GRN number")?;
+    let received_date = required(&payload.received_date, "Received date")?;
+    if payload.lines.is_empty() {
+        return Err("A goods receipt needs at least one line".to_string());
+    }
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    check_supplier_active(&mut tx, payload.supplier_id).await?;
+    let (taken,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM goods_receipts WHERE grn_no = ?1)")
+        .bind(&grn_no)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!("GRN number '{}' is already used", grn_no));
+    }
+    if let Some(po_id) = payload.po_id {
+        let order = fetch_purchase_order(&mut tx, po_id).await?;
+        if order.supplier_id != payload.supplier_id {
+            return Err(format!("PO {} is from another supplier", order.po_no));
+        }
+        if order.status != "open" {
+            return Err(format!("PO {} is {}; reopen it to receive goods", order.po_no, order.status));
+        }
+    }
+    let settings = matching::load_settings(&mut tx).await?;
+
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO goods_receipts (grn_no, supplier_id, po_id, received_date, narration, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+    )
+    .bind(&grn_no)
+    .bind(payload.supplier_id)
+    .bind(payload.po_id)
+    .bind(received_date)
+    .bind(validation::clean(payload.narration))
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut po_lines = Vec::new();
+    for (i, line) in payload.lines.into_iter().enumerate() {
+        let line_no = i as i64 + 1;
+        if line.qty <= 0.0 {
+            return Err(format!("Line {}: quantity must be above zero", line_no));
+        }
+        let (part_no, description) = match line.po_line_id {
+            Some(po_line_id) => {
+                let found: Option<(Option<i64>, String, String, f64, f64)> = sqlx::query_as(
+                    "SELECT l.po_id, l.part_no, l.description, l.qty,
+                            COALESCE((SELECT SUM(gl.qty) FROM goods_receipt_lines gl WHERE gl.po_line_id = l.id), 0.0)
+                     FROM purchase_order_lines l WHERE l.id = ?1",
+                )
+                .bind(po_line_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                let (po_id, part_no, description, ordered, received) =
+                    found.ok_or_else(|| format!("Line {}: PO line {} not found", line_no, po_line_id))?;
+                if payload.po_id.is_none() || po_id != payload.po_id {
+                    return Err(format!("Line {}: receive order lines on a GRN for that order", line_no));
+                }
+                if let Some(given) = line.part_no.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+                    if !given.eq_ignore_ascii_case(&part_no) {
+                        return Err(format!("Line {}: the order line is for '{}', not '{}'", line_no, part_no, given));
+                    }
+                }
+                // `received` includes earlier lines of this GRN, already inserted
+                let limit = ordered * (1.0 + settings.qty_tolerance_pct / 100.0);
+                if received + line.qty > limit + 1e-9 {
+                    return Err(format!(
+                        "Line {}: receiving {} more of '{}' would exceed the {} ordered ({} received so far)",
+                        line_no, line.qty, part_no, ordered, received
+                    ));
+                }
+                po_lines.push(po_line_id);
+                (part_no, validation::clean(line.description).unwrap_or(description))
+            }
+            None => {
+                let part_no = resolve_part_no(&mut tx, line.part_no, &KeptLines::default())
+                    .await?
+                    .ok_or_else(|| format!("Line {}: part number is required", line_no))?;
+                let (description,): (String,) = sqlx::query_as("SELECT description FROM items WHERE part_no = ?1")
+                    .bind(&part_no)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                (part_no, validation::clean(line.description).unwrap_or(description))
+            }
+        };
+        let unit = uom::resolve(&mut tx, line.unit).await?;
+        sqlx::query(
+            "INSERT INTO goods_receipt_lines (grn_id, line_no, po_line_id, part_no, description, qty, unit)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(id)
+        .bind(line_no)
+        .bind(line.po_line_id)
+        .bind(part_no)
+        .bind(description)
+        .bind(line.qty)
+        .bind(unit)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    matching::rematch_po_lines(&mut tx, &po_lines).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_goods_receipt(db, id).await
+}
+
+/// Remove a goods receipt no invoice line bills. Entries billing its order lines are matched again.
+#[tauri::command]
+pub async fn delete_goods_receipt(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let (billed,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM purchase_items pi JOIN goods_receipt_lines gl ON gl.id = pi.grn_line_id WHERE gl.grn_id = ?1",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if billed > 0 {
+        return Err(format!("{} invoice line(s) bill this GRN; unlink them first", billed));
+    }
+    let po_lines: Vec<i64> = sqlx::query_as::<_, (i64,)>(
+        "SELECT po_line_id FROM goods_receipt_lines WHERE grn_id = ?1 AND po_line_id IS NOT NULL",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(l,)| l)
+    .collect();
+    let res = sqlx::query("DELETE FROM goods_receipts WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    matching::rematch_po_lines(&mut tx, &po_lines).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+// Three-way matching
+
+/// Differences between invoice lines and their PO and GRN found when the purchase was last saved.
+#[tauri::command]
+pub async fn get_purchase_match_exceptions(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<MatchException>, String> {
+    sqlx::query_as::<_, MatchException>(
+        "SELECT line_no, part_no, kind, expected, actual, deviation_pct, tolerance_pct, po_line_id, grn_line_id
+         FROM match_exceptions WHERE purchase_id = ?1 ORDER BY line_no, id",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// All match exceptions on live purchases dated in the period.
+#[tauri::command]
+pub async fn get_match_exception_report(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    supplier_id: Option<i64>,
+    kind: Option<String>,
+) -> Result<Vec<MatchExceptionRow>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(
+        "SELECT mx.id, mx.purchase_id, pe.invoice_no, pe.date, pe.supplier_id, s.name AS supplier_name,
+                mx.line_no, mx.part_no, mx.kind, mx.expected, mx.actual, mx.deviation_pct, mx.tolerance_pct,
+                mx.po_line_id, mx.grn_line_id, mx.created_at
+         FROM match_exceptions mx
+         JOIN purchase_entries pe ON pe.id = mx.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE pe.deleted_at IS NULL AND pe.status <> 'cancelled'",
+    );
+    if let Some(df) = date_from {
+        q.push(" AND pe.date >= ").push_bind(df);
+    }
+    if let Some(dt) = date_to {
+        q.push(" AND pe.date <= ").push_bind(dt);
+    }
+    if let Some(sid) = supplier_id {
+        q.push(" AND pe.supplier_id = ").push_bind(sid);
+    }
+    if let Some(k) = kind {
+        q.push(" AND mx.kind = ").push_bind(k);
+    }
+    q.push(" ORDER BY pe.date DESC, pe.id DESC, mx.line_no");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_match_settings(db: State<'_, Db>) -> Result<MatchSettings, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    matching::load_settings(&mut conn).await
+}
+
+/// Change the tolerances. Already stored exceptions keep the tolerances they were flagged with.
+#[tauri::command]
+pub async fn update_match_settings(db: State<'_, Db>, payload: MatchSettingsUpdate) -> Result<MatchSettings, String> {
+    if payload.qty_tolerance_pct.is_some_and(|t| t < 0.0) || payload.price_tolerance_pct.is_some_and(|t| t < 0.0) {
+        return Err("Tolerances can't be negative".to_string());
+    }
+    sqlx::query(
+        "UPDATE match_settings SET
+            qty_tolerance_pct = COALESCE(?1, qty_tolerance_pct),
+            price_tolerance_pct = COALESCE(?2, price_tolerance_pct)
+         WHERE id = 1",
+    )
+    .bind(payload.qty_tolerance_pct)
+    .bind(payload.price_tolerance_pct)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    matching::load_settings(&mut conn).await
+}
+
+/// Lines of open orders still waiting for goods, oldest order first.
+#[tauri::command]
+pub async fn get_open_po_report(db: State<'_, Db>, supplier_id: Option<i64>) -> Result<Vec<OpenPoLine>, String> {
+    sqlx::query_as::<_, OpenPoLine>(&format!(
+        "SELECT po_id, po_no, po_date, expected_date, supplier_id, supplier_name, po_line_id, line_no, part_no,
+                description, ordered_qty, received_qty, invoiced_qty, ordered_qty - received_qty AS pending_qty,
+                price, (ordered_qty - received_qty) * price AS pending_value
+         FROM (
+            SELECT po.id AS po_id, po.po_no, po.po_date, po.expected_date, po.supplier_id, s.name AS supplier_name,
+                   l.id AS po_line_id, l.line_no, l.part_no, l.description, l.qty AS ordered_qty, l.price,
+                   COALESCE((SELECT SUM(gl.qty) FROM goods_receipt_lines gl WHERE gl.po_line_id = l.id), 0.0) AS received_qty,
+                   COALESCE((SELECT SUM(pi.qty) FROM purchase_items pi {LIVE_BILLED} WHERE pi.po_line_id = l.id), 0.0) AS invoiced_qty
+            FROM purchase_order_lines l
+            JOIN purchase_orders po ON po.id = l.po_id
+            JOIN suppliers s ON s.id = po.supplier_id
+            WHERE po.status = 'open' AND (?1 IS NULL OR po.supplier_id = ?1)
+         )
+         WHERE ordered_qty - received_qty > 1e-9
+         ORDER BY po_date, po_no, line_no",
+    ))
+    .bind(supplier_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// GRN lines received but not yet fully billed, oldest receipt first.
+#[tauri::command]
+pub async fn get_pending_grn_report(db: State<'_, Db>, supplier_id: Option<i64>) -> Result<Vec<PendingGrnLine>, String> {
+    sqlx::query_as::<_, PendingGrnLine>(&format!(
+        "SELECT grn_id, grn_no, received_date, supplier_id, supplier_name, po_no, grn_line_id, line_no, part_no,
+                description, received_qty, invoiced_qty, received_qty - invoiced_qty AS pending_qty, price
+         FROM (
+            SELECT g.id AS grn_id, g.grn_no, g.received_date, g.supplier_id, s.name AS supplier_name, po.po_no,
+                   gl.id AS grn_line_id, gl.line_no, gl.part_no, gl.description, gl.qty AS received_qty, l.price,
+                   COALESCE((SELECT SUM(pi.qty) FROM purchase_items pi {LIVE_BILLED} WHERE pi.grn_line_id = gl.id), 0.0) AS invoiced_qty
+            FROM goods_receipt_lines gl
+            JOIN goods_receipts g ON g.id = gl.grn_id
+            JOIN suppliers s ON s.id = g.supplier_id
+            LEFT JOIN purchase_order_lines l ON l.id = gl.po_line_id
+            LEFT JOIN purchase_orders po ON po.id = l.po_id
+            WHERE (?1 IS NULL OR g.supplier_id = ?1)
+         )
+         WHERE received_qty - invoiced_qty > 1e-9
+         ORDER BY received_date, grn_no, line_no",
+    ))
+    .bind(supplier_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Payments
+
+const PAYMENT_SELECT: &str = "SELECT sp.id, sp.supplier_id, s.name AS supplier_name, sp.payment_date, sp.mode, sp.reference,
+        sp.amount, sp.tds_amount, sp.narration,
+        COALESCE(a.allocated, 0.0) AS allocated,
+        ROUND(sp.amount + sp.tds_amount - COALESCE(a.allocated, 0.0), 2) AS unallocated,
+        sp.created_by, sp.created_at
+     FROM supplier_payments sp
+     JOIN suppliers s ON s.id = sp.supplier_id
+     LEFT JOIN (SELECT payment_id, SUM(amount) AS allocated FROM payment_allocations GROUP BY payment_id) a
+       ON a.payment_id = sp.id";
+
+async fn fetch_payment(conn: &mut SqliteConnection, id: i64) -> Result<PaymentDetail, String> {
+    let payment = sqlx::query_as::<_, SupplierPayment>(&format!("{PAYMENT_SELECT} WHERE sp.id = ?1"))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Payment not found".to_string())?;
+    let allocations = sqlx::query_as::<_, PaymentAllocation>(
+        "SELECT pa.id, pa.payment_id, pa.purchase_id, pe.invoice_no, pe.date, pe.invoice_value, pa.amount
+         FROM payment_allocations pa JOIN purchase_entries pe ON pe.id = pa.purchase_id
+         WHERE pa.payment_id = ?1 ORDER BY pe.date, pe.id",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(PaymentDetail { payment, allocations })
+}
+
+/// Payments, newest first. `unallocated_only` keeps those with an advance left.
+#[tauri::command]
+pub async fn get_payments(
+    db: State<'_, Db>,
+    supplier_id: Option<i64>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    unallocated_only: Option<bool>,
+) -> Result<Vec<SupplierPayment>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(format!("SELECT * FROM ({PAYMENT_SELECT}) WHERE 1 = 1"));
+    if let Some(sid) = supplier_id {
+        q.push(" AND supplier_id = ").push_bind(sid);
+    }
+    if let Some(df) = date_from {
+        q.push(" AND payment_date >= ").push_bind(df);
+    }
+    if let Some(dt) = date_to {
+        q.push(" AND payment_date <= ").push_bind(dt);
+    }
+    if unallocated_only.unwrap_or(false) {
+        q.push(" AND unallocated > 0");
+    }
+    q.push(" ORDER BY payment_date DESC, id DESC");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_payment(db: State<'_, Db>, id: i64) -> Result<PaymentDetail, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    fetch_payment(&mut conn, id).await
+}
+
+/// Record a payment to a supplier. Whatever isn't allocated to their bills stays as an advance.
+#[tauri::command]
+pub async fn add_payment(db: State<'_, Db>, payload: PaymentCreate, user_id: Option<i64>) -> Result<PaymentDetail, String> {
+    let payment_date = required(&payload.payment_date, "Payment date")?;
+    let mode = payments::mode(&payload.mode)?;
+    payments::check_amounts(payload.amount, payload.tds_amount)?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let supplier: Option<(bool,)> = sqlx::query_as("SELECT deleted_at IS NOT NULL FROM suppliers WHERE id = ?1")
+        .bind(payload.supplier_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    match supplier {
+        Some((false,)) => {}
+        Some((true,)) => return Err("Supplier is deleted".to_string()),
+        None => return Err("Supplier not found".to_string()),
+    }
+
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO supplier_payments (supplier_id, payment_date, mode, reference, amount, tds_amount, narration, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) RETURNING id",
+    )
+    .bind(payload.supplier_id)
+    .bind(payment_date)
+    .bind(mode)
+    .bind(validation::clean(payload.reference))
+    .bind(payload.amount)
+    .bind(payload.tds_amount)
+    .bind(validation::clean(payload.narration))
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    payments::allocate(&mut tx, id, payload.supplier_id, payload.amount + payload.tds_amount, &payload.allocations).await?;
+    let detail = fetch_payment(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(detail)
+}
+
+/// Change a payment. Given allocations replace the old ones, which is also how an advance is
+/// set against bills later.
+#[tauri::command]
+pub async fn update_payment(db: State<'_, Db>, payload: PaymentUpdate) -> Result<PaymentDetail, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let current = fetch_payment(&mut tx, payload.id).await?.payment;
+    let mode = payload.mode.as_deref().map(payments::mode).transpose()?;
+    let payment_date = payload.payment_date.as_deref().map(|d| required(d, "Payment date")).transpose()?;
+    let amount = payload.amount.unwrap_or(current.amount);
+    let tds_amount = payload.tds_amount.unwrap_or(current.tds_amount);
+    payments::check_amounts(amount, tds_amount)?;
+
+    sqlx::query(
+        "UPDATE supplier_payments SET
+            payment_date = COALESCE(?2, payment_date),
+            mode = COALESCE(?3, mode),
+            reference = CASE WHEN ?4 THEN ?5 ELSE reference END,
+            amount = ?6,
+            tds_amount = ?7,
+            narration = CASE WHEN ?8 THEN ?9 ELSE narration END
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(payment_date)
+    .bind(mode)
+    .bind(payload.reference.is_some())
+    .bind(validation::clean(payload.reference))
+    .bind(amount)
+    .bind(tds_amount)
+    .bind(payload.narration.is_some())
+    .bind(validation::clean(payload.narration))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match payload.allocations {
+        Some(allocations) => {
+            payments::allocate(&mut tx, payload.id, current.supplier_id, amount + tds_amount, &allocations).await?;
+        }
+        None => {
+            let allocated = payments::allocated(&mut tx, payload.id).await?;
+            if allocated > amount + tds_amount + 0.005 {
+                return Err(format!(
+                    "{:.2} of this payment is allocated to bills; reduce the allocations first",
+                    allocated
+                ));
+            }
+        }
+    }
+    let detail = fetch_payment(&mut tx, payload.id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(detail)
+}
+
+/// Delete a payment; the bills it settled are open again.
+#[tauri::command]
+pub async fn delete_payment(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM supplier_payments WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Payments settling an entry, oldest first.
+#[tauri::command]
+pub async fn get_purchase_payments(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchasePayment>, String> {
+    sqlx::query_as::<_, PurchasePayment>(
+        "SELECT pa.id AS allocation_id, sp.id AS payment_id, sp.payment_date, sp.mode, sp.reference, pa.amount
+         FROM payment_allocations pa JOIN supplier_payments sp ON sp.id = pa.payment_id
+         WHERE pa.purchase_id = ?1
+         ORDER BY sp.payment_date, sp.id",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// What is owed to each supplier with open bills or advances, largest balance first.
+#[tauri::command]
+pub async fn get_supplier_outstanding(db: State<'_, Db>, supplier_id: Option<i64>) -> Result<Vec<SupplierOutstanding>, String> {
+    sqlx::query_as::<_, SupplierOutstanding>(
+        "SELECT supplier_id, supplier_name, bills, payable, paid, noted, outstanding, advance, unapplied_notes,
+                ROUND(outstanding - advance - unapplied_notes, 2) AS net_balance
+         FROM (
+            SELECT s.id AS supplier_id, s.name AS supplier_name,
+                   COALESCE(b.bills, 0) AS bills, COALESCE(b.payable, 0.0) AS payable, COALESCE(b.paid, 0.0) AS paid,
+                   COALESCE(b.noted, 0.0) AS noted, COALESCE(b.outstanding, 0.0) AS outstanding,
+                   COALESCE(adv.advance, 0.0) AS advance, COALESCE(un.unapplied, 0.0) AS unapplied_notes
+            FROM suppliers s
+            LEFT JOIN (
+                SELECT supplier_id, SUM(outstanding > 0) AS bills,
+                       ROUND(SUM(invoice_value - tds_value), 2) AS payable,
+                       ROUND(SUM(paid_amount), 2) AS paid,
+                       ROUND(SUM(noted_amount), 2) AS noted,
+                       ROUND(SUM(outstanding), 2) AS outstanding
+                FROM purchase_entries
+                WHERE deleted_at IS NULL AND status <> 'cancelled'
+                GROUP BY supplier_id
+            ) b ON b.supplier_id = s.id
+            LEFT JOIN (
+                SELECT sp.supplier_id,
+                       ROUND(SUM(sp.amount + sp.tds_amount - COALESCE(a.allocated, 0.0)), 2) AS advance
+                FROM supplier_payments sp
+                LEFT JOIN (SELECT payment_id, SUM(amount) AS allocated FROM payment_allocations GROUP BY payment_id) a
+                  ON a.payment_id = sp.id
+                GROUP BY sp.supplier_id
+            ) adv ON adv.supplier_id = s.id
+            LEFT JOIN (
+                SELECT n.supplier_id,
+                       ROUND(SUM(CASE n.kind WHEN 'debit' THEN n.amount ELSE -n.amount END), 2) AS unapplied
+                FROM supplier_notes n
+                WHERE NOT EXISTS (SELECT 1 FROM purchase_entries pe
+                                  WHERE pe.id = n.purchase_id AND pe.deleted_at IS NULL AND pe.status <> 'cancelled')
+                GROUP BY n.supplier_id
+            ) un ON un.supplier_id = s.id
+            WHERE s.merged_into IS NULL AND (?1 IS NULL OR s.id = ?1)
+         )
+         WHERE ?1 IS NOT NULL OR outstanding <> 0 OR advance <> 0 OR unapplied_notes <> 0
+         ORDER BY net_balance DESC, supplier_name",
+    )
+    .bind(supplier_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Payables ageing
+
+/// Unpaid invoices per supplier as of a date, in buckets of days overdue. `bucket_days` are the
+/// upper limits of the closed buckets (30, 60 and 90 by default).
+#[tauri::command]
+pub async fn get_payables_ageing(
+    db: State<'_, Db>,
+    as_of: Option<String>,
+    bucket_days: Option<Vec<i64>>,
+    supplier_id: Option<i64>,
+) -> Result<AgeingReport, String> {
+    let as_of = ageing::as_of(as_of)?;
+    let buckets = ageing::buckets(bucket_days)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    ageing::report(&mut conn, &as_of, supplier_id, buckets).await
+}
+
+/// The invoices behind an ageing cell. `bucket` is a bucket label from the report, or
+/// "not_due"; without it every open invoice comes back.
+#[tauri::command]
+pub async fn get_payables_ageing_invoices(
+    db: State<'_, Db>,
+    as_of: Option<String>,
+    bucket_days: Option<Vec<i64>>,
+    supplier_id: Option<i64>,
+    bucket: Option<String>,
+) -> Result<Vec<AgeingInvoice>, String> {
+    let as_of = ageing::as_of(as_of)?;
+    let buckets = ageing::buckets(bucket_days)?;
+    let wanted = match bucket.as_deref() {
+        None => None,
+        Some("not_due") => Some(None),
+        Some(label) => match buckets.iter().position(|b| b.label == label) {
+            Some(i) => Some(Some(i)),
+            None => return Err(format!("Unknown ageing bucket '{}'", label)),
+        },
+    };
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let rows = ageing::invoices(&mut conn, &as_of, supplier_id, &buckets).await?;
+    Ok(rows.into_iter().filter(|r| wanted.map_or(true, |w| r.bucket == w)).collect())
+}
+
+/// Write the ageing report to `path`: supplier totals on one sheet, the open invoices on another.
+#[tauri::command]
+pub async fn export_payables_ageing(
+    db: State<'_, Db>,
+    path: String,
+    as_of: Option<String>,
+    bucket_days: Option<Vec<i64>>,
+    supplier_id: Option<i64>,
+) -> Result<(), String> {
+    let as_of = ageing::as_of(as_of)?;
+    let buckets = ageing::buckets(bucket_days)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let invoices = ageing::invoices(&mut conn, &as_of, supplier_id, &buckets).await?;
+    let report = ageing::report(&mut conn, &as_of, supplier_id, buckets).await?;
+    drop(conn);
+
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+
+    let sheet = workbook.add_worksheet().set_name("Ageing").map_err(|e| e.to_string())?;
+    sheet
+        .write_string_with_format(0, 0, format!("Payables ageing as of {}", report.as_of), &bold)
+        .map_err(|e| e.to_string())?;
+    let headers = ["Supplier", "Terms (Days)", "Invoices", "Not Due"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(report.buckets.iter().map(|b| b.label.clone()))
+        .chain(["Outstanding", "Advance", "Unapplied Notes", "Net Balance"].into_iter().map(str::to_string));
+    for (i, header) in headers.enumerate() {
+        sheet.write_string_with_format(2, i as u16, header, &bold).map_err(|e| e.to_string())?;
+    }
+    let amounts = |not_due: f64, buckets: &[f64], outstanding: f64, advance: f64, notes: f64, net: f64| {
+        std::iter::once(not_due)
+            .chain(buckets.iter().copied())
+            .chain([outstanding, advance, notes, net])
+            .collect::<Vec<_>>()
+    };
+    let mut row = 3;
+    for r in &report.rows {
+        sheet.write_string(row, 0, &r.supplier_name).map_err(|e| e.to_string())?;
+        if let Some(days) = r.payment_terms_days {
+            sheet.write_number(row, 1, days as f64).map_err(|e| e.to_string())?;
+        }
+        sheet.write_number(row, 2, r.invoices as f64).map_err(|e| e.to_string())?;
+        for (i, v) in amounts(r.not_due, &r.buckets, r.outstanding, r.advance, r.unapplied_notes, r.net_balance).into_iter().enumerate() {
+            sheet.write_number(row, 3 + i as u16, v).map_err(|e| e.to_string())?;
+        }
+        row += 1;
+    }
+    sheet.write_string_with_format(row, 0, "Total", &bold).map_err(|e| e.to_string())?;
+    for (i, v) in amounts(
+        report.not_due,
+        &report.totals,
+        report.outstanding,
+        report.advance,
+        report.unapplied_notes,
+        report.net_balance,
+    )
+    .into_iter()
+    .enumerate()
+    {
+        sheet.write_number_with_format(row, 3 + i as u16, v, &bold).map_err(|e| e.to_string())?;
+    }
+
+    let sheet = workbook.add_worksheet().set_name("Invoices").map_err(|e| e.to_string())?;
+    let headers = ["Supplier", "Invoice No", "Invoice Date", "Due Date", "Days", "Bucket", "Payable", "Paid", "Notes", "Outstanding"];
+    for (i, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, i as u16, *header, &bold).map_err(|e| e.to_string())?;
+    }
+    for (n, inv) in invoices.iter().enumerate() {
+        let row = n as u32 + 1;
+        let bucket = inv.bucket.map_or("Not due", |b| report.buckets[b].label.as_str());
+        sheet.write_string(row, 0, &inv.supplier_name).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 1, &inv.invoice_no).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 2, &inv.date).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 3, inv.due_date.as_deref().unwrap_or("")).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 4, inv.days as f64).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 5, bucket).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 6, inv.payable).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 7, inv.paid).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 8, inv.noted).map_err(|e| e.to_string())?;
+        sheet.write_number(row, 9, inv.outstanding).map_err(|e| e.to_string())?;
+    }
+
+    workbook.save(&path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Supplier ledger
+
+const OPENING_BALANCE_SELECT: &str = "SELECT ob.id, ob.supplier_id, s.name AS supplier_name, ob.fy_start,
+        printf('%d-%02d', CAST(substr(ob.fy_start, 1, 4) AS INTEGER), (CAST(substr(ob.fy_start, 1, 4) AS INTEGER) + 1) % 100) AS fy,
+        ob.amount, ob.narration, ob.created_by, ob.created_at, ob.updated_at
+     FROM supplier_opening_balances ob JOIN suppliers s ON s.id = ob.supplier_id";
+
+const NOTE_SELECT: &str = "SELECT n.id, n.supplier_id, s.name AS supplier_name, n.kind, n.note_no, n.note_date,
+        n.purchase_id, pe.invoice_no, n.amount, n.reason, n.created_by, n.created_at
+     FROM supplier_notes n
+     JOIN suppliers s ON s.id = n.supplier_id
+     LEFT JOIN purchase_entries pe ON pe.id = n.purchase_id";
+
+/// Opening balances by supplier and year, latest year first.
+#[tauri::command]
+pub async fn get_opening_balances(db: State<'_, Db>, supplier_id: Option<i64>) -> Result<Vec<SupplierOpeningBalance>, String> {
+    sqlx::query_as::<_, SupplierOpeningBalance>(&format!(
+        "{OPENING_BALANCE_SELECT} WHERE ?1 IS NULL OR ob.supplier_id = ?1 ORDER BY ob.fy_start DESC, s.name"
+    ))
+    .bind(supplier_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Set a supplier's opening balance for a financial year, replacing any set before.
+#[tauri::command]
+pub async fn set_opening_balance(
+    db: State<'_, Db>,
+    payload: OpeningBalanceSet,
+    user_id: Option<i64>,
+) -> Result<SupplierOpeningBalance, String> {
+    if payload.amount.is_nan() {
+        return Err("Opening balance must be a number".to_string());
+    }
+    let fy_start = chrono::NaiveDate::from_ymd_opt(payload.fy_start_year, 4, 1)
+        .filter(|_| (1900..=9999).contains(&payload.fy_start_year))
+        .ok_or_else(|| format!("{} is not a financial year", payload.fy_start_year))?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM suppliers WHERE id = ?1 AND deleted_at IS NULL")
+        .bind(payload.supplier_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Supplier not found or deleted".to_string());
+    }
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO supplier_opening_balances (supplier_id, fy_start, amount, narration, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (supplier_id, fy_start) DO UPDATE SET
+            amount = excluded.amount,
+            narration = excluded.narration,
+            updated_at = datetime('now')
+         RETURNING id",
+    )
+    .bind(payload.supplier_id)
+    .bind(&fy_start)
+    .bind((payload.amount * 100.0).round() / 100.0)
+    .bind(validation::clean(payload.narration))
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query_as::<_, SupplierOpeningBalance>(&format!("{OPENING_BALANCE_SELECT} WHERE ob.id = ?1"))
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_opening_balance(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM supplier_opening_balances WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+async fn fetch_note(conn: &mut SqliteConnection, id: i64) -> Result<SupplierNote, String> {
+    sqlx::query_as::<_, SupplierNote>(&format!("{NOTE_SELECT} WHERE n.id = ?1"))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Note not found".to_string())
+}
+
+/// Fail if the supplier already has a note of this kind numbered `note_no`, other than `id`.
+async fn check_note_no(conn: &mut SqliteConnection, supplier_id: i64, kind: &str, note_no: &str, id: Option<i64>) -> Result<(), String> {
+    let (taken,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM supplier_notes
+                        WHERE supplier_id = ?1 AND kind = ?2 AND note_no = ?3 AND (?4 IS NULL OR id <> ?4))",
+    )
+    .bind(supplier_id)
+    .bind(kind)
+    .bind(note_no)
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!("{} note number '{}' is already used for this supplier", if kind == "debit" { "Debit" } else { "Credit" }, note_no));
+    }
+    Ok(())
+}
+
+/// Debit and credit notes, newest first.
+#[tauri::command]
+pub async fn get_supplier_notes(
+    db: State<'_, Db>,
+    supplier_id: Option<i64>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<SupplierNote>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(format!("{NOTE_SELECT} WHERE 1 = 1"));
+    if let Some(sid) = supplier_id {
+        q.push(" AND n.supplier_id = ").push_bind(sid);
+    }
+    if let Some(df) = date_from {
+        q.push(" AND n.note_date >= ").push_bind(df);
+    }
+    if let Some(dt) = date_to {
+        q.push(" AND n.note_date <= ").push_bind(dt);
+    }
+    q.push(" ORDER BY n.note_date DESC, n.id DESC");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Record a debit or credit note. A note against an invoice must be against one of the
+/// supplier's live, uncancelled entries, and a debit note can't take it below what is paid.
+#[tauri::command]
+pub async fn add_supplier_note(db: State<'_, Db>, payload: SupplierNoteCreate, user_id: Option<i64>) -> Result<SupplierNote, String> {
+    let kind = ledger::note_kind(&payload.kind)?;
+    let note_no = required(&payload.note_no, "Note number")?;
+    let note_date = ledger::parse_date(&payload.note_date, "Note date")?.format("%Y-%m-%d").to_string();
+    if payload.amount.is_nan() || payload.amount <= 0.0 {
+        return Err("Note amount must be above zero".to_string());
+    }
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let supplier: Option<(bool,)> = sqlx::query_as("SELECT deleted_at IS NOT NULL FROM suppliers WHERE id = ?1")
+        .bind(payload.supplier_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    match supplier {
+        Some((false,)) => {}
+        Some((true,)) => return Err("Supplier is deleted".to_string()),
+        None => return Err("Supplier not found".to_string()),
+    }
+    if let Some(pid) = payload.purchase_id {
+        let (ok,): (bool,) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM purchase_entries
+                            WHERE id = ?1 AND supplier_id = ?2 AND deleted_at IS NULL AND status <> 'cancelled')",
+        )
+        .bind(pid)
+        .bind(payload.supplier_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if !ok {
+            return Err("The invoice is not a live, uncancelled entry of this supplier".to_string());
+        }
+    }
+    check_note_no(&mut tx, payload.supplier_id, &kind, &note_no, None).await?;
+
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO supplier_notes (supplier_id, kind, note_no, note_date, purchase_id, amount, reason, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) RETURNING id",
+    )
+    .bind(payload.supplier_id)
+    .bind(&kind)
+    .bind(&note_no)
+    .bind(note_date)
+    .bind(payload.purchase_id)
+    .bind(payload.amount)
+    .bind(validation::clean(payload.reason))
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some(pid) = payload.purchase_id {
+        payments::check_overpaid(&mut tx, pid).await?;
+    }
+    let note = fetch_note(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(note)
+}
+
+/// Change a note's number, date, amount or reason. Supplier, kind and invoice are fixed.
+#[tauri::command]
+pub async fn update_supplier_note(db: State<'_, Db>, payload: SupplierNoteUpdate) -> Result<SupplierNote, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let current = fetch_note(&mut tx, payload.id).await?;
+    let note_no = payload.note_no.as_deref().map(|n| required(n, "Note number")).transpose()?;
+    let note_date = payload
+        .note_date
+        .as_deref()
+        .map(|d| ledger::parse_date(d, "Note date").map(|d| d.format("%Y-%m-%d").to_string()))
+        .transpose()?;
+    if payload.amount.is_some_and(|a| a.is_nan() || a <= 0.0) {
+        return Err("Note amount must be above zero".to_string());
+    }
+    if let Some(no) = &note_no {
+        check_note_no(&mut tx, current.supplier_id, &current.kind, no, Some(current.id)).await?;
+    }
+
+    sqlx::query(
+        "UPDATE supplier_notes SET
+            note_no = COALESCE(?2, note_no),
+            note_date = COALESCE(?3, note_date),
+            amount = COALESCE(?4, amount),
+            reason = CASE WHEN ?5 THEN ?6 ELSE reason END
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(note_no)
+    .bind(note_date)
+    .bind(payload.amount)
+    .bind(payload.reason.is_some())
+    .bind(validation::clean(payload.reason))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some(pid) = current.purchase_id {
+        payments::check_overpaid(&mut tx, pid).await?;
+    }
+    let note = fetch_note(&mut tx, payload.id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(note)
+}
+
+/// Delete a note. A credit note can't go while payments cover what it added to its invoice.
+#[tauri::command]
+pub async fn delete_supplier_note(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let purchase: Option<(Option<i64>,)> = sqlx::query_as("DELETE FROM supplier_notes WHERE id = ?1 RETURNING purchase_id")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some((Some(pid),)) = purchase {
+        payments::check_overpaid(&mut tx, pid).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(purchase.is_some())
+}
+
+/// Resolve a statement period: the current financial year to date when not given.
+fn ledger_period(date_from: Option<String>, date_to: Option<String>) -> Result<(chrono::NaiveDate, chrono::NaiveDate), String> {
+    let to = match date_to.as_deref() {
+        Some(d) => ledger::parse_date(d, "End date")?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let from = match date_from.as_deref() {
+        Some(d) => ledger::parse_date(d, "Start date")?,
+        None => ledger::fy_start(to),
+    };
+    Ok((from, to))
+}
+
+/// A supplier's ledger for a period: opening balance, then invoices, TDS, notes and payments
+/// in date order with the running balance.
+#[tauri::command]
+pub async fn get_supplier_ledger(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<SupplierLedger, String> {
+    let (from, to) = ledger_period(date_from, date_to)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    ledger::statement(&mut conn, supplier_id, from, to).await
+}
+
+/// Write a supplier's ledger to an Excel workbook at `path`.
+#[tauri::command]
+pub async fn export_supplier_ledger(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    path: String,
+) -> Result<(), String> {
+    let (from, to) = ledger_period(date_from, date_to)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let l = ledger::statement(&mut conn, supplier_id, from, to).await?;
+    drop(conn);
+
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let sheet = workbook.add_worksheet().set_name("Ledger").map_err(|e| e.to_string())?;
+    sheet.write_string_with_format(0, 0, "Statement of account", &bold).map_err(|e| e.to_string())?;
+    sheet.write_string(1, 0, &l.supplier_name).map_err(|e| e.to_string())?;
+    sheet
+        .write_string(2, 0, format!("GSTIN: {}", l.gst_no.as_deref().unwrap_or("-")))
+        .map_err(|e| e.to_string())?;
+    sheet
+        .write_string(3, 0, format!("Period: {} to {}", l.date_from, l.date_to))
+        .map_err(|e| e.to_string())?;
+
+    let headers = ["Date", "Particulars", "Reference", "Debit", "Credit", "Balance"];
+    for (i, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(5, i as u16, *header, &bold).map_err(|e| e.to_string())?;
+    }
+    sheet.write_string(6, 0, &l.date_from).map_err(|e| e.to_string())?;
+    sheet.write_string(6, 1, "Opening balance").map_err(|e| e.to_string())?;
+    sheet.write_string(6, 5, ledger::balance_text(l.opening_balance)).map_err(|e| e.to_string())?;
+    let mut row = 7;
+    for line in &l.lines {
+        sheet.write_string(row, 0, &line.date).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 1, ledger::particulars(line)).map_err(|e| e.to_string())?;
+        sheet.write_string(row, 2, line.reference.as_deref().unwrap_or("")).map_err(|e| e.to_string())?;
+        if line.debit != 0.0 {
+            sheet.write_number(row, 3, line.debit).map_err(|e| e.to_string())?;
+        }
+        if line.credit != 0.0 {
+            sheet.write_number(row, 4, line.credit).map_err(|e| e.to_string())?;
+        }
+        sheet.write_string(row, 5, ledger::balance_text(line.balance)).map_err(|e| e.to_string())?;
+        row += 1;
+    }
+    sheet.write_string_with_format(row, 1, "Total", &bold).map_err(|e| e.to_string())?;
+    sheet.write_number_with_format(row, 3, l.total_debit, &bold).map_err(|e| e.to_string())?;
+    sheet.write_number_with_format(row, 4, l.total_credit, &bold).map_err(|e| e.to_string())?;
+    sheet.write_string(row + 1, 0, &l.date_to).map_err(|e| e.to_string())?;
+    sheet.write_string_with_format(row + 1, 1, "Closing balance", &bold).map_err(|e| e.to_string())?;
+    sheet
+        .write_string_with_format(row + 1, 5, ledger::balance_text(l.closing_balance), &bold)
+        .map_err(|e| e.to_string())?;
+
+    workbook.save(&path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Write a supplier's ledger to a PDF at `path`, for sending to the supplier to confirm.
+#[tauri::command]
+pub async fn export_supplier_ledger_pdf(
+    db: State<'_, Db>,
+    supplier_id: i64,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    path: String,
+) -> Result<(), String> {
+    let (from, to) = ledger_period(date_from, date_to)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let l = ledger::statement(&mut conn, supplier_id, from, to).await?;
+    drop(conn);
+    ledger::write_pdf(&l, &path)
+}
+
+// Custom fields
+
+async fn fetch_custom_field(db: &Db, id: i64) -> Result<CustomField, String> {
+    let row: custom_fields::CustomFieldRow = sqlx::query_as(&format!(
+        "SELECT {} FROM custom_field_definitions WHERE id = ?1",
+        custom_fields::COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Custom field not found".to_string())?;
+    CustomField::try_from(row)
+}
+
+/// Table holding the values of an entity's custom fields.
+fn custom_field_table(entity: &str) -> &'static str {
+    match entity {
+        "supplier" => "suppliers",
+        "item" => "items",
+        _ => "purchase_entries",
+    }
+}
+
+/// Custom field definitions for one entity (supplier, purchase or item), in display order.
+#[tauri::command]
+pub async fn get_custom_fields(
+    db: State<'_, Db>,
+    entity: String,
+    include_inactive: Option<bool>,
+) -> Result<Vec<CustomField>, String> {
+    let entity = custom_fields::entity(&entity)?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    custom_fields::load(&mut conn, &entity, include_inactive.unwrap_or(false)).await
+}
+
+/// Define a custom field. Admins only.
+#[tauri::command]
+pub async fn add_custom_field(db: State<'_, Db>, payload: CustomFieldCreate, user_id: i64) -> Result<CustomField, String> {
+    require_admin(&db, user_id).await?;
+    let entity = custom_fields::entity(&payload.entity)?;
+    let label = required(&payload.label, "Label")?;
+    let key = custom_fields::key(payload.key.as_deref().unwrap_or(&label))?;
+    let field_type = custom_fields::field_type(&payload.field_type)?;
+    let options = custom_fields::options(&field_type, payload.options)?;
+
+    let res = sqlx::query(
+        "INSERT INTO custom_field_definitions (entity, key, label, field_type, options_json, required, position, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6,
+                 COALESCE(?7, (SELECT COALESCE(MAX(position), 0) + 1 FROM custom_field_definitions WHERE entity = ?1)), ?8)",
+    )
+    .bind(&entity)
+    .bind(&key)
+    .bind(label)
+    .bind(field_type)
+    .bind(serde_json::to_string(&options).map_err(|e| e.to_string())?)
+    .bind(payload.required as i64)
+    .bind(payload.position)
+    .bind(user_id)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => {
+            format!("A {} field with key '{}' already exists", entity, key)
+        }
+        _ => e.to_string(),
+    })?;
+    fetch_custom_field(&db, res.last_insert_rowid()).await
+}
+
+/// Change a custom field's label, options, required flag, order or active state. Admins only.
+/// Removing a list option is refused while records still hold it.
+#[tauri::command]
+pub async fn update_custom_field(db: State<'_, Db>, payload: CustomFieldUpdate, user_id: i64) -> Result<CustomField, String> {
+    require_admin(&db, user_id).await?;
+    let current = fetch_custom_field(&db, payload.id).await?;
+    let label = payload.label.as_deref().map(|l| required(l, "Label")).transpose()?;
+    let options = match payload.options {
+        Some(o) => {
+            let options = custom_fields::options(&current.field_type, o)?;
+            let table = custom_field_table(&current.entity);
+            for removed in current.options.iter().filter(|o| !options.iter().any(|n| n.eq_ignore_ascii_case(o))) {
+                let (used,): (i64,) = sqlx::query_as(&format!(
+                    "SELECT COUNT(*) FROM {table} WHERE json_extract(custom_fields, ?1) = ?2",
+                ))
+                .bind(format!("$.\"{}\"", current.key))
+                .bind(removed)
+                .fetch_one(&db.pool())
+                .await
+                .map_err(|e| e.to_string())?;
+                if used > 0 {
+                    return Err(format!("'{}' is still used on {} record(s)", removed, used));
+                }
+            }
+            Some(serde_json::to_string(&options).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+
+    sqlx::query(
+        "UPDATE custom_field_definitions SET
+            label = COALESCE(?2, label),
+            options_json = COALESCE(?3, options_json),
+            required = COALESCE(?4, required),
+            active = COALESCE(?5, active),
+            position = COALESCE(?6, position)
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(label)
+    .bind(options)
+    .bind(payload.required.map(|b| b as i64))
+    .bind(payload.active.map(|b| b as i64))
+    .bind(payload.position)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    fetch_custom_field(&db, payload.id).await
+}
+
+/// Delete a custom field and its stored values. Admins only; deactivate it instead
+/// to keep the values.
+#[tauri::command]
+pub async fn delete_custom_field(db: State<'_, Db>, id: i64, user_id: i64) -> Result<bool, String> {
+    require_admin(&db, user_id).await?;
+    let field = fetch_custom_field(&db, id).await?;
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(&format!(
+        "UPDATE {} SET custom_fields = json_remove(custom_fields, ?1) WHERE json_extract(custom_fields, ?1) IS NOT NULL",
+        custom_field_table(&field.entity)
+    ))
+    .bind(format!("$.\"{}\"", field.key))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let res = sqlx::query("DELETE FROM custom_field_definitions WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+// Dimensions
+
+const DIMENSION_COLUMNS: &str = "id, kind, code, name, active, created_at";
+
+async fn fetch_dimension(db: &Db, id: i64) -> Result<Dimension, String> {
+    sqlx::query_as::<_, Dimension>(&format!("SELECT {DIMENSION_COLUMNS} FROM dimensions WHERE id = ?1"))
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Dimension not found".to_string())
+}
+
+fn dimension_write_error(e: sqlx::Error, code: &str) -> String {
+    match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => format!("Code '{}' already exists", code),
+        _ => e.to_string(),
+    }
+}
+
+/// Cost centers, departments and projects, optionally of one kind.
+#[tauri::command]
+pub async fn get_dimensions(
+    db: State<'_, Db>,
+    kind: Option<String>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<Dimension>, String> {
+    let kind = kind.as_deref().map(dimensions::kind).transpose()?;
+    sqlx::query_as::<_, Dimension>(&format!(
+        "SELECT {DIMENSION_COLUMNS} FROM dimensions
+         WHERE (?1 IS NULL OR kind = ?1) AND (?2 OR active = 1)
+         ORDER BY kind, code",
+    ))
+    .bind(kind)
+    .bind(include_inactive.unwrap_or(false))
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_dimension(db: State<'_, Db>, payload: DimensionCreate) -> Result<Dimension, String> {
+    let kind = dimensions::kind(&payload.kind)?;
+    let code = required(&payload.code, "Code")?.to_uppercase();
+    let name = required(&payload.name, "Name")?;
+    let res = sqlx::query("INSERT INTO dimensions (kind, code, name) VALUES (?1, ?2, ?3)")
+        .bind(kind)
+        .bind(&code)
+        .bind(name)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| dimension_write_error(e, &code))?;
+    fetch_dimension(&db, res.last_insert_rowid()).await
+}
+
+/// Rename or (de)activate a dimension. Inactive ones stay on existing entries but
+/// cannot be picked for new splits.
+#[tauri::command]
+pub async fn update_dimension(db: State<'_, Db>, payload: DimensionUpdate) -> Result<Dimension, String> {
+    let code = payload.code.as_deref().map(|c| required(c, "Code").map(|c| c.to_uppercase())).transpose()?;
+    let name = payload.name.as_deref().map(|n| required(n, "Name")).transpose()?;
+    let res = sqlx::query(
+        "UPDATE dimensions SET code = COALESCE(?2, code), name = COALESCE(?3, name), active = COALESCE(?4, active)
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(&code)
+    .bind(name)
+    .bind(payload.active.map(|b| b as i64))
+    .execute(&db.pool())
+    .await
+    .map_err(|e| dimension_write_error(e, code.as_deref().unwrap_or_default()))?;
+    if res.rows_affected() == 0 {
+        return Err("Dimension not found".to_string());
+    }
+    fetch_dimension(&db, payload.id).await
+}
+
+/// Delete a dimension no purchase is tagged with. Ones in use can only be deactivated.
+#[tauri::command]
+pub async fn delete_dimension(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let dimension = fetch_dimension(&db, id).await?;
+    let (used,): (i64,) =
+        sqlx::query_as("SELECT COUNT(DISTINCT purchase_id) FROM purchase_allocations WHERE dimension_id = ?1")
+            .bind(id)
+            .fetch_one(&db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+    if used > 0 {
+        return Err(format!(
+            "'{}' is used on {} purchase(s); deactivate it instead",
+            dimension.code, used
+        ));
+    }
+    let (rules,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM approval_rules WHERE dimension_id = ?1")
+        .bind(id)
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    if rules > 0 {
+        return Err(format!("'{}' is used by {} approval rule(s)", dimension.code, rules));
+    }
+
+    let res = sqlx::query("DELETE FROM dimensions WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Entry-level and line-level splits of a purchase; entry-level ones first.
+#[tauri::command]
+pub async fn get_purchase_allocations(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchaseAllocation>, String> {
+    sqlx::query_as::<_, PurchaseAllocation>(
+        "SELECT pa.id, pa.purchase_id, pa.item_id, pa.dimension_id, d.kind, d.code, d.name, pa.percent
+         FROM purchase_allocations pa JOIN dimensions d ON d.id = pa.dimension_id
+         WHERE pa.purchase_id = ?1
+         ORDER BY pa.item_id IS NOT NULL, pa.item_id, d.kind, pa.percent DESC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Replace the splits of a purchase (`item_id` None) or of one of its lines.
+/// An empty list removes them.
+#[tauri::command]
+pub async fn set_purchase_allocations(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    item_id: Option<i64>,
+    allocations: Vec<AllocationInput>,
+    user_id: Option<i64>,
+) -> Result<Vec<PurchaseAllocation>, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let (live,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM purchase_entries WHERE id = ?1 AND deleted_at IS NULL)",
+    )
+    .bind(purchase_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if !live {
+        return Err("Purchase not found or deleted".to_string());
+    }
+    workflow::check_editable(&mut tx, purchase_id).await?;
+    if let Some(item_id) = item_id {
+        let (owned,): (bool,) =
+            sqlx::query_as("SELECT EXISTS (SELECT 1 FROM purchase_items WHERE id = ?1 AND purchase_id = ?2)")
+                .bind(item_id)
+                .bind(purchase_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        if !owned {
+            return Err("Item not found on this purchase".to_string());
+        }
+    }
+    let before = approvals::fingerprint(&mut tx, purchase_id).await?;
+    dimensions::replace(&mut tx, purchase_id, item_id, &allocations).await?;
+    approvals::reopen_if_changed(&mut tx, purchase_id, before, user_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_purchase_allocations(db, purchase_id).await
+}
+
+// Item master
+
+const ITEM_COLUMNS: &str =
+    "id, part_no, description, hsn_code, gst_rate, unit, supplier_id, active, created_at, updated_at, custom_fields";
+
+fn item_write_error(e: sqlx::Error, part_no: &str) -> String {
+    match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => {
+            format!("Part number '{}' already exists in the item master", part_no)
+        }
+        _ => e.to_string(),
+    }
+}
+
+fn required(value: &str, what: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("{} is required", what));
+    }
+    Ok(value.to_string())
+}
+
+async fn fetch_item(db: &Db, id: i64) -> Result<Item, String> {
+    sqlx::query_as::<_, Item>(&format!("SELECT {ITEM_COLUMNS} FROM items WHERE id = ?1"))
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())
+}
+
+/// Item master by part number. `search` matches part number, description or HSN code.
+#[tauri::command]
+pub async fn get_master_items(
+    db: State<'_, Db>,
+    search: Option<String>,
+    supplier_id: Option<i64>,
+    include_inactive: Option<bool>,
+    custom_fields: Option<Vec<CustomFieldFilter>>,
+) -> Result<Vec<Item>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(format!("SELECT {ITEM_COLUMNS} FROM items WHERE 1 = 1"));
+    if !include_inactive.unwrap_or(false) {
+        q.push(" AND active = 1");
+    }
+    if let Some(sid) = supplier_id {
+        q.push(" AND supplier_id = ").push_bind(sid);
+    }
+    if let Some(term) = search.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        let like = format!("%{}%", term);
+        q.push(" AND (part_no LIKE ").push_bind(like.clone());
+        q.push(" OR description LIKE ").push_bind(like.clone());
+        q.push(" OR hsn_code LIKE ").push_bind(like).push(")");
+    }
+    let custom: Vec<&CustomFieldFilter> = custom_fields.iter().flatten().collect();
+    custom_fields::push_filters(&mut q, "custom_fields", &custom);
+    q.push(" ORDER BY part_no");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_master_item(db: State<'_, Db>, id: i64) -> Result<Item, String> {
+    fetch_item(&db, id).await
+}
+
+#[tauri::command]
+pub async fn add_master_item(db: State<'_, Db>, payload: ItemCreate) -> Result<Item, String> {
+    let part_no = required(&payload.part_no, "Part number")?;
+    let description = required(&payload.description, "Description")?;
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let unit = uom::resolve(&mut conn, payload.unit).await?;
+    let custom = custom_fields::check(&mut conn, "item", payload.custom_fields, None).await?;
+
+    let res = sqlx::query(
+        "INSERT INTO items (part_no, description, hsn_code, gst_rate, unit, supplier_id, active, custom_fields)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(&part_no)
+    .bind(description)
+    .bind(payload.hsn_code)
+    .bind(payload.gst_rate)
+    .bind(unit)
+    .bind(payload.supplier_id)
+    .bind(payload.active.unwrap_or(true) as i64)
+    .bind(Json(custom))
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| item_write_error(e, &part_no))?;
+    drop(conn);
+
+    fetch_item(&db, res.last_insert_rowid()).await
+}
+
+/// Update an item. Renaming the part number also renames it on the lines of live entries, so it
+/// is refused while approved, posted or cancelled entries use the part.
+#[tauri::command]
+pub async fn update_master_item(db: State<'_, Db>, payload: ItemUpdate, user_id: Option<i64>) -> Result<Item, String> {
+    let current = fetch_item(&db, payload.id).await?;
+    let part_no = match &payload.part_no {
+        Some(p) => required(p, "Part number")?,
+        None => current.part_no.clone(),
+    };
+    let description = payload.description.as_deref().map(|d| required(d, "Description")).transpose()?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let unit = uom::resolve(&mut tx, payload.unit).await?;
+    let custom = match payload.custom_fields {
+        Some(given) => Some(Json(custom_fields::check(&mut tx, "item", Some(given), Some(current.custom_fields.0)).await?)),
+        None => None,
+    };
+    sqlx::query(
+        "UPDATE items SET
+            part_no = ?2,
+            description = COALESCE(?3, description),
+            hsn_code = COALESCE(?4, hsn_code),
+            gst_rate = COALESCE(?5, gst_rate),
+            unit = COALESCE(?6, unit),
+            supplier_id = COALESCE(?7, supplier_id),
+            active = COALESCE(?8, active),
+            custom_fields = COALESCE(?9, custom_fields),
+            updated_at = datetime('now')
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(&part_no)
+    .bind(description)
+    .bind(payload.hsn_code)
+    .bind(payload.gst_rate)
+    .bind(unit)
+    .bind(payload.supplier_id)
+    .bind(payload.active.map(|b| b as i64))
+    .bind(custom)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| item_write_error(e, &part_no))?;
+
+    if part_no != current.part_no {
+        let using: Vec<(i64, String, bool)> = sqlx::query_as(
+            "SELECT DISTINCT pe.id, pe.status, pe.deleted_at IS NOT NULL
+             FROM purchase_items pi JOIN purchase_entries pe ON pe.id = pi.purchase_id
+             WHERE pi.part_no = ?1 COLLATE NOCASE
+             ORDER BY pe.id",
+        )
+        .bind(&current.part_no)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        // Posted and cancelled entries are locked, and approved ones would have to be approved again
+        let blocked = using
+            .iter()
+            .filter(|(_, status, deleted)| workflow::LOCKED.contains(&status.as_str()) || (status == "approved" && !deleted))
+            .count();
+        if blocked > 0 {
+            return Err(format!(
+                "Part number '{}' is on {} approved, posted or cancelled purchase(s) and can't be renamed; add the new part number as its own item instead",
+                current.part_no, blocked
+            ));
+        }
+        // Deleted entries keep their lines as they were
+        let touched = using.into_iter().filter(|(_, _, deleted)| !deleted).map(|(id, _, _)| id);
+        let note = format!("Part number renamed from '{}' to '{}'", current.part_no, part_no);
+        for purchase_id in touched {
+            let before = approvals::fingerprint(&mut tx, purchase_id).await?;
+            sqlx::query("UPDATE purchase_items SET part_no = ?3 WHERE purchase_id = ?1 AND part_no = ?2 COLLATE NOCASE")
+                .bind(purchase_id)
+                .bind(&current.part_no)
+                .bind(&part_no)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            approvals::reopen_if_changed(&mut tx, purchase_id, before, user_id).await?;
+            revisions::record(&mut tx, purchase_id, "items", Some(note.clone()), user_id).await?;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    fetch_item(&db, payload.id).await
+}
+
+/// Delete an item that no purchase line uses. Items in use can only be deactivated.
+#[tauri::command]
+pub async fn delete_master_item(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let item = fetch_item(&db, id).await?;
+    let (used,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM purchase_items WHERE part_no = ?1 COLLATE NOCASE")
+        .bind(&item.part_no)
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    if used > 0 {
+        return Err(format!(
+            "Part number '{}' is used on {} purchase line(s); deactivate it instead",
+            item.part_no, used
+        ));
+    }
+
+    let res = sqlx::query("DELETE FROM items WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Line suggestions for the purchase form, ranked by match, supplier and recency,
+/// with the last price, unit and GST rate to pre-fill the line.
+#[tauri::command]
+pub async fn suggest_items(
+    db: State<'_, Db>,
+    query: String,
+    supplier_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<ItemSuggestion>, String> {
+    let limit = limit.unwrap_or(8).clamp(1, 50) as usize;
+    suggest::suggest(&db.pool(), &query, supplier_id, limit).await
+}
+
+/// One-time import of the item master the frontend kept in localStorage.
+/// Existing part numbers are updated; entries without a part number are skipped.
+#[tauri::command]
+pub async fn import_master_items(db: State<'_, Db>, items: Vec<LegacyItem>) -> Result<ItemImportResult, String> {
+    let mut result = ItemImportResult { imported: 0, updated: 0, skipped: Vec::new() };
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+
+    for it in items {
+        let part_no = match it.part_no.as_deref().map(str::trim) {
+            Some(p) if !p.is_empty() => p.to_string(),
+            _ => {
+                result.skipped.push(format!("'{}' has no part number", it.description));
+                continue;
+            }
+        };
+        if it.description.trim().is_empty() {
+            result.skipped.push(format!("'{}' has no description", part_no));
+            continue;
+        }
+        // Suppliers may have been removed since the entry was saved in the browser
+        let supplier_id = match it.supplier_id {
+            Some(sid) => sqlx::query_as::<_, (i64,)>("SELECT id FROM suppliers WHERE id = ?1")
+                .bind(sid)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|(id,)| id),
+            None => None,
+        };
+        let gst_rate = it.gst_percent.filter(|g| *g > 0.0);
+        let active = it.active.unwrap_or(true) as i64;
+
+        let existing: Option<(i64,)> = sqlx::query_as("SELECT id FROM items WHERE part_no = ?1")
+            .bind(&part_no)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        match existing {
+            Some((id,)) => {
+                sqlx::query(
+                    "UPDATE items SET description = ?2, gst_rate = COALESCE(?3, gst_rate),
+                        supplier_id = COALESCE(?4, supplier_id), active = ?5, updated_at = datetime('now')
+                     WHERE id = ?1",
+                )
+                .bind(id)
+                .bind(it.description.trim())
+                .bind(gst_rate)
+                .bind(supplier_id)
+                .bind(active)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                result.updated += 1;
+            }
+            None => {
+                sqlx::query(
+                    "INSERT INTO items (part_no, description, gst_rate, supplier_id, active) VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .bind(&part_no)
+                .bind(it.description.trim())
+                .bind(gst_rate)
+                .bind(supplier_id)
+                .bind(active)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                result.imported += 1;
+            }
+        }
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+// Units of measure
+
+fn uom_code(code: &str) -> Result<String, String> {
+    let code = code.trim().to_uppercase();
+    if code.is_empty() || code.len() > 10 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Unit code must be 1-10 letters or digits".to_string());
+    }
+    Ok(code)
+}
+
+fn uom_write_error(e: sqlx::Error, what: &str) -> String {
+    match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => format!("{} already exists", what),
+        sqlx::Error::Database(d) if d.message().contains("FOREIGN KEY") => "Unknown unit or item".to_string(),
+        _ => e.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_uoms(db: State<'_, Db>, include_inactive: Option<bool>) -> Result<Vec<Uom>, String> {
+    sqlx::query_as::<_, Uom>(
+        "SELECT code, name, uqc, active FROM uoms WHERE (?1 OR active = 1) ORDER BY code",
+    )
+    .bind(include_inactive.unwrap_or(false))
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_uom(db: State<'_, Db>, payload: UomCreate) -> Result<Uom, String> {
+    let code = uom_code(&payload.code)?;
+    let name = required(&payload.name, "Unit name")?;
+    let uqc = match payload.uqc.as_deref() {
+        Some(u) => uom_code(u)?,
+        None => code.clone(),
+    };
+    sqlx::query("INSERT INTO uoms (code, name, uqc) VALUES (?1, ?2, ?3)")
+        .bind(&code)
+        .bind(name)
+        .bind(uqc)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| uom_write_error(e, &format!("Unit '{}'", code)))?;
+
+    sqlx::query_as::<_, Uom>("SELECT code, name, uqc, active FROM uoms WHERE code = ?1")
+        .bind(&code)
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_uom(db: State<'_, Db>, payload: UomUpdate) -> Result<Uom, String> {
+    let name = payload.name.as_deref().map(|n| required(n, "Unit name")).transpose()?;
+    let uqc = payload.uqc.as_deref().map(uom_code).transpose()?;
+    let res = sqlx::query(
+        "UPDATE uoms SET name = COALESCE(?2, name), uqc = COALESCE(?3, uqc), active = COALESCE(?4, active)
+         WHERE code = ?1",
+    )
+    .bind(payload.code.trim())
+    .bind(name)
+    .bind(uqc)
+    .bind(payload.active.map(|b| b as i64))
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Unit not found".to_string());
+    }
+
+    sqlx::query_as::<_, Uom>("SELECT code, name, uqc, active FROM uoms WHERE code = ?1")
+        .bind(payload.code.trim())
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_uom_aliases(db: State<'_, Db>) -> Result<Vec<UomAlias>, String> {
+    sqlx::query_as::<_, UomAlias>("SELECT alias, uom_code FROM uom_aliases ORDER BY uom_code, alias")
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Map a spelling to a unit, replacing any earlier mapping. Run `normalize_units` afterwards
+/// to apply it to stored lines.
+#[tauri::command]
+pub async fn set_uom_alias(db: State<'_, Db>, alias: String, uom_code: String) -> Result<UomAlias, String> {
+    let alias = alias.trim().to_string();
+    if alias.is_empty() {
+        return Err("Alias is required".to_string());
+    }
+    sqlx::query(
+        "INSERT INTO uom_aliases (alias, uom_code) VALUES (?1, (SELECT code FROM uoms WHERE code = ?2))
+         ON CONFLICT(alias) DO UPDATE SET uom_code = excluded.uom_code",
+    )
+    .bind(&alias)
+    .bind(uom_code.trim())
+    .execute(&db.pool())
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(d) if d.message().contains("NOT NULL") => format!("Unit '{}' not found", uom_code.trim()),
+        _ => uom_write_error(e, &format!("Alias '{}'", alias)),
+    })?;
+
+    sqlx::query_as::<_, UomAlias>("SELECT alias, uom_code FROM uom_aliases WHERE alias = ?1")
+        .bind(&alias)
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_uom_alias(db: State<'_, Db>, alias: String) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM uom_aliases WHERE alias = ?1")
+        .bind(alias.trim())
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// General conversions, plus the item's own when `item_id` is given.
+#[tauri::command]
+pub async fn get_uom_conversions(db: State<'_, Db>, item_id: Option<i64>) -> Result<Vec<UomConversion>, String> {
+    sqlx::query_as::<_, UomConversion>(
+        "SELECT id, item_id, from_code, to_code, factor FROM uom_conversions
+         WHERE item_id IS NULL OR item_id = ?1
+         ORDER BY item_id IS NULL, from_code, to_code",
+    )
+    .bind(item_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Add or change a conversion, e.g. `{ item_id, from_code: "BOX", to_code: "NOS", factor: 12 }`.
+#[tauri::command]
+pub async fn set_uom_conversion(db: State<'_, Db>, payload: UomConversionInput) -> Result<UomConversion, String> {
+    if payload.factor.is_nan() || payload.factor <= 0.0 {
+        return Err("Conversion factor must be greater than zero".to_string());
+    }
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let from = uom::resolve(&mut conn, Some(payload.from_code)).await?.ok_or("From unit is required")?;
+    let to = uom::resolve(&mut conn, Some(payload.to_code)).await?.ok_or("To unit is required")?;
+    if from == to {
+        return Err("A unit cannot be converted to itself".to_string());
+    }
+
+    sqlx::query(
+        "INSERT INTO uom_conversions (item_id, from_code, to_code, factor) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(COALESCE(item_id, 0), from_code COLLATE NOCASE, to_code COLLATE NOCASE)
+         DO UPDATE SET factor = excluded.factor",
+    )
+    .bind(payload.item_id)
+    .bind(&from)
+    .bind(&to)
+    .bind(payload.factor)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| uom_write_error(e, "Conversion"))?;
+
+    sqlx::query_as::<_, UomConversion>(
+        "SELECT id, item_id, from_code, to_code, factor FROM uom_conversions
+         WHERE item_id IS ?1 AND from_code = ?2 AND to_code = ?3",
+    )
+    .bind(payload.item_id)
+    .bind(&from)
+    .bind(&to)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_uom_conversion(db: State<'_, Db>, id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM uom_conversions WHERE id = ?1")
+        .bind(id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Rewrite free-text units on purchase lines and items to unit codes. With `dry_run`
+/// nothing is changed; the result lists what would map and what has no match yet.
+#[tauri::command]
+pub async fn normalize_units(db: State<'_, Db>, dry_run: Option<bool>) -> Result<UnitNormalization, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+
+    // Exact codes are already normalised; everything else is a candidate
+    let raws: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT unit, SUM(n) FROM (
+            SELECT unit, COUNT(*) AS n FROM purchase_items GROUP BY unit
+            UNION ALL
+            SELECT unit, COUNT(*) AS n FROM items GROUP BY unit
+         )
+         WHERE unit IS NOT NULL AND unit COLLATE BINARY NOT IN (SELECT code FROM uoms)
+         GROUP BY unit COLLATE BINARY
+         ORDER BY unit",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut result = UnitNormalization { dry_run, mapped: Vec::new(), unmapped: Vec::new(), updated: 0 };
+    for (raw, lines) in raws {
+        let code = match uom::lookup(&mut tx, Some(&raw)).await? {
+            uom::Lookup::Unknown(_) => {
+                result.unmapped.push(UnitMapping { raw, code: None, lines });
+                continue;
+            }
+            uom::Lookup::Found(code) => Some(code),
+            uom::Lookup::Blank => None,
+        };
+        if !dry_run {
+            for table in ["purchase_items", "items"] {
+                let res = sqlx::query(&format!("UPDATE {table} SET unit = ?2 WHERE unit = ?1 COLLATE BINARY"))
+                    .bind(&raw)
+                    .bind(&code)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                result.updated += res.rows_affected() as i64;
+            }
+        }
+        result.mapped.push(UnitMapping { raw, code, lines });
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+#[derive(sqlx::FromRow)]
+struct QuantityLine {
+    part_no: Option<String>,
+    description: String,
+    qty: f64,
+    unit: Option<String>,
+    item_id: Option<i64>,
+    master_unit: Option<String>,
+}
+
+/// Purchased quantities per item, converted to the item's base unit. Lines whose unit
+/// has no conversion are counted separately rather than mixed into the total.
+#[tauri::command]
+pub async fn get_quantity_report(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    filters: Option<PurchaseFilters>,
+) -> Result<Vec<QuantitySummary>, String> {
+    let f = merge_date_range(filters, date_from, date_to);
+
+    let mut q = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT pi.part_no, pi.description, pi.qty, pi.unit, it.id AS item_id, it.unit AS master_unit
+         FROM purchase_items pi
+         LEFT JOIN items it ON it.part_no = pi.part_no
+         WHERE pi.purchase_id IN (SELECT pe.id{PURCHASE_FROM}",
+    ));
+    push_purchase_filters(&mut q, &f)?;
+    q.push(") ORDER BY pi.id");
+    let rows: Vec<QuantityLine> = q
+        .build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let converter = uom::Converter::load(&mut conn).await?;
+
+    // Group by part number, or by description for lines without one
+    let mut groups: Vec<(String, Vec<QuantityLine>)> = Vec::new();
+    let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for row in rows {
+        let key = match row.part_no.as_deref().filter(|p| !p.trim().is_empty()) {
+            Some(p) => format!("p:{}", p.to_lowercase()),
+            None => format!("d:{}", row.description.trim().to_lowercase()),
+        };
+        let i = *index.entry(key.clone()).or_insert_with(|| {
+            groups.push((key, Vec::new()));
+            groups.len() - 1
+        });
+        groups[i].1.push(row);
+    }
+
+    let mut out = Vec::with_capacity(groups.len());
+    for (_, lines) in groups {
+        let first = &lines[0];
+        let item_id = first.item_id;
+        let base_unit = first.master_unit.clone().or_else(|| {
+            let mut counts: Vec<(String, usize)> = Vec::new();
+            for u in lines.iter().filter_map(|l| l.unit.clone()) {
+                match counts.iter_mut().find(|(c, _)| *c == u) {
+                    Some((_, n)) => *n += 1,
+                    None => counts.push((u, 1)),
+                }
+            }
+            counts.into_iter().max_by_key(|(_, n)| *n).map(|(u, _)| u)
+        });
+
+        let mut s = QuantitySummary {
+            part_no: first.part_no.clone(),
+            description: first.description.clone(),
+            base_unit: base_unit.clone(),
+            total_qty: 0.0,
+            lines: lines.len() as i64,
+            unconverted_lines: 0,
+            unconverted_units: Vec::new(),
+        };
+        for line in &lines {
+            let factor = match (&line.unit, &base_unit) {
+                (Some(u), Some(b)) => converter.factor(item_id, u, b),
+                (None, None) => Some(1.0),
+                _ => None,
+            };
+            match factor {
+                Some(f) => s.total_qty += line.qty * f,
+                None => {
+                    s.unconverted_lines += 1;
+                    let u = line.unit.clone().unwrap_or_else(|| "(none)".to_string());
+                    if !s.unconverted_units.contains(&u) {
+                        s.unconverted_units.push(u);
+                    }
+                }
+            }
+        }
+        out.push(s);
+    }
+    out.sort_by(|a, b| a.part_no.cmp(&b.part_no).then_with(|| a.description.cmp(&b.description)));
+    Ok(out)
+}
+
+// Prices
+
+/// Every purchase of `part_no`, newest first, optionally for one supplier or period.
+/// Cancelled entries are left out.
+#[tauri::command]
+pub async fn get_price_history(
+    db: State<'_, Db>,
+    part_no: String,
+    supplier_id: Option<i64>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<PriceHistoryEntry>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(
+        "SELECT pe.id AS purchase_id, pe.invoice_no, pe.date, pe.supplier_id, s.name AS supplier_name,
+                pi.part_no, pi.description, pi.qty, pi.unit, pi.price
+         FROM purchase_items pi
+         JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE pe.deleted_at IS NULL AND pe.status <> 'cancelled' AND pi.part_no = ",
+    );
+    q.push_bind(part_no.trim().to_string()).push(" COLLATE NOCASE");
+    if let Some(sid) = supplier_id {
+        q.push(" AND pe.supplier_id = ").push_bind(sid);
+    }
+    if let Some(df) = date_from {
+        q.push(" AND pe.date >= ").push_bind(df);
+    }
+    if let Some(dt) = date_to {
+        q.push(" AND pe.date <= ").push_bind(dt);
+    }
+    q.push(" ORDER BY pe.date DESC, pe.id DESC, pi.id DESC");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check lines before saving so the form can warn. `purchase_id` excludes the
+/// entry being edited from its own history.
+#[tauri::command]
+pub async fn check_price_variances(
+    db: State<'_, Db>,
+    date: String,
+    items: Vec<PurchaseItemPayload>,
+    purchase_id: Option<i64>,
+) -> Result<Vec<PriceVariance>, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let settings = pricing::load_settings(&mut conn).await?;
+    let lines: Vec<pricing::Line> = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, it)| {
+            let part_no = it.part_no.filter(|p| !p.trim().is_empty())?;
+            Some(pricing::Line { line_no: i as i64 + 1, part_no, description: it.description, price: it.price })
+        })
+        .collect();
+    pricing::check(&mut conn, &date, purchase_id, &lines, &settings).await
+}
+
+/// Variances flagged when the purchase was last saved.
+#[tauri::command]
+pub async fn get_purchase_price_variances(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PriceVariance>, String> {
+    sqlx::query_as::<_, PriceVariance>(
+        "SELECT line_no, part_no, description, price, last_price, avg_price, samples,
+                deviation_last_pct, deviation_avg_pct, threshold_pct
+         FROM price_variances WHERE purchase_id = ?1 ORDER BY line_no",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// All price exceptions on live purchases dated in the period, largest deviation first.
+#[tauri::command]
+pub async fn get_price_variance_report(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    supplier_id: Option<i64>,
+) -> Result<Vec<PriceVarianceException>, String> {
+    let mut q = QueryBuilder::<Sqlite>::new(
+        "SELECT pv.id, pv.purchase_id, pe.invoice_no, pe.date, pe.supplier_id, s.name AS supplier_name,
+                pv.line_no, pv.part_no, pv.description, pv.price, pv.last_price, pv.avg_price, pv.samples,
+                pv.deviation_last_pct, pv.deviation_avg_pct, pv.threshold_pct, pv.created_at
+         FROM price_variances pv
+         JOIN purchase_entries pe ON pe.id = pv.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE pe.deleted_at IS NULL",
+    );
+    if let Some(df) = date_from {
+        q.push(" AND pe.date >= ").push_bind(df);
+    }
+    if let Some(dt) = date_to {
+        q.push(" AND pe.date <= ").push_bind(dt);
+    }
+    if let Some(sid) = supplier_id {
+        q.push(" AND pe.supplier_id = ").push_bind(sid);
+    }
+    q.push(" ORDER BY MAX(ABS(pv.deviation_last_pct), ABS(pv.deviation_avg_pct)) DESC, pe.date DESC");
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_price_variance_settings(db: State<'_, Db>) -> Result<PriceVarianceSettings, String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    pricing::load_settings(&mut conn).await
+}
+
+/// Change the threshold or window. Already stored variances keep the settings they were flagged with.
+#[tauri::command]
+pub async fn update_price_variance_settings(
+    db: State<'_, Db>,
+    payload: PriceVarianceSettingsUpdate,
+) -> Result<PriceVarianceSettings, String> {
+    if payload.threshold_pct.map(|t| t <= 0.0).unwrap_or(false) {
+        return Err("Variance threshold must be above 0%".to_string());
+    }
+    if payload.window_size.map(|w| w < 1).unwrap_or(false) {
+        return Err("Rolling average needs at least one purchase".to_string());
+    }
+
+    sqlx::query(
+        "UPDATE price_variance_settings SET
+            enabled = COALESCE(?1, enabled),
+            threshold_pct = COALESCE(?2, threshold_pct),
+            window_size = COALESCE(?3, window_size)
+         WHERE id = 1",
+    )
+    .bind(payload.enabled.map(|b| if b { 1_i64 } else { 0_i64 }))
+    .bind(payload.threshold_pct)
+    .bind(payload.window_size)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    pricing::load_settings(&mut conn).await
+}
+
+// Auth
+
+#[tauri::command]
+pub async fn register(db: State<'_, Db>, payload: UserCreate) -> Result<User, String> {
+    if payload.username.is_empty() {
+        return Err("Username cannot be empty".to_string());
+    }
+    if payload.password.is_empty() {
+        return Err("Password cannot be empty".to_string());
+    }
+
+    let existing_user: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE username = ?1")
+        .bind(&payload.username)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if existing_user.is_some() {
+        return Err("Username is already taken".to_string());
+    }
+
+    // TODO: hash password
+    let password_hash = payload.password;
+    let role = payload.role.unwrap_or_else(|| "user".to_string());
+
+    let rec: (i64,) = sqlx::query_as(
+        "INSERT INTO users (username, password_hash, role)
+         VALUES (?1, ?2, ?3)
+         RETURNING id",
+    )
+    .bind(&payload.username)
+    .bind(&password_hash)
+    .bind(&role)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, username, password_hash, role, created_at FROM users WHERE id = ?1",
+    )
+    .bind(rec.0)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(user)
+}
+
+#[tauri::command]
+pub async fn login(db: State<'_, Db>, payload: LoginPayload) -> Result<LoginResponse, String> {
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, username, password_hash, role, created_at FROM users WHERE username = ?1",
+    )
+    .bind(&payload.username)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Invalid username or password".to_string())?;
+
+    // TODO: verify password hash
+    if user.password_hash != payload.password {
+        return Err("Invalid username or password".to_string());
+    }
+
+    // TODO: generate a real JWT token
+    let token = "fake-jwt-token".to_string();
+
+    Ok(LoginResponse { user, token })
+}
+
+// Saved views
+
+const SAVED_VIEW_SELECT: &str = "SELECT v.id, v.user_id, u.username AS owner_name, v.name, v.filters_json, v.sort_by, v.sort_dir,
+        v.columns_json, v.page_size, v.is_shared, (d.view_id IS NOT NULL) AS is_default, v.created_at, v.updated_at
+     FROM saved_views v
+     JOIN users u ON u.id = v.user_id
+     LEFT JOIN saved_view_defaults d ON d.view_id = v.id AND d.user_id = ?1
+     WHERE (v.user_id = ?1 OR v.is_shared = 1)";
+
+#[derive(sqlx::FromRow)]
+struct SavedViewRow {
+    id: i64,
+    user_id: i64,
+    owner_name: String,
+    name: String,
+    filters_json: String,
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+    columns_json: Option<String>,
+    page_size: Option<i64>,
+    is_shared: i64,
+    is_default: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+impl TryFrom<SavedViewRow> for SavedView {
+    type Error = String;
+
+    fn try_from(r: SavedViewRow) -> Result<Self, String> {
+        let filters = serde_json::from_str(&r.filters_json)
+            .map_err(|e| format!("Saved view '{}' has unreadable filters: {}", r.name, e))?;
+        let columns = r
+            .columns_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| format!("Saved view '{}' has unreadable columns: {}", r.name, e))?;
+        Ok(SavedView {
+            id: r.id,
+            user_id: r.user_id,
+            owner_name: r.owner_name,
+            name: r.name,
+            filters,
+            sort_by: r.sort_by,
+            sort_dir: r.sort_dir,
+            columns,
+            page_size: r.page_size,
+            is_shared: r.is_shared != 0,
+            is_default: r.is_default != 0,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+    }
+}
+
+/// A view `user_id` may use: their own or one shared by a teammate.
+/// Without a user only shared views are visible.
+async fn fetch_saved_view(db: &Db, id: i64, user_id: Option<i64>) -> Result<SavedView, String> {
+    let row: Option<SavedViewRow> = sqlx::query_as(&format!("{SAVED_VIEW_SELECT} AND v.id = ?2"))
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    row.ok_or_else(|| "Saved view not found".to_string())?.try_into()
+}
+
+/// Normalised view settings ready to store; rejects sorts `get_purchases` would refuse.
+fn check_view_settings(
+    name: &str,
+    sort_by: &Option<String>,
+    sort_dir: &Option<String>,
+    page_size: Option<i64>,
+) -> Result<(String, Option<String>), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("View name is required".to_string());
+    }
+    if sort_by.is_some() || sort_dir.is_some() {
+        resolve_purchase_sort(sort_by.clone(), sort_dir.clone())?;
+    }
+    if let Some(size) = page_size {
+        if !(1..=200).contains(&size) {
+            return Err("Page size must be between 1 and 200".to_string());
+        }
+    }
+    Ok((name.to_string(), sort_dir.as_deref().map(str::to_ascii_lowercase)))
+}
+
+fn columns_json(columns: &Option<Vec<String>>) -> Result<Option<String>, String> {
+    match columns {
+        Some(c) if !c.is_empty() => serde_json::to_string(c).map(Some).map_err(|e| e.to_string()),
+        _ => Ok(None),
+    }
+}
+
+fn view_write_error(e: sqlx::Error, name: &str) -> String {
+    match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => {
+            format!("You already have a view named '{}'", name)
+        }
+        _ => e.to_string(),
+    }
+}
+
+/// The user's own views followed by views shared by others, each by name.
+#[tauri::command]
+pub async fn get_saved_views(db: State<'_, Db>, user_id: i64) -> Result<Vec<SavedView>, String> {
+    let rows: Vec<SavedViewRow> = sqlx::query_as(&format!(
+        "{SAVED_VIEW_SELECT} ORDER BY (v.user_id <> ?1), v.name COLLATE NOCASE",
+    ))
+    .bind(user_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    rows.into_iter().map(SavedView::try_from).collect()
+}
+
+#[tauri::command]
+pub async fn get_saved_view(db: State<'_, Db>, id: i64, user_id: i64) -> Result<SavedView, String> {
+    fetch_saved_view(&db, id, Some(user_id)).await
+}
+
+/// The view the purchase list should open with, if the user picked one.
+#[tauri::command]
+pub async fn get_default_saved_view(db: State<'_, Db>, user_id: i64) -> Result<Option<SavedView>, String> {
+    let row: Option<SavedViewRow> = sqlx::query_as(&format!("{SAVED_VIEW_SELECT} AND d.view_id IS NOT NULL"))
+        .bind(user_id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    row.map(SavedView::try_from).transpose()
+}
+
+#[tauri::command]
+pub async fn create_saved_view(
+    db: State<'_, Db>,
+    payload: SavedViewCreate,
+    user_id: i64,
+) -> Result<SavedView, String> {
+    let (name, sort_dir) = check_view_settings(&payload.name, &payload.sort_by, &payload.sort_dir, payload.page_size)?;
+    let filters = serde_json::to_string(&payload.filters.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let columns = columns_json(&payload.columns)?;
+
+    let res = sqlx::query(
+        "INSERT INTO saved_views (user_id, name, filters_json, sort_by, sort_dir, columns_json, page_size, is_shared)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(user_id)
+    .bind(&name)
+    .bind(filters)
+    .bind(&payload.sort_by)
+    .bind(sort_dir)
+    .bind(columns)
+    .bind(payload.page_size)
+    .bind(payload.is_shared.unwrap_or(false) as i64)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| view_write_error(e, &name))?;
+
+    fetch_saved_view(&db, res.last_insert_rowid(), Some(user_id)).await
+}
+
+/// Update a view the user owns. Shared views can be used but not edited by others.
+#[tauri::command]
+pub async fn update_saved_view(
+    db: State<'_, Db>,
+    payload: SavedViewUpdate,
+    user_id: i64,
+) -> Result<SavedView, String> {
+    let current = fetch_saved_view(&db, payload.id, Some(user_id)).await?;
+    if current.user_id != user_id {
+        return Err("Only the owner can change a saved view".to_string());
+    }
+
+    let name = payload.name.unwrap_or(current.name);
+    let sort_by = payload.sort_by.or(current.sort_by);
+    let sort_dir = payload.sort_dir.or(current.sort_dir);
+    let page_size = payload.page_size.or(current.page_size);
+    let (name, sort_dir) = check_view_settings(&name, &sort_by, &sort_dir, page_size)?;
+    let filters = serde_json::to_string(&payload.filters.unwrap_or(current.filters)).map_err(|e| e.to_string())?;
+    let columns = columns_json(&payload.columns.or(current.columns))?;
+    let is_shared = payload.is_shared.unwrap_or(current.is_shared);
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE saved_views SET name = ?2, filters_json = ?3, sort_by = ?4, sort_dir = ?5, columns_json = ?6,
+            page_size = ?7, is_shared = ?8, updated_at = datetime('now')
+         WHERE id = ?1",
+    )
+    .bind(payload.id)
+    .bind(&name)
+    .bind(filters)
+    .bind(sort_by)
+    .bind(sort_dir)
+    .bind(columns)
+    .bind(page_size)
+    .bind(is_shared as i64)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| view_write_error(e, &name))?;
+
+    if !is_shared {
+        // Teammates can no longer see it, so it can no longer be their default
+        sqlx::query("DELETE FROM saved_view_defaults WHERE view_id = ?1 AND user_id <> ?2")
+            .bind(payload.id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    fetch_saved_view(&db, payload.id, Some(user_id)).await
+}
+
+#[tauri::command]
+pub async fn delete_saved_view(db: State<'_, Db>, id: i64, user_id: i64) -> Result<bool, String> {
+    let res = sqlx::query("DELETE FROM saved_views WHERE id = ?1 AND user_id = ?2")
+        .bind(id)
+        .bind(user_id)
+        .execute(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Make `id` the user's default view, or clear the default with `None`.
+#[tauri::command]
+pub async fn set_default_saved_view(db: State<'_, Db>, id: Option<i64>, user_id: i64) -> Result<bool, String> {
+    match id {
+        Some(id) => {
+            fetch_saved_view(&db, id, Some(user_id)).await?;
+            sqlx::query(
+                "INSERT INTO saved_view_defaults (user_id, view_id) VALUES (?1, ?2)
+                 ON CONFLICT(user_id) DO UPDATE SET view_id = excluded.view_id",
+            )
+            .bind(user_id)
+            .bind(id)
+            .execute(&db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        None => {
+            sqlx::query("DELETE FROM saved_view_defaults WHERE user_id = ?1")
+                .bind(user_id)
+                .execute(&db.pool())
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(true)
+}
+
+// Search
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix.
+/// Quotes are stripped so user input can never inject FTS5 syntax.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| t.chars().any(char::is_alphanumeric))
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    if terms.is_empty() { None } else { Some(terms.join(" ")) }
+}
+
+/// Ranked full-text search across suppliers, purchase entries and their items, grouped by kind.
+#[tauri::command]
+pub async fn global_search(db: State<'_, Db>, query: String, limit: Option<i64>) -> Result<SearchResults, String> {
+    let limit = limit.unwrap_or(10).clamp(1, 50);
+    let Some(fts) = fts_query(&query) else {
+        return Ok(SearchResults { query, suppliers: vec![], purchases: vec![], items: vec![] });
+    };
+
+    // Title matches (name, invoice no, part no) outweigh body matches
+    let suppliers: Vec<SearchHit> = sqlx::query_as(
+        "SELECT 'supplier' AS kind, s.id, NULL AS purchase_id, s.name AS title, s.gst_no AS subtitle,
+                snippet(search_index, -1, '[', ']', '…', 8) AS snippet,
+                bm25(search_index, 0, 0, 0, 10.0, 2.0) AS score
+         FROM search_index
+         JOIN suppliers s ON s.id = search_index.ref_id
+         WHERE search_index MATCH ?1 AND search_index.kind = 'supplier' AND s.deleted_at IS NULL
+         ORDER BY score
+         LIMIT ?2",
+    )
+    .bind(&fts)
+    .bind(limit)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let purchases: Vec<SearchHit> = sqlx::query_as(
+        "SELECT 'purchase' AS kind, pe.id, pe.id AS purchase_id, pe.invoice_no AS title,
+                s.name || ' · ' || pe.date AS subtitle,
+                snippet(search_index, -1, '[', ']', '…', 8) AS snippet,
+                bm25(search_index, 0, 0, 0, 10.0, 2.0) AS score
+         FROM search_index
+         JOIN purchase_entries pe ON pe.id = search_index.ref_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE search_index MATCH ?1 AND search_index.kind = 'purchase' AND pe.deleted_at IS NULL
+         ORDER BY score, pe.date DESC
+         LIMIT ?2",
+    )
+    .bind(&fts)
+    .bind(limit)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let items: Vec<SearchHit> = sqlx::query_as(
+        "SELECT 'item' AS kind, pi.id, pi.purchase_id, COALESCE(pi.part_no, pi.description) AS title,
+                pe.invoice_no || ' · ' || s.name AS subtitle,
+                snippet(search_index, -1, '[', ']', '…', 8) AS snippet,
+                bm25(search_index, 0, 0, 0, 10.0, 2.0) AS score
+         FROM search_index
+         JOIN purchase_items pi ON pi.id = search_index.ref_id
+         JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE search_index MATCH ?1 AND search_index.kind = 'item' AND pe.deleted_at IS NULL
+         ORDER BY score, pe.date DESC
+         LIMIT ?2",
+    )
+    .bind(&fts)
+    .bind(limit)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(SearchResults { query, suppliers, purchases, items })
+}
+
+// Revisions
+
+#[tauri::command]
+pub async fn get_purchase_revisions(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<PurchaseRevision>, String> {
+    let rows: Vec<PurchaseRevision> = sqlx::query_as(
+        "SELECT r.id, r.purchase_id, r.revision_no, r.action, r.note, r.created_by, u.username AS created_by_name, r.created_at
+         FROM purchase_revisions r
+         LEFT JOIN users u ON u.id = r.created_by
+         WHERE r.purchase_id = ?1
+         ORDER BY r.revision_no DESC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+async fn load_revision(db: &Db, revision_id: i64) -> Result<(PurchaseRevision, String, String), String> {
+    let revision = sqlx::query_as::<_, PurchaseRevision>(
+        "SELECT r.id, r.purchase_id, r.revision_no, r.action, r.note, r.created_by, u.username AS created_by_name, r.created_at
+         FROM purchase_revisions r
+         LEFT JOIN users u ON u.id = r.created_by
+         WHERE r.id = ?1",
+    )
+    .bind(revision_id)
+    .fetch_optional(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Revision not found".to_string())?;
+
+    let (header_json, items_json): (String, String) =
+        sqlx::query_as("SELECT header_json, items_json FROM purchase_revisions WHERE id = ?1")
+            .bind(revision_id)
+            .fetch_one(&db.pool())
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok((revision, header_json, items_json))
+}
+
+#[tauri::command]
+pub async fn get_purchase_revision(db: State<'_, Db>, revision_id: i64) -> Result<PurchaseRevisionDetail, String> {
+    let (revision, header_json, items_json) = load_revision(&db, revision_id).await?;
+    Ok(PurchaseRevisionDetail {
+        revision,
+        header: serde_json::from_str(&header_json).map_err(|e| e.to_string())?,
+        items: serde_json::from_str(&items_json).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Field-by-field differences going from one revision to another of the same entry.
+#[tauri::command]
+pub async fn diff_purchase_revisions(db: State<'_, Db>, from_revision_id: i64, to_revision_id: i64) -> Result<RevisionDiff, String> {
+    let from = get_purchase_revision(db.clone(), from_revision_id).await?;
+    let to = get_purchase_revision(db.clone(), to_revision_id).await?;
+    if from.revision.purchase_id != to.revision.purchase_id {
+        return Err("Revisions belong to different purchases".to_string());
+    }
+
+    Ok(RevisionDiff {
+        purchase_id: from.revision.purchase_id,
+        from_revision_no: from.revision.revision_no,
+        to_revision_no: to.revision.revision_no,
+        header: revisions::diff_header(&from.header, &to.header),
+        items: revisions::diff_items(&from.items, &to.items),
+    })
+}
+
+/// Put an entry back the way `revision_id` recorded it. The revert is itself saved as a new revision.
+/// The status stays as it is, except that a verified or approved entry whose amounts, supplier,
+/// lines or splits change goes back to pending for approval.
+#[tauri::command]
+pub async fn revert_purchase(db: State<'_, Db>, revision_id: i64, user_id: Option<i64>) -> Result<PurchaseEntry, String> {
+    let (revision, header_json, items_json) = load_revision(&db, revision_id).await?;
+    let h = revisions::parse_header(&header_json)?;
+    let items = revisions::parse_items(&items_json)?;
+    let pid = revision.purchase_id;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    workflow::check_editable(&mut tx, pid).await?;
+    let before = approvals::fingerprint(&mut tx, pid).await?;
+    let res = sqlx::query(
+        "UPDATE purchase_entries SET
+            supplier_id = COALESCE((SELECT merged_into FROM suppliers WHERE id = ?2), ?2),
+            invoice_no = ?3, date = ?4, entry_date = ?5, gst_rate = ?6,
+            basic_value = ?7, sgst = ?8, cgst = ?9, igst = ?10, invoice_value = ?11,
+            tds_value = ?12, narration = ?13,
+            custom_fields = COALESCE(?14, custom_fields)
+         WHERE id = ?1 AND deleted_at IS NULL",
+    )
+    .bind(pid)
+    .bind(h.supplier_id)
+    .bind(h.invoice_no)
+    .bind(h.date)
+    .bind(h.entry_date)
+    .bind(h.gst_rate)
+    .bind(h.basic_value)
+    .bind(h.sgst)
+    .bind(h.cgst)
+    .bind(h.igst)
+    .bind(h.invoice_value)
+    .bind(h.tds_value)
+    .bind(h.narration)
+    .bind(h.custom_fields.map(Json))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Purchase not found or deleted".to_string());
+    }
+    payments::check_supplier(&mut tx, pid).await?;
+    payments::check_overpaid(&mut tx, pid).await?;
+
+    sqlx::query("DELETE FROM purchase_items WHERE purchase_id = ?1")
+        .bind(pid)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Lines come from a snapshot; the master may have changed since, so they are not re-checked.
+    // Their splits went with the deleted rows and come back from the snapshot.
+    insert_item_rows(&mut tx, pid, items).await?;
+    approvals::reopen_if_changed(&mut tx, pid, before, user_id).await?;
+    let note = format!("Reverted to revision {}", revision.revision_no);
+    revisions::record(&mut tx, pid, "revert", Some(note), user_id).await?;
+    pricing::record(&mut tx, pid).await?;
+    matching::record(&mut tx, pid).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
+    .bind(pid)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Fail unless `user_id` belongs to an admin.
+async fn require_admin(db: &Db, user_id: i64) -> Result<(), String> {
+    let role: Option<(String,)> = sqlx::query_as("SELECT role FROM users WHERE id = ?1")
+        .bind(user_id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+    match role {
+        Some((r,)) if r == "admin" => Ok(()),
+        Some(_) => Err("Only administrators can perform this action".to_string()),
+        None => Err("Unknown user".to_string()),
+    }
+}
+
+// Attachments
+
+const ATTACHMENT_SELECT: &str =
+    "SELECT a.id, a.purchase_id, a.sha256, a.file_name, a.kind, a.mime_type, a.size_bytes,
+            a.created_by, a.created_at,
+            (SELECT COUNT(DISTINCT o.purchase_id) FROM attachments o
+             WHERE o.sha256 = a.sha256 AND o.purchase_id <> a.purchase_id) AS shared_with
+     FROM attachments a";
+
+async fn fetch_attachment(db: &Db, id: i64) -> Result<Attachment, String> {
+    sqlx::query_as::<_, Attachment>(&format!("{ATTACHMENT_SELECT} WHERE a.id = ?1"))
+        .bind(id)
+        .fetch_optional(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Attachment not found".to_string())
+}
+
+/// Other entries the same file (by content) is attached to.
+async fn attachment_uses(db: &Db, sha256: &str, purchase_id: i64) -> Result<Vec<AttachmentUse>, String> {
+    sqlx::query_as::<_, AttachmentUse>(
+        "SELECT a.id AS attachment_id, pe.id AS purchase_id, s.name AS supplier_name, pe.invoice_no, pe.date,
+                pe.deleted_at IS NOT NULL AS deleted
+         FROM attachments a
+         JOIN purchase_entries pe ON pe.id = a.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE a.sha256 = ?1 AND a.purchase_id <> ?2
+         ORDER BY pe.date DESC, pe.id DESC",
+    )
+    .bind(sha256)
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Copy the file at `path` into the attachment store and link it to a purchase.
+/// The result lists other entries that already carry the same file.
+#[tauri::command]
+pub async fn add_attachment(
+    db: State<'_, Db>,
+    purchase_id: i64,
+    path: String,
+    kind: Option<String>,
+    user_id: Option<i64>,
+) -> Result<AttachmentAdded, String> {
+    let kind = kind.unwrap_or_else(|| "invoice".to_string());
+    if !attachments::KINDS.contains(&kind.as_str()) {
+        return Err(format!("Unknown attachment kind '{}'", kind));
+    }
+    let source = std::path::PathBuf::from(&path);
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| "Choose a file to attach".to_string())?;
+    let mime_type = attachments::mime_type(&file_name)
+        .ok_or_else(|| "Only PDF and image files can be attached".to_string())?;
+
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let (live,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM purchase_entries WHERE id = ?1 AND deleted_at IS NULL)",
+    )
+    .bind(purchase_id)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
+    if !live {
+        return Err("Purchase not found or deleted".to_string());
+    }
+    workflow::check_editable(&mut conn, purchase_id).await?;
+    drop(conn);
 
-    let supplier = sqlx::query_as::<_, Supplier>(
-        "SELECT id, name, gst_no, state_code, tds_flag, tds_rate, contact, email FROM suppliers WHERE id = ?1",
+    let (sha256, size_bytes) = attachments::store(&attachments::root(&db.data_dir()), &source)?;
+    let res = sqlx::query(
+        "INSERT INTO attachments (purchase_id, sha256, file_name, kind, mime_type, size_bytes, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
     )
-    .bind(payload.id)
-    .fetch_one(&db.0)
+    .bind(purchase_id)
+    .bind(&sha256)
+    .bind(&file_name)
+    .bind(&kind)
+    .bind(mime_type)
+    .bind(size_bytes)
+    .bind(user_id)
+    .execute(&db.pool())
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| match &e {
+        sqlx::Error::Database(d) if d.message().contains("UNIQUE") => {
+            "This file is already attached to the entry".to_string()
+        }
+        _ => e.to_string(),
+    })?;
 
-    Ok(supplier)
+    let attachment = fetch_attachment(&db, res.last_insert_rowid()).await?;
+    let also_attached_to = attachment_uses(&db, &sha256, purchase_id).await?;
+    Ok(AttachmentAdded { attachment, also_attached_to })
 }
 
-// Auth
+#[tauri::command]
+pub async fn get_attachments(db: State<'_, Db>, purchase_id: i64) -> Result<Vec<Attachment>, String> {
+    sqlx::query_as::<_, Attachment>(&format!(
+        "{ATTACHMENT_SELECT} WHERE a.purchase_id = ?1 ORDER BY a.created_at, a.id",
+    ))
+    .bind(purchase_id)
+    .fetch_all(&db.pool())
+    .await
+    .map_err(|e| e.to_string())
+}
 
+/// Other entries carrying the same file as attachment `id`.
 #[tauri::command]
-pub async fn register(db: State<'_, Db>, payload: UserCreate) -> Result<User, String> {
-    if payload.username.is_empty() {
-        return Err("Username cannot be empty".to_string());
-    }
-    if payload.password.is_empty() {
-        return Err("Password cannot be empty".to_string());
-    }
+pub async fn get_attachment_uses(db: State<'_, Db>, id: i64) -> Result<Vec<AttachmentUse>, String> {
+    let attachment = fetch_attachment(&db, id).await?;
+    attachment_uses(&db, &attachment.sha256, attachment.purchase_id).await
+}
 
-    let existing_user: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE username = ?1")
-        .bind(&payload.username)
-        .fetch_optional(&db.0)
+/// Open an attachment in the system viewer. Returns the path of the copy that was opened.
+#[tauri::command]
+pub async fn open_attachment(db: State<'_, Db>, id: i64) -> Result<String, String> {
+    let a = fetch_attachment(&db, id).await?;
+    let path = attachments::materialize(&attachments::root(&db.data_dir()), &a.sha256, a.id, &a.file_name)?;
+    attachments::launch(&path)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Unlink an attachment. The stored file goes once no other entry uses it;
+/// backups keep their own copy.
+#[tauri::command]
+pub async fn remove_attachment(db: State<'_, Db>, id: i64, user_id: Option<i64>) -> Result<bool, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let found: Option<(i64, String)> = sqlx::query_as("SELECT purchase_id, file_name FROM attachments WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
+    let Some((purchase_id, file_name)) = found else {
+        return Ok(false);
+    };
+    workflow::check_editable(&mut tx, purchase_id).await?;
 
-    if existing_user.is_some() {
-        return Err("Username is already taken".to_string());
-    }
+    let (sha256,): (String,) = sqlx::query_as("DELETE FROM attachments WHERE id = ?1 RETURNING sha256")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let note = format!("Removed attachment '{}'", file_name);
+    revisions::record(&mut tx, purchase_id, "attachments", Some(note), user_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
 
-    // TODO: hash password
-    let password_hash = payload.password;
-    let role = payload.role.unwrap_or_else(|| "user".to_string());
+    attachments::remove_unreferenced(&db.pool(), &attachments::root(&db.data_dir()), &[sha256]).await?;
+    Ok(true)
+}
 
-    let rec: (i64,) = sqlx::query_as(
-        "INSERT INTO users (username, password_hash, role)
-         VALUES (?1, ?2, ?3)
-         RETURNING id",
-    )
-    .bind(&payload.username)
-    .bind(&password_hash)
-    .bind(&role)
-    .fetch_one(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
+// Recycle bin
 
-    let user = sqlx::query_as::<_, User>(
-        "SELECT id, username, password_hash, role, created_at FROM users WHERE id = ?1",
+#[tauri::command]
+pub async fn get_recycle_bin(db: State<'_, Db>) -> Result<Vec<RecycleBinEntry>, String> {
+    let rows: Vec<RecycleBinEntry> = sqlx::query_as(
+        "SELECT 'purchase' AS entity, pe.id, s.name || ' / ' || pe.invoice_no AS label,
+                pe.deleted_at, pe.deleted_by, u.username AS deleted_by_name,
+                pe.deleted_at <= datetime('now', '-' || ?1 || ' days') AS purgeable
+         FROM purchase_entries pe
+         JOIN suppliers s ON s.id = pe.supplier_id
+         LEFT JOIN users u ON u.id = pe.deleted_by
+         WHERE pe.deleted_at IS NOT NULL
+         UNION ALL
+         SELECT 'supplier' AS entity, s.id, s.name AS label,
+                s.deleted_at, s.deleted_by, u.username AS deleted_by_name,
+                s.deleted_at <= datetime('now', '-' || ?1 || ' days') AS purgeable
+         FROM suppliers s
+         LEFT JOIN users u ON u.id = s.deleted_by
+         WHERE s.deleted_at IS NOT NULL AND s.merged_into IS NULL
+         ORDER BY deleted_at DESC",
     )
-    .bind(rec.0)
-    .fetch_one(&db.0)
+    .bind(RECYCLE_BIN_RETENTION_DAYS)
+    .fetch_all(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
-
-    Ok(user)
+    Ok(rows)
 }
 
+/// Bring a purchase back from the recycle bin. A bill entered again while this one was
+/// deleted is refused as a duplicate unless `allow_duplicate` is set.
 #[tauri::command]
-pub async fn login(db: State<'_, Db>, payload: LoginPayload) -> Result<LoginResponse, String> {
-    let user = sqlx::query_as::<_, User>(
-        "SELECT id, username, password_hash, role, created_at FROM users WHERE username = ?1",
+pub async fn restore_purchase(
+    db: State<'_, Db>,
+    id: i64,
+    user_id: Option<i64>,
+    allow_duplicate: Option<bool>,
+) -> Result<PurchaseEntry, String> {
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let found: Option<(bool, i64, String, String, f64)> = sqlx::query_as(
+        "SELECT s.deleted_at IS NOT NULL, pe.supplier_id, pe.invoice_no, pe.date, pe.invoice_value
+         FROM purchase_entries pe JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE pe.id = ?1 AND pe.deleted_at IS NOT NULL",
     )
-    .bind(&payload.username)
-    .fetch_optional(&db.0)
+    .bind(id)
+    .fetch_optional(&mut *tx)
     .await
-    .map_err(|e| e.to_string())?
-    .ok_or_else(|| "Invalid username or password".to_string())?;
-
-    // TODO: verify password hash
-    if user.password_hash != payload.password {
-        return Err("Invalid username or password".to_string());
+    .map_err(|e| e.to_string())?;
+    let (supplier_id, invoice_no, date, invoice_value) = match found {
+        None => return Err("Purchase not found in the recycle bin".to_string()),
+        Some((true, ..)) => return Err("Restore the supplier of this purchase first".to_string()),
+        Some((false, supplier_id, invoice_no, date, invoice_value)) => (supplier_id, invoice_no, date, invoice_value),
+    };
+    if !allow_duplicate.unwrap_or(false) {
+        let suspects = find_duplicate_invoices(&mut tx, supplier_id, &invoice_no, &date, invoice_value, Some(id)).await?;
+        if !suspects.is_empty() {
+            let list: Vec<String> = suspects
+                .iter()
+                .map(|d| format!("{} from {} dated {} for {:.2}", d.invoice_no, d.supplier_name, d.date, d.invoice_value))
+                .collect();
+            return Err(format!("Possible duplicate of {}. Confirm to restore anyway.", list.join("; ")));
+        }
     }
 
-    // TODO: generate a real JWT token
-    let token = "fake-jwt-token".to_string();
+    let res = sqlx::query(
+        "UPDATE purchase_entries SET deleted_at = NULL, deleted_by = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+    )
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Purchase not found in the recycle bin".to_string());
+    }
+    revisions::record(&mut tx, id, "restore", None, user_id).await?;
 
-    Ok(LoginResponse { user, token })
+    let entry = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(entry)
 }
 
-// Reports
-
 #[tauri::command]
-pub async fn get_report_summary(db: State<'_, Db>, date_from: Option<String>, date_to: Option<String>) -> Result<ReportSummary, String> {
-    let total_purchases: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(invoice_value), 0) FROM purchase_entries WHERE date >= ?1 AND date <= ?2",
+pub async fn restore_supplier(db: State<'_, Db>, id: i64, user_id: Option<i64>) -> Result<Supplier, String> {
+    let res = sqlx::query(
+        "UPDATE suppliers SET deleted_at = NULL, deleted_by = NULL, restored_at = datetime('now'), restored_by = ?2
+         WHERE id = ?1 AND deleted_at IS NOT NULL AND merged_into IS NULL",
     )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_one(&db.0)
+    .bind(id)
+    .bind(user_id)
+    .execute(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Supplier not found in the recycle bin or merged into another supplier".to_string());
+    }
 
-    let total_gst: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(sgst + cgst + igst), 0) FROM purchase_entries WHERE date >= ?1 AND date <= ?2",
-    )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_one(&db.0)
+    let supplier = sqlx::query_as::<_, Supplier>(&format!(
+        "SELECT {SUPPLIER_COLUMNS} FROM suppliers WHERE id = ?1",
+    ))
+    .bind(id)
+    .fetch_one(&db.pool())
     .await
     .map_err(|e| e.to_string())?;
+    Ok(supplier)
+}
 
-    let total_suppliers: (i64,) = sqlx::query_as(
-        "SELECT COUNT(DISTINCT supplier_id) FROM purchase_entries WHERE date >= ?1 AND date <= ?2",
+/// Permanently remove recycle-bin rows older than the retention window. Admin only.
+#[tauri::command]
+pub async fn purge_recycle_bin(db: State<'_, Db>, user_id: i64) -> Result<PurgeResult, String> {
+    require_admin(&db, user_id).await?;
+
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    // purchase_items and attachments go with their entry via ON DELETE CASCADE; note the files first
+    let files: Vec<String> = sqlx::query_as::<_, (String,)>(
+        "SELECT a.sha256 FROM attachments a JOIN purchase_entries pe ON pe.id = a.purchase_id
+         WHERE pe.deleted_at IS NOT NULL AND pe.deleted_at <= datetime('now', '-' || ?1 || ' days')",
+    )
+    .bind(RECYCLE_BIN_RETENTION_DAYS)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(h,)| h)
+    .collect();
+    let purchases = sqlx::query(
+        "DELETE FROM purchase_entries
+         WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', '-' || ?1 || ' days')",
     )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_one(&db.0)
+    .bind(RECYCLE_BIN_RETENTION_DAYS)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
-
-    let total_items: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM purchase_items WHERE purchase_id IN (SELECT id FROM purchase_entries WHERE date >= ?1 AND date <= ?2)",
+    // Suppliers still referenced by a (possibly deleted) purchase must wait for that purchase.
+    // Orders, receipts, payments and notes have no recycle bin, so suppliers they refer to stay.
+    let suppliers = sqlx::query(
+        "DELETE FROM suppliers
+         WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', '-' || ?1 || ' days')
+           AND merged_into IS NULL -- kept so old revisions still resolve to the survivor
+           AND NOT EXISTS (SELECT 1 FROM purchase_entries pe WHERE pe.supplier_id = suppliers.id)
+           AND NOT EXISTS (SELECT 1 FROM purchase_orders po WHERE po.supplier_id = suppliers.id)
+           AND NOT EXISTS (SELECT 1 FROM goods_receipts gr WHERE gr.supplier_id = suppliers.id)
+           AND NOT EXISTS (SELECT 1 FROM supplier_payments sp WHERE sp.supplier_id = suppliers.id)
+           AND NOT EXISTS (SELECT 1 FROM supplier_notes sn WHERE sn.supplier_id = suppliers.id)",
     )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_one(&db.0)
+    .bind(RECYCLE_BIN_RETENTION_DAYS)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    attachments::remove_unreferenced(&db.pool(), &attachments::root(&db.data_dir()), &files).await?;
+
+    Ok(PurgeResult {
+        purchases: purchases.rows_affected() as i64,
+        suppliers: suppliers.rows_affected() as i64,
+    })
+}
+
+// Reports
+
+#[tauri::command]
+pub async fn get_report_summary(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    filters: Option<PurchaseFilters>,
+) -> Result<ReportSummary, String> {
+    let f = merge_date_range(filters, date_from, date_to);
+
+    let mut totals_q = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT COALESCE(SUM(pe.invoice_value), 0), COALESCE(SUM(pe.sgst + pe.cgst + pe.igst), 0), COUNT(DISTINCT pe.supplier_id){PURCHASE_FROM}",
+    ));
+    push_purchase_filters(&mut totals_q, &f)?;
+    let (total_purchases, total_gst, total_suppliers): (f64, f64, i64) = totals_q
+        .build_query_as()
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut items_q = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT COUNT(*) FROM purchase_items WHERE purchase_id IN (SELECT pe.id{PURCHASE_FROM}",
+    ));
+    push_purchase_filters(&mut items_q, &f)?;
+    items_q.push(")");
+    let (total_items,): (i64,) = items_q
+        .build_query_as()
+        .fetch_one(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
 
     Ok(ReportSummary {
-        total_purchases: total_purchases.0,
-        total_gst: total_gst.0,
-        total_suppliers: total_suppliers.0,
-        total_items: total_items.0,
+        total_purchases,
+        total_gst,
+        total_suppliers,
+        total_items,
     })
 }
 
 #[tauri::command]
-pub async fn get_purchases_by_supplier(db: State<'_, Db>, date_from: Option<String>, date_to: Option<String>) -> Result<Vec<PurchasesBySupplier>, String> {
-    let rows: Vec<PurchasesBySupplier> = sqlx::query_as(
-        "SELECT s.name as supplier_name, SUM(pe.invoice_value) as total_purchases
-         FROM purchase_entries pe
-         JOIN suppliers s ON pe.supplier_id = s.id
-         WHERE pe.date >= ?1 AND pe.date <= ?2
-         GROUP BY s.name
-         ORDER BY total_purchases DESC",
-    )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_all(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
+pub async fn get_purchases_by_supplier(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    filters: Option<PurchaseFilters>,
+) -> Result<Vec<PurchasesBySupplier>, String> {
+    let f = merge_date_range(filters, date_from, date_to);
+
+    let mut q = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT s.name as supplier_name, SUM(pe.invoice_value) as total_purchases{PURCHASE_FROM}",
+    ));
+    push_purchase_filters(&mut q, &f)?;
+    q.push(" GROUP BY s.name ORDER BY total_purchases DESC");
+
+    let rows: Vec<PurchasesBySupplier> = q
+        .build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(rows)
 }
 
+/// Taxable value (before GST) of matching purchases broken down by the dimensions of one kind.
+/// A line's own splits apply to its amount; the entry's splits apply to the rest of the
+/// entry's taxable value. Whatever carries no split of this kind comes back as one row
+/// with no `dimension_id`.
 #[tauri::command]
-pub async fn export_purchases(db: State<'_, Db>, date_from: Option<String>, date_to: Option<String>) -> Result<Vec<PurchaseEntry>, String> {
-    let rows: Vec<PurchaseEntry> = sqlx::query_as(
-        "SELECT id, supplier_id, invoice_no, date, entry_date, gst_rate, basic_value, sgst, cgst, igst, invoice_value, tds_value, narration, status FROM purchase_entries WHERE date >= ?1 AND date <= ?2 ORDER BY date DESC",
+pub async fn get_spend_by_dimension(
+    db: State<'_, Db>,
+    kind: String,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    filters: Option<PurchaseFilters>,
+) -> Result<Vec<DimensionSpend>, String> {
+    let kind = dimensions::kind(&kind)?;
+    let f = merge_date_range(filters, date_from, date_to);
+
+    let mut q = QueryBuilder::<Sqlite>::new(format!("WITH f AS (SELECT pe.id, pe.basic_value{PURCHASE_FROM}"));
+    push_purchase_filters(&mut q, &f)?;
+    q.push(
+        "),
+         splits AS (
+            SELECT pa.purchase_id, pa.item_id, pa.dimension_id, pa.percent
+            FROM purchase_allocations pa JOIN dimensions d ON d.id = pa.dimension_id
+            WHERE d.kind = ",
     )
-    .bind(&date_from)
-    .bind(&date_to)
-    .fetch_all(&db.0)
-    .await
-    .map_err(|e| e.to_string())?;
-    Ok(rows)
+    .push_bind(kind)
+    .push(
+        " AND pa.purchase_id IN (SELECT id FROM f)
+         ),
+         lines AS (
+            SELECT pi.id, pi.purchase_id, pi.amount,
+                   EXISTS (SELECT 1 FROM splits sp WHERE sp.item_id = pi.id) AS own
+            FROM purchase_items pi WHERE pi.purchase_id IN (SELECT id FROM f)
+         ),
+         rest AS (
+            SELECT f.id, f.basic_value - COALESCE((SELECT SUM(l.amount) FROM lines l WHERE l.purchase_id = f.id AND l.own), 0) AS amount
+            FROM f
+         ),
+         shares AS (
+            SELECT sp.dimension_id, l.purchase_id, l.amount * sp.percent / 100.0 AS amount
+            FROM lines l JOIN splits sp ON sp.item_id = l.id
+            UNION ALL
+            SELECT sp.dimension_id, r.id, r.amount * sp.percent / 100.0
+            FROM rest r JOIN splits sp ON sp.purchase_id = r.id AND sp.item_id IS NULL
+            UNION ALL
+            SELECT NULL, r.id, r.amount
+            FROM rest r WHERE NOT EXISTS (SELECT 1 FROM splits sp WHERE sp.purchase_id = r.id AND sp.item_id IS NULL)
+         )
+         SELECT sh.dimension_id, d.code, d.name, SUM(sh.amount) AS spend, COUNT(DISTINCT sh.purchase_id) AS purchases
+         FROM shares sh LEFT JOIN dimensions d ON d.id = sh.dimension_id
+         GROUP BY sh.dimension_id
+         HAVING sh.dimension_id IS NOT NULL OR ABS(SUM(sh.amount)) >= 0.005
+         ORDER BY sh.dimension_id IS NULL, spend DESC",
+    );
+
+    q.build_query_as()
+        .fetch_all(&db.pool())
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Export matching purchases. With `view_id`, the saved view's filters and sort
+/// apply unless `filters` is given; the date range always narrows the result.
 #[tauri::command]
-pub async fn delete_supplier(db: State<'_, Db>, id: i64) -> Result<bool, String> {
-    let res = sqlx::query("DELETE FROM suppliers WHERE id = ?1")
-        .bind(id)
-        .execute(&db.0)
+pub async fn export_purchases(
+    db: State<'_, Db>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    filters: Option<PurchaseFilters>,
+    view_id: Option<i64>,
+    user_id: Option<i64>,
+) -> Result<Vec<PurchaseEntry>, String> {
+    let view = match view_id {
+        Some(id) => Some(fetch_saved_view(&db, id, user_id).await?),
+        None => None,
+    };
+    let (filters, order) = match view {
+        Some(v) => {
+            let (_, col, dir) = resolve_purchase_sort(v.sort_by, v.sort_dir)?;
+            (filters.or(Some(v.filters)), format!("{} {}, pe.id {}", col, dir, dir))
+        }
+        None => (filters, "pe.date DESC".to_string()),
+    };
+    let f = merge_date_range(filters, date_from, date_to);
+
+    let mut q = QueryBuilder::<Sqlite>::new(format!("SELECT {}{PURCHASE_FROM}", qualified(PURCHASE_COLUMNS, "pe")));
+    push_purchase_filters(&mut q, &f)?;
+    q.push(format!(" ORDER BY {}", order));
+
+    let rows: Vec<PurchaseEntry> = q
+        .build_query_as()
+        .fetch_all(&db.pool())
         .await
         .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+/// Move a supplier to the recycle bin. Refused while the supplier is in use; the error counts
+/// the uses and offers deactivation instead.
+#[tauri::command]
+pub async fn delete_supplier(db: State<'_, Db>, id: i64, user_id: Option<i64>) -> Result<bool, SupplierDeleteError> {
+    let (exists, live, deleted, orders, receipts, paid, notes): (i64, i64, i64, i64, i64, i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM suppliers WHERE id = ?1 AND deleted_at IS NULL),
+                COUNT(*) FILTER (WHERE deleted_at IS NULL),
+                COUNT(*) FILTER (WHERE deleted_at IS NOT NULL),
+                (SELECT COUNT(*) FROM purchase_orders WHERE supplier_id = ?1),
+                (SELECT COUNT(*) FROM goods_receipts WHERE supplier_id = ?1),
+                (SELECT COUNT(*) FROM supplier_payments WHERE supplier_id = ?1),
+                (SELECT COUNT(*) FROM supplier_notes WHERE supplier_id = ?1)
+         FROM purchase_entries WHERE supplier_id = ?1",
+    )
+    .bind(id)
+    .fetch_one(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    if exists == 0 {
+        return Err(SupplierDeleteError {
+            code: "not_found".to_string(),
+            message: "Supplier not found or already deleted".to_string(),
+            purchases: 0,
+            deleted_purchases: 0,
+            purchase_orders: 0,
+            goods_receipts: 0,
+            payments: 0,
+            notes: 0,
+            can_deactivate: false,
+        });
+    }
+    let uses: Vec<String> = [
+        (live, "purchase(s)"),
+        (orders, "purchase order(s)"),
+        (receipts, "goods receipt(s)"),
+        (paid, "payment(s)"),
+        (notes, "debit/credit note(s)"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, what)| format!("{} {}", n, what))
+    .collect();
+    if !uses.is_empty() {
+        return Err(SupplierDeleteError {
+            code: "supplier_in_use".to_string(),
+            message: format!(
+                "Supplier has {} and cannot be deleted. Deactivate it to hide it from new entries.",
+                uses.join(", ")
+            ),
+            purchases: live,
+            deleted_purchases: deleted,
+            purchase_orders: orders,
+            goods_receipts: receipts,
+            payments: paid,
+            notes,
+            can_deactivate: true,
+        });
+    }
+
+    let res = sqlx::query(
+        "UPDATE suppliers SET deleted_at = datetime('now'), deleted_by = ?2
+         WHERE id = ?1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
     Ok(res.rows_affected() > 0)
 }
 
+/// Deactivate or reactivate a supplier. Inactive suppliers keep their purchases and
+/// still show in reports, but are left out of pickers and take no new purchases.
 #[tauri::command]
-pub async fn import_suppliers_from_excel(db: State<'_, Db>, path: String) -> Result<usize, String> {
-    let mut workbook: Xlsx<_> = open_workbook(path).map_err(|e| e.to_string())?;
-    let sheet = workbook.worksheet_range("Sheet1").ok_or_else(|| "Sheet1 not found".to_string())??;
+pub async fn set_supplier_active(
+    db: State<'_, Db>,
+    id: i64,
+    active: bool,
+    user_id: Option<i64>,
+) -> Result<Supplier, String> {
+    let res = sqlx::query(
+        "UPDATE suppliers SET
+            active = ?2,
+            deactivated_at = CASE WHEN ?2 THEN NULL ELSE COALESCE(deactivated_at, datetime('now')) END,
+            deactivated_by = CASE WHEN ?2 THEN NULL ELSE COALESCE(deactivated_by, ?3) END
+         WHERE id = ?1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(active)
+    .bind(user_id)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err("Supplier not found or deleted".to_string());
+    }
+    fetch_supplier(&db, id).await
+}
 
-    let mut count = 0;
-    for row in sheet.rows().skip(1) {
-        let name = row.get(0).and_then(|c| c.get_string()).unwrap_or_default().to_string();
-        if name.is_empty() {
-            continue;
+/// Columns of the supplier import sheet, in order. One contact, address pair and
+/// bank account per row; add more from the supplier screen afterwards.
+const SUPPLIER_TEMPLATE_HEADERS: [&str; 27] = [
+    "Name", "GST No", "State Code", "TDS Flag", "TDS Rate", "Contact", "Email",
+    "PAN", "MSME No", "MSME Category", "Payment Terms (Days)", "Payment Terms",
+    "Contact Role", "Contact Phone",
+    "Billing Address", "Billing City", "Billing State Code", "Billing PIN",
+    "Shipping Address", "Shipping City", "Shipping State Code", "Shipping PIN",
+    "Account Name", "Account No", "IFSC", "Bank Name", "Branch",
+];
+
+fn cell_text(row: &[calamine::DataType], i: usize) -> Option<String> {
+    match row.get(i)? {
+        calamine::DataType::Empty => None,
+        // Whole numbers (PIN codes, account numbers) come back as floats
+        calamine::DataType::Float(f) if f.fract() == 0.0 => Some(format!("{:.0}", f)),
+        c => Some(c.to_string().trim().to_string()).filter(|s| !s.is_empty()),
+    }
+}
+
+fn supplier_from_row(row: &[calamine::DataType]) -> Result<Option<SupplierCreate>, String> {
+    let Some(name) = cell_text(row, 0) else {
+        return Ok(None);
+    };
+    let tds_flag = match row.get(3).and_then(|c| c.get_bool()) {
+        Some(b) => b,
+        None => matches!(
+            cell_text(row, 3).map(|s| s.to_lowercase()).as_deref(),
+            Some("yes" | "y" | "true" | "1")
+        ),
+    };
+    let tds_rate = match cell_text(row, 4) {
+        Some(s) => Some(s.parse::<f64>().map_err(|_| format!("Invalid TDS rate '{}'", s))?),
+        None => None,
+    };
+    let payment_terms_days = match cell_text(row, 10) {
+        Some(s) => Some(s.parse::<i64>().map_err(|_| format!("Invalid payment terms '{}'", s))?),
+        None => None,
+    };
+
+    let mut addresses = Vec::new();
+    for (kind, at) in [("billing", 14), ("shipping", 18)] {
+        if let Some(line1) = cell_text(row, at) {
+            addresses.push(SupplierAddressCreate {
+                kind: kind.to_string(),
+                line1,
+                line2: None,
+                city: cell_text(row, at + 1),
+                state_code: cell_text(row, at + 2),
+                pincode: cell_text(row, at + 3),
+                country: None,
+                is_default: true,
+            });
+        }
+    }
+    let bank_accounts = match (cell_text(row, 23), cell_text(row, 24)) {
+        (Some(account_no), Some(ifsc)) => vec![SupplierBankAccountCreate {
+            account_name: cell_text(row, 22).unwrap_or_else(|| name.clone()),
+            account_no,
+            ifsc,
+            bank_name: cell_text(row, 25),
+            branch: cell_text(row, 26),
+            is_primary: true,
+        }],
+        (None, None) => Vec::new(),
+        _ => return Err("Bank account needs both an account number and an IFSC".to_string()),
+    };
+    let contact = cell_text(row, 5);
+    let email = cell_text(row, 6);
+    let contacts = match &contact {
+        Some(c) => vec![SupplierContactCreate {
+            name: c.clone(),
+            role: cell_text(row, 12),
+            phone: cell_text(row, 13),
+            email: email.clone(),
+            is_primary: true,
+        }],
+        None => Vec::new(),
+    };
+
+    Ok(Some(SupplierCreate {
+        name,
+        gst_no: cell_text(row, 1),
+        state_code: cell_text(row, 2),
+        tds_flag,
+        tds_rate,
+        contact,
+        email,
+        pan: cell_text(row, 7),
+        msme_no: cell_text(row, 8),
+        msme_category: cell_text(row, 9),
+        payment_terms_days,
+        payment_terms: cell_text(row, 11),
+        addresses,
+        bank_accounts,
+        contacts,
+        custom_fields: None,
+    }))
+}
+
+/// Custom field columns after the fixed template columns, matched by label or key.
+async fn supplier_custom_columns(
+    conn: &mut SqliteConnection,
+    header: &[calamine::DataType],
+) -> Result<Vec<(usize, String)>, String> {
+    let fields = custom_fields::load(conn, "supplier", false).await?;
+    let mut columns = Vec::new();
+    for i in SUPPLIER_TEMPLATE_HEADERS.len()..header.len() {
+        let Some(title) = cell_text(header, i) else { continue };
+        match fields.iter().find(|f| f.label.eq_ignore_ascii_case(&title) || f.key.eq_ignore_ascii_case(&title)) {
+            Some(f) => columns.push((i, f.key.clone())),
+            None => return Err(format!("Column '{}' is not a supplier custom field", title)),
         }
+    }
+    Ok(columns)
+}
 
-        let supplier = SupplierCreate {
-            name,
-            gst_no: row.get(1).and_then(|c| c.get_string()).map(|s| s.to_string()),
-            state_code: row.get(2).and_then(|c| c.get_string()).map(|s| s.to_string()),
-            tds_flag: row.get(3).and_then(|c| c.get_bool()).unwrap_or(false),
-            tds_rate: row.get(4).and_then(|c| c.get_f64()),
-            contact: row.get(5).and_then(|c| c.get_string()).map(|s| s.to_string()),
-            email: row.get(6).and_then(|c| c.get_string()).map(|s| s.to_string()),
-        };
+/// A custom field cell as text; Excel dates come back as day serials and are turned into YYYY-MM-DD.
+fn custom_cell(row: &[calamine::DataType], i: usize) -> Option<serde_json::Value> {
+    match row.get(i)? {
+        calamine::DataType::DateTime(serial) => chrono::NaiveDate::from_ymd_opt(1899, 12, 30)
+            .and_then(|base| base.checked_add_signed(chrono::Duration::days(*serial as i64)))
+            .map(|d| serde_json::Value::String(d.format("%Y-%m-%d").to_string())),
+        _ => cell_text(row, i).map(serde_json::Value::String),
+    }
+}
+
+/// Import suppliers from the template sheet. The import is all or nothing:
+/// the first invalid row aborts it with its row number.
+#[tauri::command]
+pub async fn import_suppliers_from_excel(db: State<'_, Db>, path: String) -> Result<usize, String> {
+    let mut workbook: Xlsx<_> = open_workbook(path).map_err(|e: calamine::XlsxError| e.to_string())?;
+    let sheet = workbook.worksheet_range("Sheet1").map_err(|e| e.to_string())?;
 
-        add_supplier(db.clone(), supplier).await?;
+    let mut tx = db.pool().begin().await.map_err(|e| e.to_string())?;
+    let custom_columns = match sheet.rows().next() {
+        Some(header) => supplier_custom_columns(&mut tx, header).await?,
+        None => Vec::new(),
+    };
+    let mut count = 0;
+    for (i, row) in sheet.rows().enumerate().skip(1) {
+        let mut supplier = match supplier_from_row(row) {
+            Ok(Some(s)) => s,
+            Ok(None) => continue,
+            Err(e) => return Err(format!("Row {}: {}", i + 1, e)),
+        };
+        let custom: CustomValues = custom_columns
+            .iter()
+            .filter_map(|(col, key)| custom_cell(row, *col).map(|v| (key.clone(), v)))
+            .collect();
+        supplier.custom_fields = Some(custom);
+        insert_supplier(&mut tx, supplier)
+            .await
+            .map_err(|e| format!("Row {}: {}", i + 1, e))?;
         count += 1;
     }
+    tx.commit().await.map_err(|e| e.to_string())?;
 
     Ok(count)
 }
 
+/// Write the supplier import template. Active supplier custom fields follow the fixed columns.
 #[tauri::command]
-pub async fn generate_supplier_template(path: String) -> Result<(), String> {
+pub async fn generate_supplier_template(db: State<'_, Db>, path: String) -> Result<(), String> {
+    let mut conn = db.pool().acquire().await.map_err(|e| e.to_string())?;
+    let fields = custom_fields::load(&mut conn, "supplier", false).await?;
+    drop(conn);
+
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
 
-    let headers = ["Name", "GST No", "State Code", "TDS Flag", "TDS Rate", "Contact", "Email"];
-    for (i, header) in headers.iter().enumerate() {
+    let headers = SUPPLIER_TEMPLATE_HEADERS.iter().copied().chain(fields.iter().map(|f| f.label.as_str()));
+    for (i, header) in headers.enumerate() {
         worksheet.write_string(0, i as u16, header).map_err(|e| e.to_string())?;
     }
 
@@ -619,3 +5976,83 @@ pub async fn generate_supplier_template(path: String) -> Result<(), String> {
     Ok(())
 }
 
+
+// Backups
+
+/// Back up the live database. Without a `path` the copy goes to the backups folder; either way
+/// the attachment files go along.
+#[tauri::command]
+pub async fn create_backup(db: State<'_, Db>, path: Option<String>) -> Result<BackupInfo, String> {
+    match path {
+        Some(p) => backup::create_at(&db, std::path::Path::new(&p)).await,
+        None => backup::create(&db, backup::KIND_MANUAL).await,
+    }
+}
+
+#[tauri::command]
+pub async fn list_backups(db: State<'_, Db>) -> Result<Vec<BackupInfo>, String> {
+    backup::list(db.path())
+}
+
+/// Replace the live database with a verified backup. Returns the safety backup taken first.
+#[tauri::command]
+pub async fn restore_backup(db: State<'_, Db>, path: String) -> Result<BackupInfo, String> {
+    backup::restore(&db, std::path::Path::new(&path)).await
+}
+
+#[tauri::command]
+pub async fn get_backup_settings(db: State<'_, Db>) -> Result<BackupSettings, String> {
+    backup::load_settings(&db.pool()).await
+}
+
+#[tauri::command]
+pub async fn update_backup_settings(db: State<'_, Db>, payload: BackupSettingsUpdate) -> Result<BackupSettings, String> {
+    if payload.interval_hours.map(|h| h < 1).unwrap_or(false) {
+        return Err("Backup interval must be at least 1 hour".to_string());
+    }
+    if payload.keep_last.map(|n| n < 1).unwrap_or(false) {
+        return Err("At least one scheduled backup must be kept".to_string());
+    }
+    if payload.keep_days.map(|d| d < 0).unwrap_or(false) {
+        return Err("Retention days cannot be negative".to_string());
+    }
+
+    sqlx::query(
+        "UPDATE backup_settings SET
+            enabled = COALESCE(?1, enabled),
+            interval_hours = COALESCE(?2, interval_hours),
+            keep_last = COALESCE(?3, keep_last),
+            keep_days = COALESCE(?4, keep_days)
+         WHERE id = 1",
+    )
+    .bind(payload.enabled.map(|b| if b { 1_i64 } else { 0_i64 }))
+    .bind(payload.interval_hours)
+    .bind(payload.keep_last)
+    .bind(payload.keep_days)
+    .execute(&db.pool())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let settings = backup::load_settings(&db.pool()).await?;
+
+    backup::prune(db.path(), &settings)?;
+    Ok(settings)
+}
+
+/// Write a password-encrypted archive (database snapshot, attachments, manifest) to `path`.
+#[tauri::command]
+pub async fn export_backup_archive(db: State<'_, Db>, path: String, password: String) -> Result<ArchiveManifest, String> {
+    archive::export(&db, std::path::Path::new(&path), &password).await
+}
+
+/// Decrypt and verify an archive without restoring it.
+#[tauri::command]
+pub async fn inspect_backup_archive(db: State<'_, Db>, path: String, password: String) -> Result<ArchiveManifest, String> {
+    archive::inspect(&db.data_dir(), std::path::Path::new(&path), &password).await
+}
+
+/// Restore an encrypted archive. Returns the safety backup taken of the current data.
+#[tauri::command]
+pub async fn import_backup_archive(db: State<'_, Db>, path: String, password: String) -> Result<BackupInfo, String> {
+    archive::import(&db, std::path::Path::new(&path), &password).await
+}
@@ -0,0 +1,231 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+
+use crate::models::{CustomField, CustomFieldFilter, CustomValues};
+
+/// Records that can carry custom fields, matching the CHECK constraint on the definitions.
+pub const ENTITIES: &[&str] = &["supplier", "purchase", "item"];
+pub const TYPES: &[&str] = &["text", "number", "date", "enum"];
+
+pub const COLUMNS: &str =
+    "id, entity, key, label, field_type, options_json, required, active, position, created_by, created_at";
+
+#[derive(sqlx::FromRow)]
+pub struct CustomFieldRow {
+    id: i64,
+    entity: String,
+    key: String,
+    label: String,
+    field_type: String,
+    options_json: String,
+    required: i64,
+    active: i64,
+    position: i64,
+    created_by: Option<i64>,
+    created_at: String,
+}
+
+impl TryFrom<CustomFieldRow> for CustomField {
+    type Error = String;
+
+    fn try_from(r: CustomFieldRow) -> Result<Self, String> {
+        let options = serde_json::from_str(&r.options_json)
+            .map_err(|e| format!("Custom field '{}' has unreadable options: {}", r.label, e))?;
+        Ok(CustomField {
+            id: r.id,
+            entity: r.entity,
+            key: r.key,
+            label: r.label,
+            field_type: r.field_type,
+            options,
+            required: r.required != 0,
+            active: r.active != 0,
+            position: r.position,
+            created_by: r.created_by,
+            created_at: r.created_at,
+        })
+    }
+}
+
+fn one_of(value: &str, allowed: &[&str], what: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    if allowed.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("{} must be one of {}, not '{}'", what, allowed.join(", "), value.trim()))
+    }
+}
+
+pub fn entity(value: &str) -> Result<String, String> {
+    one_of(value, ENTITIES, "Custom field entity")
+}
+
+pub fn field_type(value: &str) -> Result<String, String> {
+    one_of(value, TYPES, "Custom field type")
+}
+
+/// Field keys are lower-case letters, digits and underscores, starting with a letter
+/// ("PO Reference" becomes "po_reference").
+pub fn key(value: &str) -> Result<String, String> {
+    let mut key = String::new();
+    for c in value.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            key.push(c);
+        } else if !key.is_empty() && !key.ends_with('_') {
+            key.push('_');
+        }
+    }
+    let key = key.trim_end_matches('_').to_string();
+    if key.is_empty() || key.len() > 40 || !key.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(format!("Invalid custom field key '{}'", value.trim()));
+    }
+    Ok(key)
+}
+
+/// Enum options, trimmed, without blanks or repeats. Enum fields need at least one.
+pub fn options(field_type: &str, options: Vec<String>) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for o in options {
+        let o = o.trim().to_string();
+        if !o.is_empty() && !out.iter().any(|x| x.eq_ignore_ascii_case(&o)) {
+            out.push(o);
+        }
+    }
+    match (field_type, out.is_empty()) {
+        ("enum", true) => Err("A list field needs at least one option".to_string()),
+        ("enum", false) | (_, true) => Ok(out),
+        _ => Err("Only list fields take options".to_string()),
+    }
+}
+
+/// Definitions for `entity`, in display order.
+pub async fn load(conn: &mut SqliteConnection, entity: &str, include_inactive: bool) -> Result<Vec<CustomField>, String> {
+    let rows: Vec<CustomFieldRow> = sqlx::query_as(&format!(
+        "SELECT {COLUMNS} FROM custom_field_definitions
+         WHERE entity = ?1 AND (?2 OR active = 1)
+         ORDER BY position, id",
+    ))
+    .bind(entity)
+    .bind(include_inactive)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    rows.into_iter().map(CustomField::try_from).collect()
+}
+
+/// Normalise one value for its field: numbers may come as text, dates must be YYYY-MM-DD
+/// and list values are stored spelled as the option. Blank text and null clear the field.
+fn coerce(field: &CustomField, value: &Value) -> Result<Option<Value>, String> {
+    let text = match value {
+        Value::Null => return Ok(None),
+        Value::String(s) if s.trim().is_empty() => return Ok(None),
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return Err(format!("{} must be a single value", field.label)),
+    };
+    let invalid = || format!("{} must be a {}, not '{}'", field.label, field.field_type, text);
+    Ok(Some(match field.field_type.as_str() {
+        "number" => {
+            let n: f64 = text.replace(',', "").parse().map_err(|_| invalid())?;
+            serde_json::Number::from_f64(n).map(Value::Number).ok_or_else(invalid)?
+        }
+        "date" => {
+            NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| invalid())?;
+            Value::String(text)
+        }
+        "enum" => match field.options.iter().find(|o| o.eq_ignore_ascii_case(&text)) {
+            Some(o) => Value::String(o.clone()),
+            None => return Err(format!("{} must be one of {}, not '{}'", field.label, field.options.join(", "), text)),
+        },
+        _ => Value::String(text),
+    }))
+}
+
+/// Validate `given` values against the active definitions of `entity` and merge them over
+/// `existing`. Keys of inactive fields are carried over untouched; unknown keys are refused.
+/// Required fields must have a value afterwards.
+pub async fn check(
+    conn: &mut SqliteConnection,
+    entity: &str,
+    given: Option<CustomValues>,
+    existing: Option<CustomValues>,
+) -> Result<CustomValues, String> {
+    let fields = load(conn, entity, false).await?;
+    let mut values = existing.unwrap_or_default();
+    for (k, v) in given.unwrap_or_default() {
+        let field = fields
+            .iter()
+            .find(|f| f.key == k)
+            .ok_or_else(|| format!("Unknown custom field '{}'", k))?;
+        match coerce(field, &v)? {
+            Some(v) => values.insert(k, v),
+            None => values.remove(&k),
+        };
+    }
+    for f in fields.iter().filter(|f| f.required) {
+        if !values.contains_key(&f.key) {
+            return Err(format!("{} is required", f.label));
+        }
+    }
+    Ok(values)
+}
+
+fn bind_value<'a>(qb: &mut QueryBuilder<'a, Sqlite>, value: &Value) {
+    match value {
+        Value::Number(n) => qb.push_bind(n.as_f64().unwrap_or_default()),
+        Value::Bool(b) => qb.push_bind(b.to_string()),
+        Value::String(s) => qb.push_bind(s.clone()),
+        other => qb.push_bind(other.to_string()),
+    };
+}
+
+/// Append one `AND` condition per filter on the JSON values in `column`.
+pub fn push_filters<'a>(qb: &mut QueryBuilder<'a, Sqlite>, column: &str, filters: &[&CustomFieldFilter]) {
+    for f in filters {
+        let path = format!("$.\"{}\"", f.key.replace('"', ""));
+        let value_of = |qb: &mut QueryBuilder<'a, Sqlite>| {
+            qb.push(format!("json_extract({}, ", column)).push_bind(path.clone()).push(")");
+        };
+        match f.present {
+            Some(true) => {
+                qb.push(" AND ");
+                value_of(qb);
+                qb.push(" IS NOT NULL");
+            }
+            Some(false) => {
+                qb.push(" AND ");
+                value_of(qb);
+                qb.push(" IS NULL");
+            }
+            None => {}
+        }
+        if let Some(v) = &f.equals {
+            qb.push(" AND ");
+            value_of(qb);
+            qb.push(" = ");
+            bind_value(qb, v);
+            if v.is_string() {
+                qb.push(" COLLATE NOCASE");
+            }
+        }
+        if let Some(text) = &f.contains {
+            qb.push(" AND ");
+            value_of(qb);
+            qb.push(" LIKE ").push_bind(format!("%{}%", text));
+        }
+        if let Some(v) = &f.min {
+            qb.push(" AND ");
+            value_of(qb);
+            qb.push(" >= ");
+            bind_value(qb, v);
+        }
+        if let Some(v) = &f.max {
+            qb.push(" AND ");
+            value_of(qb);
+            qb.push(" <= ");
+            bind_value(qb, v);
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 use sqlx::{
-    migrate::MigrateError,
+    migrate::{MigrateError, Migrator},
     sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
     Pool, Sqlite,
 };
@@ -17,8 +18,51 @@ pub enum DbError {
 
 pub type DbPool = Pool<Sqlite>;
 
-#[derive(Clone)]
-pub struct Db(pub DbPool);
+/// Migrations embedded from the crate's migrations directory.
+/// This path is relative to the Cargo manifest dir (src-tauri)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Managed database state. The pool sits behind a lock so it can be swapped
+/// out after a restore without re-registering Tauri state.
+pub struct Db {
+    pool: RwLock<DbPool>,
+    path: PathBuf,
+}
+
+impl Db {
+    pub fn new(pool: DbPool, path: PathBuf) -> Self {
+        Self { pool: RwLock::new(pool), path }
+    }
+
+    /// Cheap handle to the current pool (pools are reference counted).
+    pub fn pool(&self) -> DbPool {
+        self.pool.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Path of the live database file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory holding `app.db` and its companion folders (backups, attachments).
+    pub fn data_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Install a new pool and return the previous one so the caller can close it.
+    pub fn replace(&self, pool: DbPool) -> DbPool {
+        let mut guard = self.pool.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *guard, pool)
+    }
+}
+
+/// Highest migration version bundled with this build.
+pub fn latest_migration_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
 
 /// Initialize SQLite connection pool and run migrations.
 pub async fn init(db_file: &Path) -> Result<DbPool, DbError> {
@@ -37,14 +81,20 @@ pub async fn init(db_file: &Path) -> Result<DbPool, DbError> {
 
     eprintln!("[DB] Opening SQLite at path: {:?}", db_file);
 
+    // Migrate through a connection of its own with foreign keys off: rebuilding a table that others
+    // reference is only safe that way, and the pragma can't be changed inside the transaction
+    // each migration runs in.
+    let migrations = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(opts.clone().foreign_keys(false))
+        .await?;
+    MIGRATOR.run(&migrations).await?;
+    migrations.close().await;
+
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
         .connect_with(opts)
         .await?;
 
-    // Run migrations embedded from the crate's migrations directory
-    // This path is relative to the Cargo manifest dir (src-tauri)
-    sqlx::migrate!("./migrations").run(&pool).await?;
-
     Ok(pool)
 }
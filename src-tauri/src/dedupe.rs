@@ -0,0 +1,267 @@
+use crate::models::{Supplier, SupplierDuplicatePair, SupplierMatch};
+
+/// Name similarity at or above this counts as a likely duplicate.
+pub const NAME_THRESHOLD: f64 = 0.85;
+
+/// Words that distinguish legal forms rather than parties ("ABC Traders" vs "ABC Traders Pvt Ltd").
+const NOISE_WORDS: &[&str] = &[
+    "m", "s", "ms", "the", "and", "pvt", "private", "ltd", "limited", "llp", "co", "company",
+    "corp", "corporation", "inc", "opc",
+];
+
+/// The identifiers a supplier is compared on.
+pub struct Probe<'a> {
+    pub name: &'a str,
+    pub gst_no: Option<&'a str>,
+    pub pan: Option<&'a str>,
+}
+
+impl<'a> From<&'a Supplier> for Probe<'a> {
+    fn from(s: &'a Supplier) -> Self {
+        Probe { name: &s.name, gst_no: s.gst_no.as_deref(), pan: s.pan.as_deref() }
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !NOISE_WORDS.contains(w))
+        .collect();
+    words.concat()
+}
+
+/// Dice coefficient over character bigrams of the normalised names.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (ba, mut bb) = (bigrams(&a), bigrams(&b));
+    if ba.is_empty() || bb.is_empty() {
+        return 0.0;
+    }
+    let total = (ba.len() + bb.len()) as f64;
+    let mut shared = 0;
+    for g in &ba {
+        if let Some(pos) = bb.iter().position(|x| x == g) {
+            bb.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total
+}
+
+fn clean(v: Option<&str>) -> Option<String> {
+    v.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty())
+}
+
+/// PAN given directly, else the one embedded in a 15-character GSTIN.
+fn effective_pan(p: &Probe) -> Option<String> {
+    clean(p.pan).or_else(|| {
+        clean(p.gst_no).filter(|g| g.len() == 15).and_then(|g| g.get(2..12).map(str::to_string))
+    })
+}
+
+/// Why two suppliers look like the same party, with a confidence score. `None` if they don't.
+pub fn compare(a: &Probe, b: &Probe) -> Option<(Vec<String>, f64)> {
+    let mut matched_on = Vec::new();
+    let mut score: f64 = 0.0;
+    if let (Some(x), Some(y)) = (clean(a.gst_no), clean(b.gst_no)) {
+        if x == y {
+            matched_on.push("gst_no".to_string());
+            score = 1.0;
+        }
+    }
+    if let (Some(x), Some(y)) = (effective_pan(a), effective_pan(b)) {
+        if x == y {
+            matched_on.push("pan".to_string());
+            score = score.max(0.95);
+        }
+    }
+    let similarity = name_similarity(a.name, b.name);
+    if similarity >= NAME_THRESHOLD {
+        matched_on.push("name".to_string());
+        score = score.max(similarity);
+    }
+    if matched_on.is_empty() {
+        None
+    } else {
+        Some((matched_on, score))
+    }
+}
+
+/// Existing suppliers that `probe` likely duplicates, best match first.
+pub fn matches(probe: &Probe, existing: Vec<Supplier>) -> Vec<SupplierMatch> {
+    let mut out: Vec<SupplierMatch> = existing
+        .into_iter()
+        .filter_map(|s| {
+            let (matched_on, score) = compare(probe, &Probe::from(&s))?;
+            Some(SupplierMatch { supplier: s, matched_on, score })
+        })
+        .collect();
+    out.sort_by(|x, y| y.score.total_cmp(&x.score));
+    out
+}
+
+/// Every pair of suppliers scoring at least `min_score`, best match first.
+pub fn pairs(suppliers: &[Supplier], min_score: f64) -> Vec<SupplierDuplicatePair> {
+    let mut out = Vec::new();
+    for (i, a) in suppliers.iter().enumerate() {
+        for b in &suppliers[i + 1..] {
+            if let Some((matched_on, score)) = compare(&Probe::from(a), &Probe::from(b)) {
+                if score >= min_score {
+                    out.push(SupplierDuplicatePair { a: a.clone(), b: b.clone(), matched_on, score });
+                }
+            }
+        }
+    }
+    out.sort_by(|x, y| y.score.total_cmp(&x.score));
+    out
+}
+
+/// A short human description of a match, e.g. "ABC Traders (same GSTIN)".
+pub fn describe(m: &SupplierMatch) -> String {
+    let reasons: Vec<&str> = m
+        .matched_on
+        .iter()
+        .map(|r| match r.as_str() {
+            "gst_no" => "same GSTIN",
+            "pan" => "same PAN",
+            _ => "similar name",
+        })
+        .collect();
+    format!("{} ({})", m.supplier.name, reasons.join(", "))
+}
+
+/// The party behind a supplier record: its PAN when known (directly or from the GSTIN),
+/// else the GSTIN, else the record itself. Suppliers with the same key are one party.
+pub fn party_key(supplier_id: i64, gst_no: Option<&str>, pan: Option<&str>) -> String {
+    let probe = Probe { name: "", gst_no, pan };
+    effective_pan(&probe)
+        .or_else(|| clean(gst_no))
+        .unwrap_or_else(|| format!("#{}", supplier_id))
+}
+
+/// A four-digit 19xx/20xx year, or a two-digit one within a year of `near` (the
+/// invoice date's year mod 100). Without a date two-digit numbers are never years.
+fn is_year(t: &str, near: Option<u32>) -> bool {
+    match t.len() {
+        2 => near
+            .zip(t.parse::<u32>().ok())
+            .is_some_and(|(yy, n)| (n + 100 - yy) % 100 <= 1 || (yy + 100 - n) % 100 <= 1),
+        4 => t.starts_with("19") || t.starts_with("20"),
+        _ => false,
+    }
+}
+
+/// "23-24", "2023-24" and "2023-2024" style financial years, optionally as "FY2023-24".
+fn is_fy_pair(a: &str, b: &str, near: Option<u32>) -> bool {
+    let a = a.strip_prefix("FY").unwrap_or(a);
+    let num = |t: &str| t.parse::<u32>().ok().filter(|_| is_year(t, near)).map(|n| n % 100);
+    matches!((num(a), num(b)), (Some(x), Some(y)) if (x + 1) % 100 == y)
+}
+
+/// "FY24", "FY2024" and a bare "FY" marker.
+fn is_fy_token(t: &str, near: Option<u32>) -> bool {
+    t.strip_prefix("FY")
+        .is_some_and(|rest| rest.is_empty() || (rest.chars().all(|c| c.is_ascii_digit()) && is_year(rest, near)))
+}
+
+/// Canonical form of an invoice number for duplicate checks: case, separators, leading
+/// zeros and financial-year prefixes or suffixes are ignored, so "INV/001/23-24",
+/// "inv-1" and "INV001" all give "INV1". Two-digit years are only recognised near the
+/// year of `date` (YYYY-MM-DD), so "INV/12-13" dated 2024 keeps its numbers. Numbers that
+/// were separated stay apart, so "INV/1/23" and "INV/12/3" differ.
+pub fn invoice_key(invoice_no: &str, date: &str) -> String {
+    let near = date.get(..4).and_then(|y| y.parse::<u32>().ok()).map(|y| y % 100);
+    let upper = invoice_no.to_uppercase();
+    let mut tokens: Vec<&str> = upper.split(|c: char| !c.is_ascii_alphanumeric()).filter(|t| !t.is_empty()).collect();
+
+    // Trim financial years off either end, keeping at least one token
+    loop {
+        let n = tokens.len();
+        if n >= 3 && is_fy_pair(tokens[n - 2], tokens[n - 1], near) {
+            tokens.truncate(n - 2);
+        } else if n >= 2 && is_fy_token(tokens[n - 1], near) {
+            tokens.truncate(n - 1);
+        } else if n >= 3 && is_fy_pair(tokens[0], tokens[1], near) {
+            tokens.drain(..2);
+        } else if n >= 2 && is_fy_token(tokens[0], near) {
+            tokens.remove(0);
+        } else {
+            break;
+        }
+    }
+
+    // Drop leading zeros from every run of digits
+    let mut key = String::with_capacity(upper.len());
+    for token in tokens {
+        if key.ends_with(|c: char| c.is_ascii_digit()) && token.starts_with(|c: char| c.is_ascii_digit()) {
+            key.push('-');
+        }
+        let mut in_digits = false;
+        let mut leading = true;
+        for c in token.chars() {
+            if c.is_ascii_digit() {
+                if !in_digits {
+                    in_digits = true;
+                    leading = true;
+                }
+                if leading && c == '0' {
+                    continue;
+                }
+                leading = false;
+            } else {
+                if in_digits && leading {
+                    key.push('0'); // the run was all zeros
+                }
+                in_digits = false;
+            }
+            key.push(c);
+        }
+        if in_digits && leading {
+            key.push('0');
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invoice_key_ignores_case_separators_zeros_and_years() {
+        assert_eq!(invoice_key("INV/001/23-24", "2023-06-01"), "INV1");
+        assert_eq!(invoice_key("inv-1", "2023-06-01"), "INV1");
+        assert_eq!(invoice_key("INV001", "2023-06-01"), "INV1");
+        assert_eq!(invoice_key("FY2023-24/INV-7", "2023-06-01"), "INV7");
+        assert_eq!(invoice_key("INV-000", ""), "INV0");
+    }
+
+    #[test]
+    fn invoice_key_keeps_separated_numbers_apart() {
+        assert_ne!(invoice_key("INV/1/23", ""), invoice_key("INV/12/3", ""));
+        assert_eq!(invoice_key("INV/1/23", ""), "INV1-23");
+        assert_eq!(invoice_key("1 005", ""), invoice_key("1-5", ""));
+        // Two-digit years far from the invoice date are kept as numbers
+        assert_eq!(invoice_key("INV/12-13", "2024-01-10"), "INV12-13");
+    }
+
+    #[test]
+    fn name_similarity_ignores_legal_forms() {
+        assert_eq!(name_similarity("ABC Traders", "M/s. ABC Traders Pvt Ltd"), 1.0);
+        assert!(name_similarity("ABC Traders", "ABC Trader") >= NAME_THRESHOLD);
+        assert!(name_similarity("ABC Traders", "XYZ Steels") < NAME_THRESHOLD);
+        assert_eq!(name_similarity("Pvt Ltd", "ABC"), 0.0);
+        assert_eq!(name_similarity("A", "B"), 0.0);
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::SqliteConnection;
+
+use crate::models::AllocationInput;
+
+/// Dimension kinds, matching the CHECK constraint on `dimensions.kind`.
+pub const KINDS: &[&str] = &["cost_center", "department", "project"];
+
+/// Splits of one kind may miss 100% by this much (rounding in thirds and the like).
+const PERCENT_TOLERANCE: f64 = 0.01;
+
+pub fn kind(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase().replace([' ', '-'], "_");
+    if KINDS.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Dimension kind must be cost_center, department or project, not '{}'", value.trim()))
+    }
+}
+
+fn label(kind: &str) -> &str {
+    match kind {
+        "cost_center" => "cost center",
+        other => other,
+    }
+}
+
+/// Replace the splits of an entry (`item_id` None) or of one of its lines with `splits`.
+/// Each kind present must add up to 100%. Inactive dimensions are refused unless the
+/// target already carried them.
+pub async fn replace(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    item_id: Option<i64>,
+    splits: &[AllocationInput],
+) -> Result<(), String> {
+    let current: HashSet<i64> = sqlx::query_as::<_, (i64,)>(
+        "SELECT dimension_id FROM purchase_allocations WHERE purchase_id = ?1 AND item_id IS ?2",
+    )
+    .bind(purchase_id)
+    .bind(item_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(id,)| id)
+    .collect();
+
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    let mut seen = HashSet::new();
+    for split in splits {
+        if !(split.percent > 0.0 && split.percent <= 100.0) {
+            return Err(format!("Split percentages must be above 0 and at most 100, not {}", split.percent));
+        }
+        if !seen.insert(split.dimension_id) {
+            return Err("Each dimension can appear only once in a split".to_string());
+        }
+        let found: Option<(String, String, i64)> =
+            sqlx::query_as("SELECT kind, code, active FROM dimensions WHERE id = ?1")
+                .bind(split.dimension_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        let (kind, code, active) = found.ok_or_else(|| format!("Dimension {} not found", split.dimension_id))?;
+        if active == 0 && !current.contains(&split.dimension_id) {
+            return Err(format!("The {} '{}' is inactive", label(&kind), code));
+        }
+        *totals.entry(kind).or_default() += split.percent;
+    }
+    for (kind, total) in &totals {
+        if (total - 100.0).abs() > PERCENT_TOLERANCE {
+            return Err(format!("The {} split adds up to {:.2}%, not 100%", label(kind), total));
+        }
+    }
+
+    sqlx::query("DELETE FROM purchase_allocations WHERE purchase_id = ?1 AND item_id IS ?2")
+        .bind(purchase_id)
+        .bind(item_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for split in splits {
+        sqlx::query(
+            "INSERT INTO purchase_allocations (purchase_id, item_id, dimension_id, percent) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(purchase_id)
+        .bind(item_id)
+        .bind(split.dimension_id)
+        .bind(split.percent)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
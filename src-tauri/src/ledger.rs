@@ -0,0 +1,315 @@
+use chrono::{Datelike, NaiveDate};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use sqlx::SqliteConnection;
+
+use crate::models::{LedgerLine, SupplierLedger};
+
+/// Supplier note kinds, matching the CHECK constraint on `supplier_notes.kind`.
+pub const NOTE_KINDS: &[&str] = &["debit", "credit"];
+
+pub fn note_kind(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    if NOTE_KINDS.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Note must be a debit or credit note, not '{}'", value.trim()))
+    }
+}
+
+pub fn parse_date(value: &str, what: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| format!("{} '{}' must be YYYY-MM-DD", what, value.trim()))
+}
+
+/// First day of the financial year (April to March) holding `date`.
+pub fn fy_start(date: NaiveDate) -> NaiveDate {
+    let year = if date.month() >= 4 { date.year() } else { date.year() - 1 };
+    NaiveDate::from_ymd_opt(year, 4, 1).unwrap_or(date)
+}
+
+/// Invoices, TDS, notes and payments of a supplier dated from `from` (when given) up to but
+/// not including `before`, in date order. Live, uncancelled entries only.
+async fn movements(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    from: Option<&str>,
+    before: &str,
+) -> Result<Vec<LedgerLine>, String> {
+    sqlx::query_as::<_, LedgerLine>(
+        "SELECT date, kind, reference, narration, debit, credit, purchase_id, payment_id, note_id FROM (
+            SELECT pe.date AS date, 1 AS seq, pe.id AS ord, 'invoice' AS kind, pe.invoice_no AS reference,
+                   pe.narration AS narration, 0.0 AS debit, pe.invoice_value AS credit,
+                   pe.id AS purchase_id, NULL AS payment_id, NULL AS note_id
+            FROM purchase_entries pe
+            WHERE pe.supplier_id = ?1 AND pe.deleted_at IS NULL AND pe.status <> 'cancelled'
+            UNION ALL
+            SELECT pe.date, 2, pe.id, 'tds', pe.invoice_no, 'TDS deducted on invoice', pe.tds_value, 0.0, pe.id, NULL, NULL
+            FROM purchase_entries pe
+            WHERE pe.supplier_id = ?1 AND pe.deleted_at IS NULL AND pe.status <> 'cancelled' AND pe.tds_value > 0
+            UNION ALL
+            SELECT n.note_date, 3, n.id, n.kind || '_note', n.note_no, n.reason,
+                   CASE WHEN n.kind = 'debit' THEN n.amount ELSE 0.0 END,
+                   CASE WHEN n.kind = 'credit' THEN n.amount ELSE 0.0 END,
+                   n.purchase_id, NULL, n.id
+            FROM supplier_notes n WHERE n.supplier_id = ?1
+            UNION ALL
+            SELECT sp.payment_date, 4, sp.id, 'payment', sp.reference, sp.narration, sp.amount, 0.0, NULL, sp.id, NULL
+            FROM supplier_payments sp WHERE sp.supplier_id = ?1 AND sp.amount > 0
+            UNION ALL
+            SELECT sp.payment_date, 5, sp.id, 'tds', sp.reference, 'TDS deducted on payment', sp.tds_amount, 0.0, NULL, sp.id, NULL
+            FROM supplier_payments sp WHERE sp.supplier_id = ?1 AND sp.tds_amount > 0
+         )
+         WHERE (?2 IS NULL OR date >= ?2) AND date < ?3
+         ORDER BY date, seq, ord",
+    )
+    .bind(supplier_id)
+    .bind(from)
+    .bind(before)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn round(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// The supplier's statement for `date_from` to `date_to`, both inclusive. The opening balance
+/// starts from the latest opening balance entered for a financial year beginning on or before
+/// `date_from`, or from the supplier's first transaction when there is none. An opening balance
+/// entered for a year starting inside the period resets the running balance there.
+pub async fn statement(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    date_from: NaiveDate,
+    date_to: NaiveDate,
+) -> Result<SupplierLedger, String> {
+    if date_to < date_from {
+        return Err("The statement can't end before it starts".to_string());
+    }
+    let (supplier_name, gst_no): (String, Option<String>) = sqlx::query_as("SELECT name, gst_no FROM suppliers WHERE id = ?1")
+        .bind(supplier_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Supplier not found".to_string())?;
+
+    let from = date_from.format("%Y-%m-%d").to_string();
+    let to = date_to.format("%Y-%m-%d").to_string();
+    let after_to = date_to.succ_opt().unwrap_or(date_to).format("%Y-%m-%d").to_string();
+
+    let anchor: Option<(String, f64)> = sqlx::query_as(
+        "SELECT fy_start, amount FROM supplier_opening_balances
+         WHERE supplier_id = ?1 AND fy_start <= ?2 ORDER BY fy_start DESC LIMIT 1",
+    )
+    .bind(supplier_id)
+    .bind(&from)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (start, carried) = match anchor {
+        Some((fy_start, amount)) => (Some(fy_start), amount),
+        None => (None, 0.0),
+    };
+    let before = movements(conn, supplier_id, start.as_deref(), &from).await?;
+    let opening_balance = round(carried + before.iter().map(|l| l.credit - l.debit).sum::<f64>());
+
+    // Opening balances of years starting inside the period, each ahead of that day's movements
+    let resets: Vec<(String, String, f64, Option<String>)> = sqlx::query_as(
+        "SELECT fy_start, printf('%d-%02d', CAST(substr(fy_start, 1, 4) AS INTEGER), (CAST(substr(fy_start, 1, 4) AS INTEGER) + 1) % 100),
+                amount, narration
+         FROM supplier_opening_balances
+         WHERE supplier_id = ?1 AND fy_start > ?2 AND fy_start <= ?3 ORDER BY fy_start",
+    )
+    .bind(supplier_id)
+    .bind(&from)
+    .bind(&to)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut resets = resets.into_iter().peekable();
+
+    let mut lines = Vec::new();
+    let mut balance = opening_balance;
+    for mut line in movements(conn, supplier_id, Some(&from), &after_to).await? {
+        while let Some((fy_start, fy, amount, narration)) = resets.next_if(|r| r.0 <= line.date) {
+            lines.push(reset_line(&fy_start, &fy, amount, narration, balance));
+            balance = amount;
+        }
+        balance = round(balance + line.credit - line.debit);
+        line.balance = balance;
+        lines.push(line);
+    }
+    for (fy_start, fy, amount, narration) in resets {
+        lines.push(reset_line(&fy_start, &fy, amount, narration, balance));
+        balance = amount;
+    }
+
+    Ok(SupplierLedger {
+        supplier_id,
+        supplier_name,
+        gst_no,
+        date_from: from,
+        date_to: to,
+        opening_balance,
+        total_debit: round(lines.iter().map(|l| l.debit).sum()),
+        total_credit: round(lines.iter().map(|l| l.credit).sum()),
+        closing_balance: balance,
+        lines,
+    })
+}
+
+/// A line taking the running balance from `balance` to the opening balance of a new year.
+fn reset_line(date: &str, fy: &str, amount: f64, narration: Option<String>, balance: f64) -> LedgerLine {
+    let diff = round(amount - balance);
+    LedgerLine {
+        date: date.to_string(),
+        kind: "opening".to_string(),
+        reference: Some(format!("FY {}", fy)),
+        narration,
+        debit: if diff < 0.0 { -diff } else { 0.0 },
+        credit: if diff > 0.0 { diff } else { 0.0 },
+        balance: amount,
+        purchase_id: None,
+        payment_id: None,
+        note_id: None,
+    }
+}
+
+/// How a line reads on a printed statement.
+pub fn particulars(line: &LedgerLine) -> String {
+    let label = match line.kind.as_str() {
+        "opening" => "Opening balance",
+        "invoice" => "Invoice",
+        "tds" => return line.narration.clone().unwrap_or_else(|| "TDS deducted".to_string()),
+        "payment" => "Payment",
+        "debit_note" => "Debit note",
+        "credit_note" => "Credit note",
+        other => other,
+    };
+    match line.narration.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(n) => format!("{} - {}", label, n),
+        None => label.to_string(),
+    }
+}
+
+/// A balance as accountants write it: Cr when owed to the supplier, Dr when they owe us.
+pub fn balance_text(balance: f64) -> String {
+    if balance.abs() < 0.005 {
+        "0.00".to_string()
+    } else if balance > 0.0 {
+        format!("{:.2} Cr", balance)
+    } else {
+        format!("{:.2} Dr", -balance)
+    }
+}
+
+/// Courier only covers plain ASCII; fit a cell to `width` characters.
+fn cell(text: &str, width: usize) -> String {
+    let ascii: String = text.chars().map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '?' }).collect();
+    if ascii.len() > width {
+        format!("{}~", &ascii[..width - 1])
+    } else {
+        ascii
+    }
+}
+
+/// Write the statement as an A4 PDF for sending to the supplier.
+pub fn write_pdf(ledger: &SupplierLedger, path: &str) -> Result<(), String> {
+    let row = |date: &str, particulars: &str, reference: &str, debit: &str, credit: &str, balance: &str| {
+        format!(
+            "{:<10} {:<28} {:<14} {:>12} {:>12} {:>14}",
+            cell(date, 10),
+            cell(particulars, 28),
+            cell(reference, 14),
+            debit,
+            credit,
+            balance
+        )
+    };
+    let amount = |v: f64| if v == 0.0 { String::new() } else { format!("{:.2}", v) };
+
+    let mut text = vec![
+        "STATEMENT OF ACCOUNT".to_string(),
+        String::new(),
+        cell(&ledger.supplier_name, 95),
+        format!("GSTIN: {}", cell(ledger.gst_no.as_deref().unwrap_or("-"), 80)),
+        format!("Period: {} to {}", ledger.date_from, ledger.date_to),
+        String::new(),
+        row("Date", "Particulars", "Reference", "Debit", "Credit", "Balance"),
+        "-".repeat(95),
+        row(&ledger.date_from, "Opening balance", "", "", "", &balance_text(ledger.opening_balance)),
+    ];
+    for l in &ledger.lines {
+        text.push(row(
+            &l.date,
+            &particulars(l),
+            l.reference.as_deref().unwrap_or(""),
+            &amount(l.debit),
+            &amount(l.credit),
+            &balance_text(l.balance),
+        ));
+    }
+    text.push("-".repeat(95));
+    text.push(row("", "Total", "", &amount(ledger.total_debit), &amount(ledger.total_credit), ""));
+    text.push(row(&ledger.date_to, "Closing balance", "", "", "", &balance_text(ledger.closing_balance)));
+    text.push(String::new());
+    text.push("Please confirm the closing balance or let us know of any difference.".to_string());
+
+    const LINES_PER_PAGE: usize = 62;
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Courier",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+
+    let chunks: Vec<&[String]> = text.chunks(LINES_PER_PAGE).collect();
+    let page_count = chunks.len();
+    let mut kids = Vec::with_capacity(page_count);
+    for (n, chunk) in chunks.into_iter().enumerate() {
+        let mut operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 9.into()]),
+            Operation::new("TL", vec![12.into()]),
+            Operation::new("Td", vec![40.into(), 800.into()]),
+        ];
+        let footer = format!("Page {} of {}", n + 1, page_count);
+        for line in chunk.iter().chain(std::iter::once(&String::new())).chain(std::iter::once(&footer)) {
+            operations.push(Operation::new("Tj", vec![Object::string_literal(line.as_str())]));
+            operations.push(Operation::new("T*", vec![]));
+        }
+        operations.push(Operation::new("ET", vec![]));
+        let content = Content { operations }.encode().map_err(|e| e.to_string())?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count as i64,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.compress();
+    doc.save(path).map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -1,6 +1,23 @@
+mod ageing;
+mod approvals;
+mod archive;
+mod attachments;
+mod backup;
 mod db;
+mod dedupe;
+mod dimensions;
+mod ledger;
+mod matching;
 mod models;
 mod commands;
+mod custom_fields;
+mod payments;
+mod pricing;
+mod revisions;
+mod suggest;
+mod uom;
+mod validation;
+mod workflow;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -36,8 +53,8 @@ pub fn run() {
 
             // Try primary
             eprintln!("[DB] primary DB path: {:?}", &primary_db_path);
-            let pool = match tauri::async_runtime::block_on(async { db::init(&primary_db_path).await }) {
-                Ok(p) => p,
+            let (pool, db_path) = match tauri::async_runtime::block_on(async { db::init(&primary_db_path).await }) {
+                Ok(p) => (p, primary_db_path),
                 Err(err_primary) => {
                     eprintln!("[DB] primary DB init failed at {:?}: {}", &primary_db_path, err_primary);
                     // Fallback to ./data/app.db
@@ -50,7 +67,7 @@ pub fn run() {
                     let fallback_db_path = fallback_dir.join("app.db");
                     eprintln!("[DB] fallback DB path: {:?}", &fallback_db_path);
                     match tauri::async_runtime::block_on(async { db::init(&fallback_db_path).await }) {
-                        Ok(p) => p,
+                        Ok(p) => (p, fallback_db_path),
                         Err(err_fallback) => return Err(format!("DB init failed. primary: {:?} => {}; fallback: {:?} => {}",
                             &primary_db_path, err_primary, &fallback_db_path, err_fallback).into()),
                     }
@@ -60,7 +77,10 @@ pub fn run() {
             // Proceed to manage state
 
             // Manage DB state for later command handlers
-            app_handle.manage(db::Db(pool));
+            app_handle.manage(db::Db::new(pool, db_path));
+
+            // Scheduled backups run in the background for the lifetime of the app
+            backup::spawn_scheduler(app_handle.clone());
 
             Ok(())
         })
@@ -79,20 +99,168 @@ pub fn run() {
             crate::commands::add_supplier,
             crate::commands::update_supplier,
             crate::commands::delete_supplier,
+            crate::commands::set_supplier_active,
+            crate::commands::check_supplier_duplicates,
+            crate::commands::find_duplicate_suppliers,
+            crate::commands::merge_suppliers,
+            crate::commands::get_supplier_merges,
+            crate::commands::get_supplier_details,
+            crate::commands::add_supplier_address,
+            crate::commands::update_supplier_address,
+            crate::commands::delete_supplier_address,
+            crate::commands::add_supplier_bank_account,
+            crate::commands::update_supplier_bank_account,
+            crate::commands::set_supplier_bank_account_verification,
+            crate::commands::delete_supplier_bank_account,
+            crate::commands::add_supplier_contact,
+            crate::commands::update_supplier_contact,
+            crate::commands::delete_supplier_contact,
             crate::commands::import_suppliers_from_excel,
             crate::commands::generate_supplier_template,
             // Purchases & Items
             crate::commands::get_purchases,
             crate::commands::add_purchase,
+            crate::commands::check_purchase_duplicates,
             crate::commands::update_purchase,
             crate::commands::delete_purchase,
             crate::commands::get_items_by_purchase,
             crate::commands::add_item,
             crate::commands::update_item,
+            // Purchase workflow
+            crate::commands::get_status_transitions,
+            crate::commands::set_status_transition,
+            crate::commands::delete_status_transition,
+            crate::commands::get_available_transitions,
+            crate::commands::transition_purchase,
+            crate::commands::get_purchase_status_history,
+            // Approvals
+            crate::commands::get_approval_rules,
+            crate::commands::add_approval_rule,
+            crate::commands::update_approval_rule,
+            crate::commands::delete_approval_rule,
+            crate::commands::get_approval_inbox,
+            crate::commands::approve_purchase,
+            crate::commands::reject_purchase,
+            crate::commands::get_purchase_approvals,
+            // Purchase orders and goods receipts
+            crate::commands::get_purchase_orders,
+            crate::commands::get_purchase_order,
+            crate::commands::add_purchase_order,
+            crate::commands::set_purchase_order_status,
+            crate::commands::delete_purchase_order,
+            crate::commands::get_goods_receipts,
+            crate::commands::get_goods_receipt,
+            crate::commands::add_goods_receipt,
+            crate::commands::delete_goods_receipt,
+            crate::commands::get_purchase_match_exceptions,
+            crate::commands::get_match_exception_report,
+            crate::commands::get_match_settings,
+            crate::commands::update_match_settings,
+            crate::commands::get_open_po_report,
+            crate::commands::get_pending_grn_report,
+            // Payments
+            crate::commands::get_payments,
+            crate::commands::get_payment,
+            crate::commands::add_payment,
+            crate::commands::update_payment,
+            crate::commands::delete_payment,
+            crate::commands::get_purchase_payments,
+            crate::commands::get_supplier_outstanding,
+            // Payables ageing
+            crate::commands::get_payables_ageing,
+            crate::commands::get_payables_ageing_invoices,
+            crate::commands::export_payables_ageing,
+            // Supplier ledger
+            crate::commands::get_opening_balances,
+            crate::commands::set_opening_balance,
+            crate::commands::delete_opening_balance,
+            crate::commands::get_supplier_notes,
+            crate::commands::add_supplier_note,
+            crate::commands::update_supplier_note,
+            crate::commands::delete_supplier_note,
+            crate::commands::get_supplier_ledger,
+            crate::commands::export_supplier_ledger,
+            crate::commands::export_supplier_ledger_pdf,
+            // Custom fields
+            crate::commands::get_custom_fields,
+            crate::commands::add_custom_field,
+            crate::commands::update_custom_field,
+            crate::commands::delete_custom_field,
+            // Dimensions
+            crate::commands::get_dimensions,
+            crate::commands::add_dimension,
+            crate::commands::update_dimension,
+            crate::commands::delete_dimension,
+            crate::commands::get_purchase_allocations,
+            crate::commands::set_purchase_allocations,
+            // Attachments
+            crate::commands::add_attachment,
+            crate::commands::get_attachments,
+            crate::commands::get_attachment_uses,
+            crate::commands::open_attachment,
+            crate::commands::remove_attachment,
+            // Revisions
+            crate::commands::get_purchase_revisions,
+            crate::commands::get_purchase_revision,
+            crate::commands::diff_purchase_revisions,
+            crate::commands::revert_purchase,
+            // Recycle bin
+            crate::commands::get_recycle_bin,
+            crate::commands::restore_purchase,
+            crate::commands::restore_supplier,
+            crate::commands::purge_recycle_bin,
             // Reports
             crate::commands::get_report_summary,
             crate::commands::get_purchases_by_supplier,
+            crate::commands::get_spend_by_dimension,
+            crate::commands::get_duplicate_invoice_report,
             crate::commands::export_purchases,
+            // Item master
+            crate::commands::get_master_items,
+            crate::commands::get_master_item,
+            crate::commands::add_master_item,
+            crate::commands::update_master_item,
+            crate::commands::delete_master_item,
+            crate::commands::import_master_items,
+            crate::commands::suggest_items,
+            // Units of measure
+            crate::commands::get_uoms,
+            crate::commands::add_uom,
+            crate::commands::update_uom,
+            crate::commands::get_uom_aliases,
+            crate::commands::set_uom_alias,
+            crate::commands::delete_uom_alias,
+            crate::commands::get_uom_conversions,
+            crate::commands::set_uom_conversion,
+            crate::commands::delete_uom_conversion,
+            crate::commands::normalize_units,
+            crate::commands::get_quantity_report,
+            // Prices
+            crate::commands::get_price_history,
+            crate::commands::check_price_variances,
+            crate::commands::get_purchase_price_variances,
+            crate::commands::get_price_variance_report,
+            crate::commands::get_price_variance_settings,
+            crate::commands::update_price_variance_settings,
+            // Saved views
+            crate::commands::get_saved_views,
+            crate::commands::get_saved_view,
+            crate::commands::get_default_saved_view,
+            crate::commands::create_saved_view,
+            crate::commands::update_saved_view,
+            crate::commands::delete_saved_view,
+            crate::commands::set_default_saved_view,
+            // Search
+            crate::commands::global_search,
+            // Backups
+            crate::commands::create_backup,
+            crate::commands::list_backups,
+            crate::commands::restore_backup,
+            crate::commands::get_backup_settings,
+            crate::commands::update_backup_settings,
+            crate::commands::export_backup_archive,
+            crate::commands::inspect_backup_archive,
+            crate::commands::import_backup_archive,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,279 @@
+use std::collections::BTreeSet;
+
+use sqlx::SqliteConnection;
+
+use crate::models::{MatchException, MatchSettings};
+
+/// Purchase order statuses, matching the CHECK constraint on `purchase_orders.status`.
+pub const PO_STATUSES: &[&str] = &["open", "closed", "cancelled"];
+
+/// Slack for float sums so a line billed exactly at the limit isn't flagged.
+const EPSILON: f64 = 1e-9;
+
+pub fn po_status(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    if PO_STATUSES.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Purchase order status must be open, closed or cancelled, not '{}'", value.trim()))
+    }
+}
+
+pub async fn load_settings(conn: &mut SqliteConnection) -> Result<MatchSettings, String> {
+    sqlx::query_as::<_, MatchSettings>("SELECT qty_tolerance_pct, price_tolerance_pct FROM match_settings WHERE id = 1")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// What an invoice line bills, once checked.
+pub struct Link {
+    pub po_line_id: Option<i64>,
+    pub grn_line_id: Option<i64>,
+    pub part_no: Option<String>, // part of the linked line
+}
+
+/// Check the PO and GRN lines an invoice line points at: both must be `supplier_id`'s, agree
+/// with each other and not sit on a cancelled order. A GRN line received against a PO line
+/// brings that PO line along. A part number given on the invoice line must be the linked one.
+pub async fn resolve(
+    conn: &mut SqliteConnection,
+    supplier_id: i64,
+    part_no: Option<&str>,
+    po_line_id: Option<i64>,
+    grn_line_id: Option<i64>,
+) -> Result<Link, String> {
+    let mut po_line_id = po_line_id;
+    let mut linked_part = None;
+
+    if let Some(id) = grn_line_id {
+        let row: Option<(i64, String, Option<i64>, String)> = sqlx::query_as(
+            "SELECT g.supplier_id, g.grn_no, gl.po_line_id, gl.part_no
+             FROM goods_receipt_lines gl JOIN goods_receipts g ON g.id = gl.grn_id
+             WHERE gl.id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        let (grn_supplier, grn_no, grn_po_line, part) = row.ok_or_else(|| format!("GRN line {} not found", id))?;
+        if grn_supplier != supplier_id {
+            return Err(format!("GRN {} is from another supplier", grn_no));
+        }
+        match (po_line_id, grn_po_line) {
+            (Some(given), Some(received)) if given != received => {
+                return Err(format!("GRN {} received that line against a different PO line", grn_no));
+            }
+            (None, received) => po_line_id = received,
+            _ => {}
+        }
+        linked_part = Some(part);
+    }
+
+    if let Some(id) = po_line_id {
+        let row: Option<(i64, String, String, String)> = sqlx::query_as(
+            "SELECT po.supplier_id, po.po_no, po.status, l.part_no
+             FROM purchase_order_lines l JOIN purchase_orders po ON po.id = l.po_id
+             WHERE l.id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        let (po_supplier, po_no, status, part) = row.ok_or_else(|| format!("PO line {} not found", id))?;
+        if po_supplier != supplier_id {
+            return Err(format!("PO {} is from another supplier", po_no));
+        }
+        if status == "cancelled" {
+            return Err(format!("PO {} is cancelled", po_no));
+        }
+        linked_part = Some(part);
+    }
+
+    if let (Some(given), Some(linked)) = (part_no.map(str::trim).filter(|p| !p.is_empty()), &linked_part) {
+        if !given.eq_ignore_ascii_case(linked) {
+            return Err(format!("Part '{}' is billed against a line for '{}'", given, linked));
+        }
+    }
+    Ok(Link { po_line_id, grn_line_id, part_no: linked_part })
+}
+
+/// Fail if any line of the entry bills a PO or GRN of a supplier other than the entry's.
+pub async fn check_supplier(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    let stray: Option<(String,)> = sqlx::query_as(
+        "SELECT COALESCE(po.po_no, g.grn_no)
+         FROM purchase_items pi
+         JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         LEFT JOIN purchase_order_lines l ON l.id = pi.po_line_id
+         LEFT JOIN purchase_orders po ON po.id = l.po_id AND po.supplier_id <> pe.supplier_id
+         LEFT JOIN goods_receipt_lines gl ON gl.id = pi.grn_line_id
+         LEFT JOIN goods_receipts g ON g.id = gl.grn_id AND g.supplier_id <> pe.supplier_id
+         WHERE pi.purchase_id = ?1 AND (po.id IS NOT NULL OR g.id IS NOT NULL)
+         LIMIT 1",
+    )
+    .bind(purchase_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    match stray {
+        Some((doc,)) => Err(format!(
+            "Lines of this entry bill {} of another supplier; unlink them before changing the supplier",
+            doc
+        )),
+        None => Ok(()),
+    }
+}
+
+fn deviation_pct(actual: f64, expected: f64) -> Option<f64> {
+    (expected > 0.0).then(|| (actual - expected) / expected * 100.0)
+}
+
+/// Quantity billed against a PO or GRN line by live, uncancelled entries up to and including
+/// `purchase_id`, so an entry is judged by what had been billed when it came in.
+async fn billed(conn: &mut SqliteConnection, grn: bool, line_id: i64, purchase_id: i64) -> Result<f64, String> {
+    let sql = if grn {
+        "SELECT COALESCE(SUM(pi.qty), 0.0) FROM purchase_items pi JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         WHERE pi.grn_line_id = ?1 AND pe.id <= ?2
+           AND (pe.id = ?2 OR (pe.deleted_at IS NULL AND pe.status <> 'cancelled'))"
+    } else {
+        "SELECT COALESCE(SUM(pi.qty), 0.0) FROM purchase_items pi JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         WHERE pi.po_line_id = ?1 AND pe.id <= ?2
+           AND (pe.id = ?2 OR (pe.deleted_at IS NULL AND pe.status <> 'cancelled'))"
+    };
+    let (qty,): (f64,) = sqlx::query_as(sql)
+        .bind(line_id)
+        .bind(purchase_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(qty)
+}
+
+/// Part number, price and PO and GRN links of an invoice line.
+type InvoiceLine = (Option<String>, f64, Option<i64>, Option<i64>);
+
+/// Compare every linked line of an entry with its PO and GRN: price against the agreed price,
+/// quantity billed so far against what was ordered and what was received.
+pub async fn check(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Vec<MatchException>, String> {
+    let settings = load_settings(conn).await?;
+    let rows: Vec<InvoiceLine> = sqlx::query_as(
+        "SELECT part_no, price, po_line_id, grn_line_id FROM purchase_items WHERE purchase_id = ?1 ORDER BY id ASC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for (i, (part_no, price, po_line_id, grn_line_id)) in rows.into_iter().enumerate() {
+        let line_no = i as i64 + 1;
+        let flag = |kind: &str, expected: f64, actual: f64, tolerance_pct: f64| MatchException {
+            line_no,
+            part_no: part_no.clone(),
+            kind: kind.to_string(),
+            expected,
+            actual,
+            deviation_pct: deviation_pct(actual, expected),
+            tolerance_pct,
+            po_line_id,
+            grn_line_id,
+        };
+        let over = |actual: f64, limit: f64, tolerance_pct: f64| actual > limit * (1.0 + tolerance_pct / 100.0) + EPSILON;
+
+        let mut billed_on_po = None;
+        if let Some(po_line) = po_line_id {
+            let (ordered, agreed): (f64, f64) = sqlx::query_as("SELECT qty, price FROM purchase_order_lines WHERE id = ?1")
+                .bind(po_line)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if (price - agreed).abs() > agreed * settings.price_tolerance_pct / 100.0 + EPSILON {
+                out.push(flag("price", agreed, price, settings.price_tolerance_pct));
+            }
+            let qty = billed(conn, false, po_line, purchase_id).await?;
+            if over(qty, ordered, settings.qty_tolerance_pct) {
+                out.push(flag("qty_ordered", ordered, qty, settings.qty_tolerance_pct));
+            }
+            billed_on_po = Some(qty);
+        }
+
+        // Against the GRN line billed, or everything received on the PO line when none is named
+        let received = match (grn_line_id, po_line_id, billed_on_po) {
+            (Some(grn_line), _, _) => {
+                let (received,): (f64,) = sqlx::query_as("SELECT qty FROM goods_receipt_lines WHERE id = ?1")
+                    .bind(grn_line)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Some((received, billed(conn, true, grn_line, purchase_id).await?))
+            }
+            (None, Some(po_line), Some(qty)) => {
+                let (received,): (f64,) =
+                    sqlx::query_as("SELECT COALESCE(SUM(qty), 0.0) FROM goods_receipt_lines WHERE po_line_id = ?1")
+                        .bind(po_line)
+                        .fetch_one(&mut *conn)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                Some((received, qty))
+            }
+            _ => None,
+        };
+        if let Some((received, qty)) = received {
+            if over(qty, received, settings.qty_tolerance_pct) {
+                out.push(flag("qty_received", received, qty, settings.qty_tolerance_pct));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Re-run the match for a saved purchase and replace its stored exceptions.
+/// Must run inside the transaction that changed the entry or its lines.
+pub async fn record(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Vec<MatchException>, String> {
+    sqlx::query("DELETE FROM match_exceptions WHERE purchase_id = ?1")
+        .bind(purchase_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let exceptions = check(conn, purchase_id).await?;
+    for x in &exceptions {
+        sqlx::query(
+            "INSERT INTO match_exceptions (purchase_id, line_no, part_no, kind, expected, actual, deviation_pct,
+                tolerance_pct, po_line_id, grn_line_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )
+        .bind(purchase_id)
+        .bind(x.line_no)
+        .bind(&x.part_no)
+        .bind(&x.kind)
+        .bind(x.expected)
+        .bind(x.actual)
+        .bind(x.deviation_pct)
+        .bind(x.tolerance_pct)
+        .bind(x.po_line_id)
+        .bind(x.grn_line_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(exceptions)
+}
+
+/// Re-match every entry billing one of `po_line_ids`, after goods against them came in or
+/// went away.
+pub async fn rematch_po_lines(conn: &mut SqliteConnection, po_line_ids: &[i64]) -> Result<(), String> {
+    let mut purchases = BTreeSet::new();
+    for id in po_line_ids {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT DISTINCT purchase_id FROM purchase_items WHERE po_line_id = ?1")
+            .bind(id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        purchases.extend(rows.into_iter().map(|(p,)| p));
+    }
+    for pid in purchases {
+        record(conn, pid).await?;
+    }
+    Ok(())
+}
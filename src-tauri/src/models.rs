@@ -1,6 +1,20 @@
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 
+/// Values of admin-defined custom fields, keyed by field key.
+pub type CustomValues = serde_json::Map<String, serde_json::Value>;
+
+/// Update fields that can be cleared: missing leaves the value alone, `null` clears it.
+/// Pair with `#[serde(default)]`.
+pub(crate) fn double_option<'de, T, D>(d: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(d).map(Some)
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Supplier {
     pub id: i64,
@@ -11,6 +25,18 @@ pub struct Supplier {
     pub tds_rate: Option<f64>,
     pub contact: Option<String>,
     pub email: Option<String>,
+    pub deleted_at: Option<String>,
+    pub deleted_by: Option<i64>,
+    pub pan: Option<String>,
+    pub msme_no: Option<String>, // Udyam registration number
+    pub msme_category: Option<String>, // micro | small | medium
+    pub payment_terms_days: Option<i64>,
+    pub payment_terms: Option<String>,
+    pub merged_into: Option<i64>, // survivor when this supplier was merged away
+    pub active: i64, // 0 = hidden from pickers, no new purchases
+    pub deactivated_at: Option<String>,
+    pub deactivated_by: Option<i64>,
+    pub custom_fields: Json<CustomValues>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,8 +48,28 @@ pub struct SupplierCreate {
     pub tds_rate: Option<f64>,
     pub contact: Option<String>,
     pub email: Option<String>,
+    #[serde(default)]
+    pub pan: Option<String>,
+    #[serde(default)]
+    pub msme_no: Option<String>,
+    #[serde(default)]
+    pub msme_category: Option<String>,
+    #[serde(default)]
+    pub payment_terms_days: Option<i64>,
+    #[serde(default)]
+    pub payment_terms: Option<String>,
+    // Child records created together with the supplier
+    #[serde(default)]
+    pub addresses: Vec<SupplierAddressCreate>,
+    #[serde(default)]
+    pub bank_accounts: Vec<SupplierBankAccountCreate>,
+    #[serde(default)]
+    pub contacts: Vec<SupplierContactCreate>,
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>,
 }
 
+/// Blank text clears a field; `null` clears the TDS rate or credit period.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SupplierUpdate {
     pub id: i64,
@@ -31,17 +77,251 @@ pub struct SupplierUpdate {
     pub gst_no: Option<String>,
     pub state_code: Option<String>,
     pub tds_flag: Option<bool>,
-    pub tds_rate: Option<f64>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub tds_rate: Option<Option<f64>>,
     pub contact: Option<String>,
     pub email: Option<String>,
+    #[serde(default)]
+    pub pan: Option<String>,
+    #[serde(default)]
+    pub msme_no: Option<String>,
+    #[serde(default)]
+    pub msme_category: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub payment_terms_days: Option<Option<i64>>,
+    #[serde(default)]
+    pub payment_terms: Option<String>,
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>, // merged into the stored values; null clears a field
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierAddress {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub kind: String, // billing | shipping
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub state_code: Option<String>,
+    pub pincode: Option<String>,
+    pub country: String,
+    pub is_default: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SupplierAddressCreate {
+    pub kind: String,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub state_code: Option<String>,
+    pub pincode: Option<String>,
+    pub country: Option<String>, // defaults to India
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// Blank text clears an optional field; the country always has one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierAddressUpdate {
+    pub id: i64,
+    pub kind: Option<String>,
+    pub line1: Option<String>,
+    pub line2: Option<String>,
+    pub city: Option<String>,
+    pub state_code: Option<String>,
+    pub pincode: Option<String>,
+    pub country: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierBankAccount {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub account_name: String,
+    pub account_no: String,
+    pub ifsc: String,
+    pub bank_name: Option<String>,
+    pub branch: Option<String>,
+    pub verification_status: String, // unverified | verified | failed
+    pub verified_at: Option<String>,
+    pub is_primary: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SupplierBankAccountCreate {
+    pub account_name: String,
+    pub account_no: String,
+    pub ifsc: String,
+    pub bank_name: Option<String>,
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+}
+
+/// Changing the account number or IFSC resets the verification status.
+/// Blank text clears an optional field.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierBankAccountUpdate {
+    pub id: i64,
+    pub account_name: Option<String>,
+    pub account_no: Option<String>,
+    pub ifsc: Option<String>,
+    pub bank_name: Option<String>,
+    pub branch: Option<String>,
+    pub is_primary: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierContact {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub name: String,
+    pub role: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub is_primary: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SupplierContactCreate {
+    pub name: String,
+    pub role: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+}
+
+/// Blank text clears an optional field.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierContactUpdate {
+    pub id: i64,
+    pub name: Option<String>,
+    pub role: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub is_primary: Option<bool>,
+}
+
+/// A supplier with its addresses, bank accounts and contact persons.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierDetails {
+    #[serde(flatten)]
+    pub supplier: Supplier,
+    pub addresses: Vec<SupplierAddress>,
+    pub bank_accounts: Vec<SupplierBankAccount>,
+    pub contacts: Vec<SupplierContact>,
+}
+
+/// Error returned by `delete_supplier`, serialised as an object so the UI can
+/// show what blocks the delete and offer deactivation instead.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierDeleteError {
+    pub code: String, // supplier_in_use | not_found | error
+    pub message: String,
+    pub purchases: i64, // live purchases blocking the delete
+    pub deleted_purchases: i64, // purchases in the recycle bin, kept with the supplier
+    pub purchase_orders: i64, // purchase orders blocking the delete
+    pub goods_receipts: i64, // goods receipts blocking the delete
+    pub payments: i64, // supplier payments blocking the delete
+    pub notes: i64, // debit/credit notes blocking the delete
+    pub can_deactivate: bool,
+}
+
+impl From<String> for SupplierDeleteError {
+    fn from(message: String) -> Self {
+        SupplierDeleteError {
+            code: "error".to_string(),
+            message,
+            purchases: 0,
+            deleted_purchases: 0,
+            purchase_orders: 0,
+            goods_receipts: 0,
+            payments: 0,
+            notes: 0,
+            can_deactivate: false,
+        }
+    }
+}
+
+/// An existing supplier that looks like the same party as another one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierMatch {
+    #[serde(flatten)]
+    pub supplier: Supplier,
+    pub matched_on: Vec<String>, // gst_no | pan | name
+    pub score: f64, // 0..1, 1 for an identical GSTIN
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierDuplicatePair {
+    pub a: Supplier,
+    pub b: Supplier,
+    pub matched_on: Vec<String>,
+    pub score: f64,
+}
+
+/// A purchase whose invoice number clashed with one of the survivor's and was renamed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvoiceRename {
+    pub purchase_id: i64,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierMerge {
+    pub id: i64,
+    pub survivor_id: i64,
+    pub merged_id: i64,
+    pub merged_name: String,
+    pub merged_gst_no: Option<String>,
+    pub purchases_moved: i64,
+    pub items_moved: i64,
+    pub renamed_invoices: Vec<InvoiceRename>,
+    pub merged_by: Option<i64>,
+    pub merged_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierMergeResult {
+    pub survivor: Supplier,
+    pub merges: Vec<SupplierMerge>,
+}
+
+/// A live purchase that may be the same bill as another one.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DuplicateInvoiceSuspect {
+    pub purchase_id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub gst_no: Option<String>,
+    pub pan: Option<String>,
+    pub invoice_no: String,
+    pub date: String,
+    pub invoice_value: f64,
+}
+
+/// Purchases from one party whose invoice numbers normalise to the same key and whose
+/// amount or date also match.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateInvoiceGroup {
+    pub invoice_key: String,
+    pub same_amount: bool,
+    pub same_date: bool,
+    pub purchases: Vec<DuplicateInvoiceSuspect>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Page<T> {
     pub data: Vec<T>,
-    pub total: i64,
+    pub total: Option<i64>, // None when the caller skipped counting
     pub page: i64,
     pub page_size: i64,
+    pub next_cursor: Option<String>, // opaque keyset token for the following page
 }
 
 // Users
@@ -111,6 +391,12 @@ pub struct PurchaseEntry {
     pub tds_value: f64,
     pub narration: Option<String>,
     pub status: String,
+    pub deleted_at: Option<String>,
+    pub deleted_by: Option<i64>,
+    pub custom_fields: Json<CustomValues>,
+    pub paid_amount: f64, // settled by payment allocations
+    pub noted_amount: f64, // debit notes less credit notes against the entry
+    pub outstanding: f64, // invoice_value - tds_value - paid_amount - noted_amount
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -122,6 +408,12 @@ pub struct PurchaseItemPayload {
     pub unit: Option<String>,
     pub price: f64,
     pub amount: Option<f64>,
+    #[serde(default)]
+    pub allocations: Option<Vec<AllocationInput>>, // line-level splits; replaces the line's set when given
+    #[serde(default)]
+    pub po_line_id: Option<i64>, // purchase order line this bills
+    #[serde(default)]
+    pub grn_line_id: Option<i64>, // goods receipt line this bills; implies its PO line
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -138,8 +430,12 @@ pub struct PurchaseCreate {
     pub invoice_value: f64,
     pub tds_value: f64,
     pub narration: Option<String>,
-    pub status: String,
+    pub status: String, // draft or pending; later moves go through transition_purchase
     pub items: Vec<PurchaseItemPayload>,
+    #[serde(default)]
+    pub allocations: Vec<AllocationInput>, // entry-level splits
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -157,18 +453,44 @@ pub struct PurchaseUpdate {
     pub invoice_value: Option<f64>,
     pub tds_value: Option<f64>,
     pub narration: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<String>, // must match the current status; use transition_purchase to move it
     pub items: Option<Vec<PurchaseItemPayload>>, // if provided, replace items
+    pub allocations: Option<Vec<AllocationInput>>, // if provided, replace entry-level splits
+    pub custom_fields: Option<CustomValues>, // merged into the stored values; null clears a field
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PurchaseFilters {
     pub supplier_id: Option<i64>,
     pub date_from: Option<String>, // inclusive
     pub date_to: Option<String>,   // inclusive
     pub gst_rate: Option<f64>,
+    pub gst_rates: Option<Vec<f64>>, // any of
     pub invoice_no: Option<String>,
     pub status: Option<String>,
+    pub statuses: Option<Vec<String>>, // any of
+    // Amount ranges, inclusive
+    pub basic_value_min: Option<f64>,
+    pub basic_value_max: Option<f64>,
+    pub invoice_value_min: Option<f64>,
+    pub invoice_value_max: Option<f64>,
+    pub tax_value_min: Option<f64>, // sgst + cgst + igst
+    pub tax_value_max: Option<f64>,
+    // Supplier fields (joined)
+    pub supplier_name: Option<String>,
+    pub supplier_gst_no: Option<String>,
+    pub supplier_state_code: Option<String>,
+    // Presence: Some(true) requires, Some(false) excludes
+    pub has_tds: Option<bool>,
+    pub has_narration: Option<bool>,
+    pub has_items: Option<bool>,
+    pub has_outstanding: Option<bool>, // anything left to pay
+    pub part_no: Option<String>, // any item whose part number contains this
+    pub dimension_ids: Option<Vec<i64>>, // tagged with every one of these, on the entry or a line
+    pub unallocated_kind: Option<String>, // no entry-level split of this dimension kind
+    pub custom_fields: Option<Vec<CustomFieldFilter>>, // all must match
+    pub any_of: Option<Vec<PurchaseFilters>>, // OR groups, each AND-ed internally
+    pub include_deleted: Option<bool>, // recycle-bin rows are hidden unless true
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
@@ -181,4 +503,1093 @@ pub struct PurchaseItem {
     pub unit: Option<String>,
     pub price: f64,
     pub amount: f64,
+    #[serde(default)]
+    pub po_line_id: Option<i64>,
+    #[serde(default)]
+    pub grn_line_id: Option<i64>,
+}
+
+// Purchase workflow
+
+/// A move the workflow allows, and who may make it.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseStatusTransition {
+    pub id: i64,
+    pub from_status: String,
+    pub to_status: String,
+    pub required_role: String, // admin | user (anyone)
+    pub requires_reason: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseStatusTransitionInput {
+    pub from_status: String,
+    pub to_status: String,
+    pub required_role: Option<String>, // defaults to user
+    #[serde(default)]
+    pub requires_reason: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseStatusChange {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub from_status: Option<String>, // None for the status the entry was created in
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub changed_by: Option<i64>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: String,
+}
+
+// Approvals
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Approver {
+    pub user_id: i64,
+    pub username: String,
+}
+
+/// Entries with an invoice value in `[min_amount, max_amount)` from the supplier and carrying
+/// the dimension, when those are set, need every approver's sign-off in order.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApprovalRule {
+    pub id: i64,
+    pub name: String,
+    pub min_amount: f64,
+    pub max_amount: Option<f64>,
+    pub supplier_id: Option<i64>,
+    pub dimension_id: Option<i64>, // usually a cost center
+    pub active: bool,
+    pub position: i64,
+    pub approvers: Vec<Approver>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApprovalRuleCreate {
+    pub name: String,
+    #[serde(default)]
+    pub min_amount: f64,
+    pub max_amount: Option<f64>,
+    pub supplier_id: Option<i64>,
+    pub dimension_id: Option<i64>,
+    pub approver_ids: Vec<i64>, // in signing order
+    pub position: Option<i64>,
+}
+
+/// `null` for the upper limit, supplier or dimension removes it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApprovalRuleUpdate {
+    pub id: i64,
+    pub name: Option<String>,
+    pub min_amount: Option<f64>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub max_amount: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub supplier_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub dimension_id: Option<Option<i64>>,
+    pub active: Option<bool>,
+    pub position: Option<i64>,
+    pub approver_ids: Option<Vec<i64>>, // replaces the approvers when given
+}
+
+/// One sign-off requested for an entry.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseApproval {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub round: i64,
+    pub step_no: i64,
+    pub rule_id: Option<i64>,
+    pub rule_name: Option<String>,
+    pub approver_id: i64,
+    pub approver_name: Option<String>,
+    pub status: String, // pending | approved | rejected | cancelled
+    pub comment: Option<String>,
+    pub amount: f64,
+    pub requested_at: String,
+    pub decided_at: Option<String>,
+}
+
+/// An entry waiting on the given approver.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ApprovalInboxItem {
+    pub approval_id: i64,
+    pub purchase_id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub invoice_no: String,
+    pub date: String,
+    pub invoice_value: f64,
+    pub step_no: i64,
+    pub steps: i64,
+    pub rule_name: Option<String>,
+    pub requested_at: String,
+}
+
+// Purchase orders and goods receipts
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseOrder {
+    pub id: i64,
+    pub po_no: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub po_date: String,
+    pub expected_date: Option<String>,
+    pub status: String, // open | closed | cancelled
+    pub narration: Option<String>,
+    pub total_value: f64, // sum of qty * price over the lines
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseOrderLine {
+    pub id: i64,
+    pub po_id: i64,
+    pub line_no: i64,
+    pub part_no: String,
+    pub description: String,
+    pub qty: f64,
+    pub unit: Option<String>,
+    pub price: f64,
+    pub received_qty: f64, // over all GRNs
+    pub invoiced_qty: f64, // over live, uncancelled purchases
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurchaseOrderDetail {
+    pub order: PurchaseOrder,
+    pub lines: Vec<PurchaseOrderLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderLineInput {
+    pub part_no: String,
+    pub description: Option<String>, // defaults to the item master's
+    pub qty: f64,
+    pub unit: Option<String>,
+    pub price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderCreate {
+    pub po_no: String,
+    pub supplier_id: i64,
+    pub po_date: String, // YYYY-MM-DD
+    pub expected_date: Option<String>,
+    pub narration: Option<String>,
+    pub lines: Vec<PurchaseOrderLineInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct GoodsReceipt {
+    pub id: i64,
+    pub grn_no: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub po_id: Option<i64>,
+    pub po_no: Option<String>,
+    pub received_date: String,
+    pub narration: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct GoodsReceiptLine {
+    pub id: i64,
+    pub grn_id: i64,
+    pub line_no: i64,
+    pub po_line_id: Option<i64>,
+    pub part_no: String,
+    pub description: String,
+    pub qty: f64,
+    pub unit: Option<String>,
+    pub invoiced_qty: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GoodsReceiptDetail {
+    pub receipt: GoodsReceipt,
+    pub lines: Vec<GoodsReceiptLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoodsReceiptLineInput {
+    pub po_line_id: Option<i64>,
+    pub part_no: Option<String>, // taken from the PO line when linked
+    pub description: Option<String>,
+    pub qty: f64,
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoodsReceiptCreate {
+    pub grn_no: String,
+    pub supplier_id: i64,
+    pub po_id: Option<i64>,
+    pub received_date: String, // YYYY-MM-DD
+    pub narration: Option<String>,
+    pub lines: Vec<GoodsReceiptLineInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MatchSettings {
+    pub qty_tolerance_pct: f64,
+    pub price_tolerance_pct: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchSettingsUpdate {
+    pub qty_tolerance_pct: Option<f64>,
+    pub price_tolerance_pct: Option<f64>,
+}
+
+/// An invoice line that doesn't agree with the PO or GRN line it bills.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MatchException {
+    pub line_no: i64,
+    pub part_no: Option<String>,
+    pub kind: String,          // price | qty_ordered | qty_received
+    pub expected: f64,         // PO price, ordered qty or received qty
+    pub actual: f64,           // invoice price, or qty billed so far including this entry
+    pub deviation_pct: Option<f64>, // signed; None when nothing was expected
+    pub tolerance_pct: f64,
+    pub po_line_id: Option<i64>,
+    pub grn_line_id: Option<i64>,
+}
+
+/// A stored match exception with the purchase it was flagged on.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MatchExceptionRow {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub invoice_no: String,
+    pub date: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub exception: MatchException,
+    pub created_at: String,
+}
+
+/// A PO line still waiting for goods.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct OpenPoLine {
+    pub po_id: i64,
+    pub po_no: String,
+    pub po_date: String,
+    pub expected_date: Option<String>,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub po_line_id: i64,
+    pub line_no: i64,
+    pub part_no: String,
+    pub description: String,
+    pub ordered_qty: f64,
+    pub received_qty: f64,
+    pub invoiced_qty: f64,
+    pub pending_qty: f64,
+    pub price: f64,
+    pub pending_value: f64, // pending_qty * price
+}
+
+/// A GRN line received but not yet fully billed.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PendingGrnLine {
+    pub grn_id: i64,
+    pub grn_no: String,
+    pub received_date: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub po_no: Option<String>,
+    pub grn_line_id: i64,
+    pub line_no: i64,
+    pub part_no: String,
+    pub description: String,
+    pub received_qty: f64,
+    pub invoiced_qty: f64,
+    pub pending_qty: f64,
+    pub price: Option<f64>, // PO price when the line is on an order
+}
+
+// Payments
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierPayment {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub payment_date: String,
+    pub mode: String, // cash | cheque | neft | rtgs | imps | upi | card | other
+    pub reference: Option<String>,
+    pub amount: f64,     // paid out
+    pub tds_amount: f64, // deducted at payment; settles bills along with amount
+    pub narration: Option<String>,
+    pub allocated: f64,
+    pub unallocated: f64, // amount + tds_amount - allocated; an advance until allocated
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PaymentAllocation {
+    pub id: i64,
+    pub payment_id: i64,
+    pub purchase_id: i64,
+    pub invoice_no: String,
+    pub date: String,
+    pub invoice_value: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentDetail {
+    pub payment: SupplierPayment,
+    pub allocations: Vec<PaymentAllocation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentAllocationInput {
+    pub purchase_id: i64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaymentCreate {
+    pub supplier_id: i64,
+    pub payment_date: String, // YYYY-MM-DD
+    pub mode: String,
+    pub reference: Option<String>,
+    pub amount: f64,
+    #[serde(default)]
+    pub tds_amount: f64,
+    pub narration: Option<String>,
+    #[serde(default)]
+    pub allocations: Vec<PaymentAllocationInput>, // leave empty to record an advance
+}
+
+/// The supplier is fixed; delete and re-enter a payment made to the wrong party. A blank
+/// reference or narration clears it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaymentUpdate {
+    pub id: i64,
+    pub payment_date: Option<String>,
+    pub mode: Option<String>,
+    pub reference: Option<String>,
+    pub amount: Option<f64>,
+    pub tds_amount: Option<f64>,
+    pub narration: Option<String>,
+    pub allocations: Option<Vec<PaymentAllocationInput>>, // replaces the allocations when given
+}
+
+/// A payment as it settles one entry.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchasePayment {
+    pub allocation_id: i64,
+    pub payment_id: i64,
+    pub payment_date: String,
+    pub mode: String,
+    pub reference: Option<String>,
+    pub amount: f64, // allocated to the entry
+}
+
+/// What is owed to a supplier across live, uncancelled entries.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierOutstanding {
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub bills: i64,         // entries with something left to pay
+    pub payable: f64,       // invoice_value - tds_value
+    pub paid: f64,
+    pub noted: f64,           // debit notes less credit notes against those entries
+    pub outstanding: f64,
+    pub advance: f64,         // unallocated payments
+    pub unapplied_notes: f64, // debit notes less credit notes against no live entry
+    pub net_balance: f64,     // outstanding - advance - unapplied_notes
+}
+
+// Payables ageing
+
+/// An ageing column: invoices `from_days` to `to_days` days old, open-ended when `to_days` is None.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeingBucket {
+    pub label: String, // e.g. "31-60", "90+"
+    pub from_days: i64,
+    pub to_days: Option<i64>,
+}
+
+/// An invoice with something left to pay as of the ageing date.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AgeingInvoice {
+    pub purchase_id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub payment_terms_days: Option<i64>,
+    pub invoice_no: String,
+    pub date: String,
+    pub due_date: Option<String>, // when the supplier has payment terms; ageing then runs from it
+    pub days: i64,                // past the due date, or since the invoice date; negative when not yet due
+    pub payable: f64,             // invoice_value - tds_value
+    pub paid: f64,                // allocated from payments made by the ageing date
+    pub noted: f64,               // debit notes less credit notes raised by the ageing date
+    pub outstanding: f64,
+    #[sqlx(skip)]
+    pub bucket: Option<usize>, // index into the report's buckets; None when not yet due
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeingSupplierRow {
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub payment_terms_days: Option<i64>,
+    pub invoices: i64,
+    pub not_due: f64,
+    pub buckets: Vec<f64>, // outstanding per bucket, in the report's bucket order
+    pub outstanding: f64,
+    pub advance: f64, // payments made by the ageing date and not yet set against those bills
+    pub unapplied_notes: f64, // debit notes less credit notes by the ageing date against none of those bills
+    pub net_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeingReport {
+    pub as_of: String,
+    pub buckets: Vec<AgeingBucket>,
+    pub rows: Vec<AgeingSupplierRow>,
+    pub not_due: f64,
+    pub totals: Vec<f64>,
+    pub outstanding: f64,
+    pub advance: f64,
+    pub unapplied_notes: f64,
+    pub net_balance: f64,
+}
+
+// Supplier ledger
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierOpeningBalance {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub fy_start: String, // YYYY-04-01
+    pub fy: String,       // e.g. "2024-25"
+    pub amount: f64,      // owed to the supplier; negative for an advance
+    pub narration: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpeningBalanceSet {
+    pub supplier_id: i64,
+    pub fy_start_year: i32, // 2024 for FY 2024-25
+    pub amount: f64,
+    pub narration: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SupplierNote {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub kind: String, // debit | credit
+    pub note_no: String,
+    pub note_date: String,
+    pub purchase_id: Option<i64>,
+    pub invoice_no: Option<String>,
+    pub amount: f64,
+    pub reason: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierNoteCreate {
+    pub supplier_id: i64,
+    pub kind: String,
+    pub note_no: String,
+    pub note_date: String,
+    pub purchase_id: Option<i64>,
+    pub amount: f64,
+    pub reason: Option<String>,
+}
+
+/// A blank reason clears it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierNoteUpdate {
+    pub id: i64,
+    pub note_no: Option<String>,
+    pub note_date: Option<String>,
+    pub amount: Option<f64>,
+    pub reason: Option<String>,
+}
+
+/// One line of a supplier's ledger. Credits add to what is owed to the supplier, debits take
+/// from it.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LedgerLine {
+    pub date: String,
+    pub kind: String, // opening | invoice | tds | payment | debit_note | credit_note
+    pub reference: Option<String>, // invoice, note or payment reference
+    pub narration: Option<String>,
+    pub debit: f64,
+    pub credit: f64,
+    #[sqlx(skip)]
+    pub balance: f64, // running, owed to the supplier when positive
+    pub purchase_id: Option<i64>,
+    pub payment_id: Option<i64>,
+    pub note_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SupplierLedger {
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub gst_no: Option<String>,
+    pub date_from: String,
+    pub date_to: String,
+    pub opening_balance: f64,
+    pub lines: Vec<LedgerLine>,
+    pub total_debit: f64,
+    pub total_credit: f64,
+    pub closing_balance: f64,
+}
+
+// Custom fields
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomField {
+    pub id: i64,
+    pub entity: String,     // supplier | purchase | item
+    pub key: String,
+    pub label: String,
+    pub field_type: String, // text | number | date | enum
+    pub options: Vec<String>, // allowed values of an enum field
+    pub required: bool,
+    pub active: bool,
+    pub position: i64,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomFieldCreate {
+    pub entity: String,
+    pub key: Option<String>, // derived from the label when left out
+    pub label: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub position: Option<i64>,
+}
+
+/// Entity, key and type are fixed once values may have been stored against them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomFieldUpdate {
+    pub id: i64,
+    pub label: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub required: Option<bool>,
+    pub active: Option<bool>,
+    pub position: Option<i64>,
+}
+
+/// Condition on one custom field. Text compares ignoring case; `min`/`max` take numbers
+/// or YYYY-MM-DD dates.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CustomFieldFilter {
+    pub entity: Option<String>, // in purchase filters, "supplier" looks at the supplier's fields
+    pub key: String,
+    pub equals: Option<serde_json::Value>,
+    pub contains: Option<String>,
+    pub min: Option<serde_json::Value>,
+    pub max: Option<serde_json::Value>,
+    pub present: Option<bool>,
+}
+
+// Dimensions
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Dimension {
+    pub id: i64,
+    pub kind: String, // cost_center | department | project
+    pub code: String,
+    pub name: String,
+    pub active: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DimensionCreate {
+    pub kind: String,
+    pub code: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DimensionUpdate {
+    pub id: i64,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AllocationInput {
+    pub dimension_id: i64,
+    pub percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseAllocation {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub item_id: Option<i64>, // None for entry-level splits
+    pub dimension_id: i64,
+    pub kind: String,
+    pub code: String,
+    pub name: String,
+    pub percent: f64,
+}
+
+/// Taxable value attributed to one dimension. `dimension_id` is None for the unallocated remainder.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DimensionSpend {
+    pub dimension_id: Option<i64>,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub spend: f64,
+    pub purchases: i64,
+}
+
+// Item master
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub part_no: String,
+    pub description: String,
+    pub hsn_code: Option<String>,
+    pub gst_rate: Option<f64>, // default for new purchase lines
+    pub unit: Option<String>,
+    pub supplier_id: Option<i64>, // usual supplier
+    pub active: i64,
+    pub created_at: String,
+    pub updated_at: String,
+    pub custom_fields: Json<CustomValues>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemCreate {
+    pub part_no: String,
+    pub description: String,
+    pub hsn_code: Option<String>,
+    pub gst_rate: Option<f64>,
+    pub unit: Option<String>,
+    pub supplier_id: Option<i64>,
+    pub active: Option<bool>, // defaults to true
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemUpdate {
+    pub id: i64,
+    pub part_no: Option<String>,
+    pub description: Option<String>,
+    pub hsn_code: Option<String>,
+    pub gst_rate: Option<f64>,
+    pub unit: Option<String>,
+    pub supplier_id: Option<i64>,
+    pub active: Option<bool>,
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>, // merged into the stored values; null clears a field
+}
+
+/// A ranked line suggestion with what is needed to pre-fill a purchase line.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemSuggestion {
+    pub source: String,        // master | history (line without a master entry)
+    pub item_id: Option<i64>,  // item master id
+    pub part_no: Option<String>,
+    pub description: String,
+    pub hsn_code: Option<String>,
+    pub gst_rate: Option<f64>, // master default, else the rate of the last purchase
+    pub unit: Option<String>,  // master default unit
+    pub last_price: Option<f64>,
+    pub last_unit: Option<String>,
+    pub last_purchase_date: Option<String>,
+    pub last_supplier_id: Option<i64>,
+    pub last_supplier_name: Option<String>,
+    pub from_supplier: bool, // last_* values come from the requested supplier
+    pub times_purchased: i64,
+    pub score: f64, // higher is better
+}
+
+// Units of measure
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Uom {
+    pub code: String,
+    pub name: String,
+    pub uqc: String, // GST unit quantity code
+    pub active: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UomCreate {
+    pub code: String,
+    pub name: String,
+    pub uqc: Option<String>, // defaults to the code
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UomUpdate {
+    pub code: String,
+    pub name: Option<String>,
+    pub uqc: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct UomAlias {
+    pub alias: String,
+    pub uom_code: String,
+}
+
+/// 1 `from_code` = `factor` × `to_code`; `item_id` limits it to one item.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct UomConversion {
+    pub id: i64,
+    pub item_id: Option<i64>,
+    pub from_code: String,
+    pub to_code: String,
+    pub factor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UomConversionInput {
+    pub item_id: Option<i64>,
+    pub from_code: String,
+    pub to_code: String,
+    pub factor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnitMapping {
+    pub raw: String,
+    pub code: Option<String>, // None when no unit or alias matches
+    pub lines: i64,           // purchase lines and items using the raw value
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnitNormalization {
+    pub dry_run: bool,
+    pub mapped: Vec<UnitMapping>,
+    pub unmapped: Vec<UnitMapping>,
+    pub updated: i64,
+}
+
+/// Purchased quantity of one item in its base unit.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuantitySummary {
+    pub part_no: Option<String>,
+    pub description: String,
+    pub base_unit: Option<String>, // item master unit, else the unit used most
+    pub total_qty: f64,            // converted lines only
+    pub lines: i64,
+    pub unconverted_lines: i64,
+    pub unconverted_units: Vec<String>,
+}
+
+// Prices
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceHistoryEntry {
+    pub purchase_id: i64,
+    pub invoice_no: String,
+    pub date: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub part_no: String,
+    pub description: String,
+    pub qty: f64,
+    pub unit: Option<String>,
+    pub price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceVarianceSettings {
+    pub enabled: i64,
+    pub threshold_pct: f64,
+    pub window_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceVarianceSettingsUpdate {
+    pub enabled: Option<bool>,
+    pub threshold_pct: Option<f64>,
+    pub window_size: Option<i64>,
+}
+
+/// A purchase line whose price is off from earlier purchases of the same part.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceVariance {
+    pub line_no: i64,
+    pub part_no: String,
+    pub description: String,
+    pub price: f64,
+    pub last_price: f64,
+    pub avg_price: f64, // rolling average over `samples` earlier purchases
+    pub samples: i64,
+    pub deviation_last_pct: f64, // signed, relative to last_price
+    pub deviation_avg_pct: f64,  // signed, relative to avg_price
+    pub threshold_pct: f64,
+}
+
+/// A stored variance with the purchase it was flagged on.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceVarianceException {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub invoice_no: String,
+    pub date: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub variance: PriceVariance,
+    pub created_at: String,
+}
+
+/// One entry of the item master the frontend used to keep in localStorage.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LegacyItem {
+    pub part_no: Option<String>,
+    pub description: String,
+    pub gst_percent: Option<f64>,
+    pub supplier_id: Option<i64>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemImportResult {
+    pub imported: i64,
+    pub updated: i64,
+    pub skipped: Vec<String>, // reason per entry that was not imported
+}
+
+// Revisions
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PurchaseRevision {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub revision_no: i64,
+    pub action: String, // create | update | items | revert | baseline
+    pub note: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_by_name: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseRevisionDetail {
+    pub revision: PurchaseRevision,
+    pub header: serde_json::Value, // PurchaseEntry as it was saved
+    pub items: serde_json::Value,  // Vec<PurchaseItem> as it was saved
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemLineChange {
+    pub line: i64,      // 1-based position in the item list
+    pub change: String, // added | removed | modified
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub purchase_id: i64,
+    pub from_revision_no: i64,
+    pub to_revision_no: i64,
+    pub header: Vec<FieldChange>,
+    pub items: Vec<ItemLineChange>,
+}
+
+// Search
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SearchHit {
+    pub kind: String, // supplier | purchase | item
+    pub id: i64,      // id of the supplier, purchase entry or purchase item
+    pub purchase_id: Option<i64>, // entry to open for purchase and item hits
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub snippet: String,
+    pub score: f64, // bm25, lower is better
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub suppliers: Vec<SearchHit>,
+    pub purchases: Vec<SearchHit>,
+    pub items: Vec<SearchHit>,
+}
+
+// Recycle bin
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RecycleBinEntry {
+    pub entity: String, // purchase | supplier
+    pub id: i64,
+    pub label: String,
+    pub deleted_at: String,
+    pub deleted_by: Option<i64>,
+    pub deleted_by_name: Option<String>,
+    pub purgeable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeResult {
+    pub purchases: i64,
+    pub suppliers: i64,
+}
+
+// Attachments
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub purchase_id: i64,
+    pub sha256: String,
+    pub file_name: String,
+    pub kind: String, // invoice | eway_bill | grn | other
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+    pub shared_with: i64, // other entries the same file is attached to
+}
+
+/// Another purchase entry carrying the same file.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AttachmentUse {
+    pub attachment_id: i64,
+    pub purchase_id: i64,
+    pub supplier_name: String,
+    pub invoice_no: String,
+    pub date: String,
+    pub deleted: bool, // entry is in the recycle bin
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachmentAdded {
+    pub attachment: Attachment,
+    pub also_attached_to: Vec<AttachmentUse>, // non-empty means a likely duplicate entry
+}
+
+// Backups
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub kind: String, // auto | manual | pre-restore | external
+    pub size_bytes: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct BackupSettings {
+    pub enabled: i64,
+    pub interval_hours: i64,
+    pub keep_last: i64,
+    pub keep_days: i64,
+    pub last_backup_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupSettingsUpdate {
+    pub enabled: Option<bool>,
+    pub interval_hours: Option<i64>,
+    pub keep_last: Option<i64>,
+    pub keep_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveFile {
+    pub path: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveManifest {
+    pub format_version: i64,
+    pub app_version: String,
+    pub migration_version: i64,
+    pub created_at: String,
+    pub db_sha256: String,
+    pub db_size_bytes: i64,
+    pub files: Vec<ArchiveFile>,
+}
+
+// Saved views
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedView {
+    pub id: i64,
+    pub user_id: i64,
+    pub owner_name: String,
+    pub name: String,
+    pub filters: PurchaseFilters,
+    pub sort_by: Option<String>,
+    pub sort_dir: Option<String>,
+    pub columns: Option<Vec<String>>, // None = all columns
+    pub page_size: Option<i64>,
+    pub is_shared: bool,
+    pub is_default: bool, // default for the requesting user
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedViewCreate {
+    pub name: String,
+    pub filters: Option<PurchaseFilters>,
+    pub sort_by: Option<String>,
+    pub sort_dir: Option<String>,
+    pub columns: Option<Vec<String>>,
+    pub page_size: Option<i64>,
+    pub is_shared: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedViewUpdate {
+    pub id: i64,
+    pub name: Option<String>,
+    pub filters: Option<PurchaseFilters>,
+    pub sort_by: Option<String>,
+    pub sort_dir: Option<String>,
+    pub columns: Option<Vec<String>>,
+    pub page_size: Option<i64>,
+    pub is_shared: Option<bool>,
 }
@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use sqlx::SqliteConnection;
+
+use crate::models::PaymentAllocationInput;
+
+/// Payment modes, matching the CHECK constraint on `supplier_payments.mode`.
+pub const MODES: &[&str] = &["cash", "cheque", "neft", "rtgs", "imps", "upi", "card", "other"];
+
+/// Slack for rounding when comparing money sums.
+const EPSILON: f64 = 0.005;
+
+pub fn mode(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    if MODES.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Unknown payment mode '{}'", value.trim()))
+    }
+}
+
+/// Check the amounts of a payment: nothing negative and something paid or deducted.
+pub fn check_amounts(amount: f64, tds_amount: f64) -> Result<(), String> {
+    if amount.is_nan() || amount < 0.0 || tds_amount.is_nan() || tds_amount < 0.0 {
+        return Err("Payment and TDS amounts can't be negative".to_string());
+    }
+    if amount + tds_amount <= 0.0 {
+        return Err("A payment needs an amount".to_string());
+    }
+    Ok(())
+}
+
+/// Replace the allocations of a payment. Each entry must be the supplier's, live and not
+/// cancelled, and can't be settled past what is left on it; together the allocations can't
+/// exceed the payment's `total` (amount plus TDS).
+pub async fn allocate(
+    conn: &mut SqliteConnection,
+    payment_id: i64,
+    supplier_id: i64,
+    total: f64,
+    allocations: &[PaymentAllocationInput],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM payment_allocations WHERE payment_id = ?1")
+        .bind(payment_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut seen = HashSet::new();
+    let mut sum = 0.0;
+    for a in allocations {
+        if !seen.insert(a.purchase_id) {
+            return Err("Allocate to each purchase only once per payment".to_string());
+        }
+        if a.amount.is_nan() || a.amount <= 0.0 {
+            return Err("Allocated amounts must be above zero".to_string());
+        }
+        let found: Option<(i64, String, String, bool, f64)> = sqlx::query_as(
+            "SELECT supplier_id, invoice_no, status, deleted_at IS NOT NULL, outstanding
+             FROM purchase_entries WHERE id = ?1",
+        )
+        .bind(a.purchase_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        let (entry_supplier, invoice_no, status, deleted, outstanding) =
+            found.ok_or_else(|| format!("Purchase {} not found", a.purchase_id))?;
+        if entry_supplier != supplier_id {
+            return Err(format!("Invoice {} is from another supplier", invoice_no));
+        }
+        if deleted || status == "cancelled" {
+            return Err(format!("Invoice {} is deleted or cancelled", invoice_no));
+        }
+        if a.amount > outstanding + EPSILON {
+            return Err(format!("Only {:.2} is left to pay on invoice {}", outstanding.max(0.0), invoice_no));
+        }
+        sum += a.amount;
+        sqlx::query("INSERT INTO payment_allocations (payment_id, purchase_id, amount) VALUES (?1, ?2, ?3)")
+            .bind(payment_id)
+            .bind(a.purchase_id)
+            .bind(a.amount)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    if sum > total + EPSILON {
+        return Err(format!("Allocations of {:.2} exceed the payment of {:.2}", sum, total));
+    }
+    Ok(())
+}
+
+/// Total already allocated from a payment.
+pub async fn allocated(conn: &mut SqliteConnection, payment_id: i64) -> Result<f64, String> {
+    let (sum,): (f64,) = sqlx::query_as("SELECT COALESCE(SUM(amount), 0.0) FROM payment_allocations WHERE payment_id = ?1")
+        .bind(payment_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(sum)
+}
+
+/// Fail if payments are allocated to the entry; cancelling or deleting it would strand them.
+pub async fn check_unpaid(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    let (paid,): (f64,) = sqlx::query_as("SELECT COALESCE((SELECT paid_amount FROM purchase_entries WHERE id = ?1), 0.0)")
+        .bind(purchase_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if paid > 0.0 {
+        return Err(format!("{:.2} of payments is allocated to this purchase; move it to other bills first", paid));
+    }
+    Ok(())
+}
+
+/// Fail if a payment allocated to the entry was made to another supplier than the entry's.
+pub async fn check_supplier(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    let (stray,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (
+            SELECT 1 FROM payment_allocations pa
+            JOIN supplier_payments sp ON sp.id = pa.payment_id
+            JOIN purchase_entries pe ON pe.id = pa.purchase_id
+            WHERE pa.purchase_id = ?1 AND sp.supplier_id <> pe.supplier_id)",
+    )
+    .bind(purchase_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    if stray {
+        return Err("Payments to the current supplier are allocated to this purchase; move them before changing the supplier".to_string());
+    }
+    Ok(())
+}
+
+/// Fail if the entry now comes to less than the payments and notes set against it.
+pub async fn check_overpaid(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    let (paid, noted, outstanding): (f64, f64, f64) =
+        sqlx::query_as("SELECT paid_amount, noted_amount, outstanding FROM purchase_entries WHERE id = ?1")
+            .bind(purchase_id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    if outstanding < -EPSILON {
+        return Err(format!(
+            "{:.2} of payments and {:.2} of notes are set against this purchase, more than it now comes to",
+            paid, noted
+        ));
+    }
+    Ok(())
+}
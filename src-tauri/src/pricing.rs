@@ -0,0 +1,145 @@
+use sqlx::SqliteConnection;
+
+use crate::models::{PriceVariance, PriceVarianceSettings};
+
+/// A purchase line to compare against earlier purchases of its part.
+pub struct Line {
+    pub line_no: i64,
+    pub part_no: String,
+    pub description: String,
+    pub price: f64,
+}
+
+pub async fn load_settings(conn: &mut SqliteConnection) -> Result<PriceVarianceSettings, String> {
+    sqlx::query_as::<_, PriceVarianceSettings>(
+        "SELECT enabled, threshold_pct, window_size FROM price_variance_settings WHERE id = 1",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn deviation_pct(price: f64, base: f64) -> f64 {
+    (price - base) / base * 100.0
+}
+
+/// Lines whose price differs from the last price or the rolling average of the
+/// part by more than the threshold. Only purchases dated on or before `date` count,
+/// so re-saving an old entry is judged against what was known at the time; cancelled
+/// ones never do.
+/// Stored lines carry the item master's spelling, so a typed part number is mapped
+/// onto it and compared exactly to keep the part number index usable.
+pub async fn check(
+    conn: &mut SqliteConnection,
+    date: &str,
+    exclude_purchase_id: Option<i64>,
+    lines: &[Line],
+    settings: &PriceVarianceSettings,
+) -> Result<Vec<PriceVariance>, String> {
+    let mut out = Vec::new();
+    if settings.enabled == 0 {
+        return Ok(out);
+    }
+
+    for line in lines {
+        let prices: Vec<(f64,)> = sqlx::query_as(
+            "SELECT pi.price FROM purchase_items pi
+             JOIN purchase_entries pe ON pe.id = pi.purchase_id
+             WHERE pi.part_no = COALESCE((SELECT part_no FROM items WHERE part_no = ?1), ?1) AND pe.deleted_at IS NULL
+               AND pe.status <> 'cancelled' AND pe.date <= ?2 AND (?3 IS NULL OR pe.id <> ?3)
+             ORDER BY pe.date DESC, pe.id DESC, pi.id DESC
+             LIMIT ?4",
+        )
+        .bind(&line.part_no)
+        .bind(date)
+        .bind(exclude_purchase_id)
+        .bind(settings.window_size)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let Some(&(last_price,)) = prices.first() else { continue };
+        let avg_price = prices.iter().map(|(p,)| p).sum::<f64>() / prices.len() as f64;
+        if last_price <= 0.0 || avg_price <= 0.0 {
+            continue;
+        }
+
+        let deviation_last_pct = deviation_pct(line.price, last_price);
+        let deviation_avg_pct = deviation_pct(line.price, avg_price);
+        if deviation_last_pct.abs() > settings.threshold_pct || deviation_avg_pct.abs() > settings.threshold_pct {
+            out.push(PriceVariance {
+                line_no: line.line_no,
+                part_no: line.part_no.clone(),
+                description: line.description.clone(),
+                price: line.price,
+                last_price,
+                avg_price,
+                samples: prices.len() as i64,
+                deviation_last_pct,
+                deviation_avg_pct,
+                threshold_pct: settings.threshold_pct,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Re-run the check for a saved purchase and replace its stored variances.
+/// Must run inside the transaction that changed the entry or its lines.
+pub async fn record(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Vec<PriceVariance>, String> {
+    sqlx::query("DELETE FROM price_variances WHERE purchase_id = ?1")
+        .bind(purchase_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let settings = load_settings(conn).await?;
+    if settings.enabled == 0 {
+        return Ok(Vec::new());
+    }
+
+    let (date,): (String,) = sqlx::query_as("SELECT date FROM purchase_entries WHERE id = ?1")
+        .bind(purchase_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(Option<String>, String, f64)> = sqlx::query_as(
+        "SELECT part_no, description, price FROM purchase_items WHERE purchase_id = ?1 ORDER BY id ASC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (part_no, description, price))| {
+            let part_no = part_no.filter(|p| !p.trim().is_empty())?;
+            Some(Line { line_no: i as i64 + 1, part_no, description, price })
+        })
+        .collect();
+
+    let variances = check(conn, &date, Some(purchase_id), &lines, &settings).await?;
+    for v in &variances {
+        sqlx::query(
+            "INSERT INTO price_variances (purchase_id, line_no, part_no, description, price, last_price, avg_price,
+                samples, deviation_last_pct, deviation_avg_pct, threshold_pct)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )
+        .bind(purchase_id)
+        .bind(v.line_no)
+        .bind(&v.part_no)
+        .bind(&v.description)
+        .bind(v.price)
+        .bind(v.last_price)
+        .bind(v.avg_price)
+        .bind(v.samples)
+        .bind(v.deviation_last_pct)
+        .bind(v.deviation_avg_pct)
+        .bind(v.threshold_pct)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(variances)
+}
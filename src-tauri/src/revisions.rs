@@ -0,0 +1,170 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqliteConnection;
+
+use crate::commands::PURCHASE_COLUMNS;
+use crate::models::{
+    AllocationInput, CustomValues, FieldChange, ItemLineChange, PurchaseEntry, PurchaseItem, PurchaseItemPayload,
+};
+
+/// Header fields that are bookkeeping rather than content and never show up in diffs.
+/// Paid, noted and outstanding amounts follow payments and notes, not edits to the entry.
+const IGNORED_HEADER_FIELDS: &[&str] = &["id", "deleted_at", "deleted_by", "paid_amount", "noted_amount", "outstanding"];
+const IGNORED_ITEM_FIELDS: &[&str] = &["id", "purchase_id"];
+
+/// A line as stored in a snapshot, with its own dimension splits. Lines without splits
+/// look the same as in snapshots taken before splits were recorded.
+#[derive(Serialize)]
+struct SnapshotItem {
+    #[serde(flatten)]
+    item: PurchaseItem,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allocations: Vec<AllocationInput>,
+}
+
+/// Snapshot the current header and items of `purchase_id` as its next revision.
+/// Must run inside the transaction that made the change.
+pub async fn record(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    action: &str,
+    note: Option<String>,
+    user_id: Option<i64>,
+) -> Result<i64, String> {
+    let header = sqlx::query_as::<_, PurchaseEntry>(&format!(
+        "SELECT {PURCHASE_COLUMNS} FROM purchase_entries WHERE id = ?1",
+    ))
+    .bind(purchase_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let items: Vec<PurchaseItem> = sqlx::query_as(
+        "SELECT id, purchase_id, part_no, description, qty, unit, price, amount, po_line_id, grn_line_id
+         FROM purchase_items WHERE purchase_id = ?1 ORDER BY id ASC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let splits: Vec<(i64, i64, f64)> = sqlx::query_as(
+        "SELECT item_id, dimension_id, percent FROM purchase_allocations
+         WHERE purchase_id = ?1 AND item_id IS NOT NULL ORDER BY id ASC",
+    )
+    .bind(purchase_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let items: Vec<SnapshotItem> = items
+        .into_iter()
+        .map(|item| {
+            let allocations = splits
+                .iter()
+                .filter(|(item_id, ..)| *item_id == item.id)
+                .map(|&(_, dimension_id, percent)| AllocationInput { dimension_id, percent })
+                .collect();
+            SnapshotItem { item, allocations }
+        })
+        .collect();
+
+    let header_json = serde_json::to_string(&header).map_err(|e| e.to_string())?;
+    let items_json = serde_json::to_string(&items).map_err(|e| e.to_string())?;
+
+    let res = sqlx::query(
+        "INSERT INTO purchase_revisions (purchase_id, revision_no, action, note, header_json, items_json, created_by)
+         VALUES (?1, (SELECT COALESCE(MAX(revision_no), 0) + 1 FROM purchase_revisions WHERE purchase_id = ?1), ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(purchase_id)
+    .bind(action)
+    .bind(note)
+    .bind(header_json)
+    .bind(items_json)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(res.last_insert_rowid())
+}
+
+/// Header values needed to put an entry back the way a revision recorded it.
+#[derive(Debug, Deserialize)]
+pub struct SnapshotHeader {
+    pub supplier_id: i64,
+    pub invoice_no: String,
+    pub date: String,
+    pub entry_date: String,
+    pub gst_rate: f64,
+    pub basic_value: f64,
+    pub sgst: f64,
+    pub cgst: f64,
+    pub igst: f64,
+    pub invoice_value: f64,
+    pub tds_value: f64,
+    pub narration: Option<String>,
+    #[serde(default)]
+    pub custom_fields: Option<CustomValues>, // absent from snapshots taken before custom fields
+}
+
+pub fn parse_header(json: &str) -> Result<SnapshotHeader, String> {
+    serde_json::from_str(json).map_err(|e| format!("revision header is unreadable: {}", e))
+}
+
+pub fn parse_items(json: &str) -> Result<Vec<PurchaseItemPayload>, String> {
+    serde_json::from_str(json).map_err(|e| format!("revision items are unreadable: {}", e))
+}
+
+fn diff_objects(old: &Value, new: &Value, ignored: &[&str]) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+    keys.into_iter()
+        .filter(|k| !ignored.contains(&k.as_str()))
+        .filter_map(|k| {
+            let a = old.get(k).cloned().unwrap_or(Value::Null);
+            let b = new.get(k).cloned().unwrap_or(Value::Null);
+            (a != b).then(|| FieldChange { field: k.clone(), old: a, new: b })
+        })
+        .collect()
+}
+
+/// Field-by-field header differences between two snapshots.
+pub fn diff_header(old: &Value, new: &Value) -> Vec<FieldChange> {
+    diff_objects(old, new, IGNORED_HEADER_FIELDS)
+}
+
+/// Line-by-line item differences. Items are re-inserted on every save so row ids
+/// are not stable; lines are matched by position instead.
+pub fn diff_items(old: &Value, new: &Value) -> Vec<ItemLineChange> {
+    let no_items = Vec::new();
+    let old = old.as_array().unwrap_or(&no_items);
+    let new = new.as_array().unwrap_or(&no_items);
+
+    (0..old.len().max(new.len()))
+        .filter_map(|i| {
+            let line = i as i64 + 1;
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) => {
+                    let fields = diff_objects(a, b, IGNORED_ITEM_FIELDS);
+                    (!fields.is_empty()).then(|| ItemLineChange { line, change: "modified".to_string(), fields })
+                }
+                (None, Some(b)) => Some(ItemLineChange {
+                    line,
+                    change: "added".to_string(),
+                    fields: diff_objects(&Value::Null, b, IGNORED_ITEM_FIELDS),
+                }),
+                (Some(a), None) => Some(ItemLineChange {
+                    line,
+                    change: "removed".to_string(),
+                    fields: diff_objects(a, &Value::Null, IGNORED_ITEM_FIELDS),
+                }),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::FromRow;
+
+use crate::db::DbPool;
+use crate::models::ItemSuggestion;
+
+/// Purchase lines considered for history, newest first.
+const HISTORY_LIMIT: i64 = 5000;
+
+// Score weights: text match dominates, context breaks ties between similar matches
+const SUPPLIER_BONUS: f64 = 25.0;
+const RECENCY_BONUS: f64 = 20.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 60.0;
+const FREQUENCY_CAP: i64 = 10;
+
+#[derive(FromRow)]
+struct MasterRow {
+    id: i64,
+    part_no: String,
+    description: String,
+    hsn_code: Option<String>,
+    gst_rate: Option<f64>,
+    unit: Option<String>,
+    supplier_id: Option<i64>,
+}
+
+#[derive(FromRow)]
+struct HistoryRow {
+    part_no: Option<String>,
+    description: String,
+    price: f64,
+    unit: Option<String>,
+    date: String,
+    gst_rate: f64,
+    supplier_id: i64,
+    supplier_name: String,
+}
+
+/// What the purchase history says about one part number (or description for lines without one).
+#[derive(Default)]
+struct History<'a> {
+    last: Option<&'a HistoryRow>,
+    last_from_supplier: Option<&'a HistoryRow>,
+    times: i64,
+}
+
+fn history_key(part_no: Option<&str>, description: &str) -> String {
+    match part_no.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => format!("p:{}", p.to_lowercase()),
+        None => format!("d:{}", description.trim().to_lowercase()),
+    }
+}
+
+/// How well `candidate` matches `query`, both lowercase: exact, prefix, word prefix,
+/// substring, then an in-order (fuzzy) character match scored by how tight it is.
+fn text_score(query: &str, candidate: &str) -> f64 {
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    if candidate == query {
+        return 100.0;
+    }
+    if candidate.starts_with(query) {
+        return 80.0;
+    }
+    if candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(query))
+    {
+        return 60.0;
+    }
+    if candidate.contains(query) {
+        return 40.0;
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut pos = 0;
+    let mut first = None;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = chars[pos..].iter().position(|c| *c == q);
+        match found {
+            Some(offset) => {
+                first.get_or_insert(pos + offset);
+                pos += offset + 1;
+            }
+            None => return 0.0,
+        }
+    }
+    let span = (pos - first.unwrap_or(0)).max(1) as f64;
+    let needed = query.chars().filter(|c| !c.is_whitespace()).count() as f64;
+    20.0 * needed / span
+}
+
+fn recency_bonus(date: &str, today: NaiveDate) -> f64 {
+    let Ok(d) = NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d") else {
+        return 0.0;
+    };
+    let days = (today - d).num_days().max(0) as f64;
+    RECENCY_BONUS * 0.5_f64.powf(days / RECENCY_HALF_LIFE_DAYS)
+}
+
+fn context_bonus(h: &History, preferred_supplier: bool, today: NaiveDate) -> f64 {
+    let recency = h.last.map(|l| recency_bonus(&l.date, today)).unwrap_or(0.0);
+    let supplier = if preferred_supplier || h.last_from_supplier.is_some() { SUPPLIER_BONUS } else { 0.0 };
+    supplier + recency + h.times.min(FREQUENCY_CAP) as f64
+}
+
+fn with_history(mut s: ItemSuggestion, h: &History) -> ItemSuggestion {
+    if let Some(line) = h.last_from_supplier.or(h.last) {
+        s.last_price = Some(line.price);
+        s.last_unit = line.unit.clone();
+        s.last_purchase_date = Some(line.date.clone());
+        s.last_supplier_id = Some(line.supplier_id);
+        s.last_supplier_name = Some(line.supplier_name.clone());
+        s.from_supplier = h.last_from_supplier.is_some();
+        s.gst_rate = s.gst_rate.or(Some(line.gst_rate));
+    }
+    s.times_purchased = h.times;
+    s
+}
+
+/// Rank active master items and past purchase lines against `query`. With a
+/// supplier, its own items and last prices are preferred. An empty query lists
+/// recently bought items.
+pub async fn suggest(
+    pool: &DbPool,
+    query: &str,
+    supplier_id: Option<i64>,
+    limit: usize,
+) -> Result<Vec<ItemSuggestion>, String> {
+    let master: Vec<MasterRow> = sqlx::query_as(
+        "SELECT id, part_no, description, hsn_code, gst_rate, unit, supplier_id FROM items WHERE active = 1",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let lines: Vec<HistoryRow> = sqlx::query_as(
+        "SELECT pi.part_no, pi.description, pi.price, pi.unit, pe.date, pe.gst_rate, pe.supplier_id, s.name AS supplier_name
+         FROM purchase_items pi
+         JOIN purchase_entries pe ON pe.id = pi.purchase_id
+         JOIN suppliers s ON s.id = pe.supplier_id
+         WHERE pe.deleted_at IS NULL
+         ORDER BY pe.date DESC, pi.id DESC
+         LIMIT ?1",
+    )
+    .bind(HISTORY_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut history: HashMap<String, History> = HashMap::new();
+    for line in &lines {
+        let h = history.entry(history_key(line.part_no.as_deref(), &line.description)).or_default();
+        h.last.get_or_insert(line);
+        if Some(line.supplier_id) == supplier_id {
+            h.last_from_supplier.get_or_insert(line);
+        }
+        h.times += 1;
+    }
+
+    let q = query.trim().to_lowercase();
+    let today = chrono::Local::now().date_naive();
+    let mut out = Vec::new();
+
+    for m in &master {
+        let key = history_key(Some(&m.part_no), &m.description);
+        let h = history.remove(&key).unwrap_or_default();
+        let text = if q.is_empty() {
+            if h.times == 0 { continue; }
+            1.0
+        } else {
+            text_score(&q, &m.part_no.to_lowercase()).max(0.9 * text_score(&q, &m.description.to_lowercase()))
+        };
+        if text <= 0.0 {
+            continue;
+        }
+        let preferred = supplier_id.is_some() && m.supplier_id == supplier_id;
+        let s = ItemSuggestion {
+            source: "master".to_string(),
+            item_id: Some(m.id),
+            part_no: Some(m.part_no.clone()),
+            description: m.description.clone(),
+            hsn_code: m.hsn_code.clone(),
+            gst_rate: m.gst_rate,
+            unit: m.unit.clone(),
+            last_price: None,
+            last_unit: None,
+            last_purchase_date: None,
+            last_supplier_id: None,
+            last_supplier_name: None,
+            from_supplier: false,
+            times_purchased: 0,
+            score: text + context_bonus(&h, preferred, today),
+        };
+        out.push(with_history(s, &h));
+    }
+
+    // Whatever is left was bought without a (still active) master entry
+    for h in history.values() {
+        let Some(line) = h.last else { continue };
+        if line.part_no.as_deref().map(str::trim).is_some_and(|p| !p.is_empty()) {
+            continue; // part number exists but is inactive or gone from the master
+        }
+        let text = if q.is_empty() { 1.0 } else { 0.9 * text_score(&q, &line.description.to_lowercase()) };
+        if text <= 0.0 {
+            continue;
+        }
+        let s = ItemSuggestion {
+            source: "history".to_string(),
+            item_id: None,
+            part_no: None,
+            description: line.description.clone(),
+            hsn_code: None,
+            gst_rate: None,
+            unit: None,
+            last_price: None,
+            last_unit: None,
+            last_purchase_date: None,
+            last_supplier_id: None,
+            last_supplier_name: None,
+            from_supplier: false,
+            times_purchased: 0,
+            score: text + context_bonus(h, false, today),
+        };
+        out.push(with_history(s, h));
+    }
+
+    out.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.part_no.cmp(&b.part_no))
+            .then_with(|| a.description.cmp(&b.description))
+    });
+    out.truncate(limit);
+    Ok(out)
+}
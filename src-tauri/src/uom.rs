@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use sqlx::SqliteConnection;
+
+/// Longest chain of conversions tried when no direct one exists (e.g. BOX -> DOZ -> NOS).
+const MAX_CONVERSION_STEPS: usize = 3;
+
+/// Turn free text into a unit code: either a code itself or a known alias, matched
+/// ignoring case. Blank units are stored as NULL; unknown ones are refused.
+pub async fn resolve(conn: &mut SqliteConnection, unit: Option<String>) -> Result<Option<String>, String> {
+    resolve_kept(conn, unit, &HashSet::new()).await
+}
+
+/// Like [`resolve`], but an unknown unit that is in `kept` (already stored on the line
+/// being saved, e.g. free text from before the units master) is kept as it is.
+pub async fn resolve_kept(
+    conn: &mut SqliteConnection,
+    unit: Option<String>,
+    kept: &HashSet<String>,
+) -> Result<Option<String>, String> {
+    match lookup(conn, unit.as_deref()).await? {
+        Lookup::Blank => Ok(None),
+        Lookup::Found(code) => Ok(Some(code)),
+        Lookup::Unknown(raw) if kept.contains(&raw) => Ok(Some(raw)),
+        Lookup::Unknown(raw) => Err(format!("Unit '{}' is not in the units master", raw)),
+    }
+}
+
+pub enum Lookup {
+    Blank,
+    Found(String),
+    Unknown(String),
+}
+
+pub async fn lookup(conn: &mut SqliteConnection, unit: Option<&str>) -> Result<Lookup, String> {
+    let raw = match unit.map(str::trim) {
+        Some(u) if !u.is_empty() => u,
+        _ => return Ok(Lookup::Blank),
+    };
+    let found: Option<(String,)> = sqlx::query_as(
+        "SELECT code FROM uoms WHERE code = ?1
+         UNION ALL
+         SELECT uom_code FROM uom_aliases WHERE alias = ?1
+         LIMIT 1",
+    )
+    .bind(raw)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(match found {
+        Some((code,)) => Lookup::Found(code),
+        None => Lookup::Unknown(raw.to_string()),
+    })
+}
+
+/// All conversion factors, general and per item, for converting many lines at once.
+pub struct Converter {
+    general: HashMap<String, Vec<(String, f64)>>,
+    per_item: HashMap<i64, HashMap<String, Vec<(String, f64)>>>,
+}
+
+fn add_edge(graph: &mut HashMap<String, Vec<(String, f64)>>, from: &str, to: &str, factor: f64) {
+    graph.entry(from.to_uppercase()).or_default().push((to.to_uppercase(), factor));
+    graph.entry(to.to_uppercase()).or_default().push((from.to_uppercase(), 1.0 / factor));
+}
+
+impl Converter {
+    pub async fn load(conn: &mut SqliteConnection) -> Result<Self, String> {
+        let rows: Vec<(Option<i64>, String, String, f64)> =
+            sqlx::query_as("SELECT item_id, from_code, to_code, factor FROM uom_conversions")
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        let mut general = HashMap::new();
+        let mut per_item: HashMap<i64, HashMap<String, Vec<(String, f64)>>> = HashMap::new();
+        for (item_id, from, to, factor) in rows {
+            match item_id {
+                Some(id) => add_edge(per_item.entry(id).or_default(), &from, &to, factor),
+                None => add_edge(&mut general, &from, &to, factor),
+            }
+        }
+        Ok(Self { general, per_item })
+    }
+
+    /// How many `to` units one `from` unit is, preferring the item's own conversions.
+    pub fn factor(&self, item_id: Option<i64>, from: &str, to: &str) -> Option<f64> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Some(1.0);
+        }
+        let item = item_id.and_then(|id| self.per_item.get(&id));
+        let edges = |unit: &str| -> Vec<(String, f64)> {
+            let own = item.and_then(|g| g.get(unit)).cloned().unwrap_or_default();
+            let shared = self.general.get(unit).cloned().unwrap_or_default();
+            own.into_iter().chain(shared).collect()
+        };
+
+        let mut seen = HashSet::from([from.clone()]);
+        let mut queue = VecDeque::from([(from, 1.0, 0)]);
+        while let Some((unit, acc, steps)) = queue.pop_front() {
+            if steps == MAX_CONVERSION_STEPS {
+                continue;
+            }
+            for (next, f) in edges(&unit) {
+                if next == to {
+                    return Some(acc * f);
+                }
+                if seen.insert(next.clone()) {
+                    queue.push_back((next, acc * f, steps + 1));
+                }
+            }
+        }
+        None
+    }
+}
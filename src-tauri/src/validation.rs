@@ -0,0 +1,99 @@
+//! Format checks for Indian tax, MSME and banking identifiers on the supplier master.
+//! Each helper trims and upper-cases its input and returns the canonical form.
+
+/// Trim an optional text field; blank becomes `None`.
+pub fn clean(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn matches(value: &str, pattern: &str) -> bool {
+    // pattern: 'A' = letter, '9' = digit, 'X' = letter or digit, anything else literal
+    value.len() == pattern.len()
+        && value.chars().zip(pattern.chars()).all(|(c, p)| match p {
+            'A' => c.is_ascii_uppercase(),
+            '9' => c.is_ascii_digit(),
+            'X' => c.is_ascii_uppercase() || c.is_ascii_digit(),
+            _ => c == p,
+        })
+}
+
+/// PAN: five letters, four digits, one letter (e.g. AAACR5055K).
+pub fn pan(value: &str) -> Result<String, String> {
+    let v = value.trim().to_uppercase();
+    if !matches(&v, "AAAAA9999A") {
+        return Err(format!("Invalid PAN '{}'", value.trim()));
+    }
+    Ok(v)
+}
+
+/// GSTINs embed the holder's PAN in characters 3 to 12.
+pub fn pan_matches_gstin(pan: &str, gst_no: &str) -> Result<(), String> {
+    let gst = gst_no.trim().to_uppercase();
+    match gst.get(2..12) {
+        Some(embedded) if gst.len() == 15 && embedded != pan => Err(format!(
+            "PAN {} does not match GSTIN {} (expected {})",
+            pan, gst, embedded
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// IFSC: four letters for the bank, a zero, then six letters or digits for the branch.
+pub fn ifsc(value: &str) -> Result<String, String> {
+    let v = value.trim().to_uppercase();
+    if !matches(&v, "AAAA0XXXXXX") {
+        return Err(format!("Invalid IFSC '{}'", value.trim()));
+    }
+    Ok(v)
+}
+
+/// Bank account numbers are 9 to 18 digits; spaces are dropped.
+pub fn account_no(value: &str) -> Result<String, String> {
+    let v: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(9..=18).contains(&v.len()) || !v.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid bank account number '{}'", value.trim()));
+    }
+    Ok(v)
+}
+
+/// Udyam registration number, e.g. UDYAM-MH-19-0012345.
+pub fn udyam(value: &str) -> Result<String, String> {
+    let v = value.trim().to_uppercase();
+    let valid = v.strip_prefix("UDYAM-").is_some_and(|rest| matches(rest, "AA-99-9999999"));
+    if !valid {
+        return Err(format!("Invalid Udyam registration number '{}'", value.trim()));
+    }
+    Ok(v)
+}
+
+pub fn msme_category(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    match v.as_str() {
+        "micro" | "small" | "medium" => Ok(v),
+        _ => Err(format!("MSME category must be micro, small or medium, not '{}'", value.trim())),
+    }
+}
+
+pub fn pincode(value: &str) -> Result<String, String> {
+    let v: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if !matches(&v, "999999") || v.starts_with('0') {
+        return Err(format!("Invalid PIN code '{}'", value.trim()));
+    }
+    Ok(v)
+}
+
+pub fn address_kind(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    match v.as_str() {
+        "billing" | "shipping" => Ok(v),
+        _ => Err(format!("Address kind must be billing or shipping, not '{}'", value.trim())),
+    }
+}
+
+/// Apply `check` to an optional field after trimming; blank stays `None`.
+pub fn optional(
+    value: Option<String>,
+    check: fn(&str) -> Result<String, String>,
+) -> Result<Option<String>, String> {
+    clean(value).map(|v| check(&v)).transpose()
+}
@@ -0,0 +1,212 @@
+use sqlx::SqliteConnection;
+
+use crate::{approvals, payments};
+use crate::models::PurchaseStatusTransition;
+
+/// Purchase statuses, matching the CHECK constraint on `purchase_entries.status`.
+/// `uploaded` is the posted state.
+pub const STATUSES: &[&str] = &["draft", "pending", "verified", "approved", "uploaded", "cancelled"];
+
+/// Statuses a new entry may be saved in.
+pub const INITIAL: &[&str] = &["draft", "pending"];
+
+/// Entries in these statuses can't be edited, deleted or reverted until moved out again.
+pub const LOCKED: &[&str] = &["uploaded", "cancelled"];
+
+pub const ROLES: &[&str] = &["admin", "user"];
+
+pub const TRANSITION_COLUMNS: &str = "id, from_status, to_status, required_role, requires_reason";
+
+/// Canonical status for `value`; "posted" is accepted for `uploaded`.
+pub fn status(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    let v = if v == "posted" { "uploaded".to_string() } else { v };
+    if STATUSES.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Unknown purchase status '{}'", value.trim()))
+    }
+}
+
+/// How a status reads in messages.
+pub fn label(status: &str) -> &str {
+    match status {
+        "uploaded" => "posted",
+        other => other,
+    }
+}
+
+pub fn initial(value: &str) -> Result<String, String> {
+    let v = status(value)?;
+    if INITIAL.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("New purchases start as draft or pending, not {}", label(&v)))
+    }
+}
+
+pub fn role(value: &str) -> Result<String, String> {
+    let v = value.trim().to_lowercase();
+    if ROLES.contains(&v.as_str()) {
+        Ok(v)
+    } else {
+        Err(format!("Role must be admin or user, not '{}'", value.trim()))
+    }
+}
+
+/// Status of a live entry; None if it doesn't exist or sits in the recycle bin.
+pub async fn current(conn: &mut SqliteConnection, purchase_id: i64) -> Result<Option<String>, String> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT status FROM purchase_entries WHERE id = ?1 AND deleted_at IS NULL")
+            .bind(purchase_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(row.map(|(s,)| s))
+}
+
+/// Fail if the entry's status locks it against changes. Missing entries pass; callers
+/// report those the way they always have.
+pub async fn check_editable(conn: &mut SqliteConnection, purchase_id: i64) -> Result<(), String> {
+    match current(conn, purchase_id).await? {
+        Some(s) if LOCKED.contains(&s.as_str()) => Err(format!(
+            "This purchase is {}; move it back to an open status before changing it",
+            label(&s)
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn user_role(conn: &mut SqliteConnection, user_id: i64) -> Result<String, String> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT role FROM users WHERE id = ?1")
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    row.map(|(r,)| r).ok_or_else(|| "Unknown user".to_string())
+}
+
+/// Add a line to the status history of an entry.
+pub async fn log(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    from: Option<&str>,
+    to: &str,
+    reason: Option<&str>,
+    user_id: Option<i64>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO purchase_status_history (purchase_id, from_status, to_status, reason, changed_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )
+    .bind(purchase_id)
+    .bind(from)
+    .bind(to)
+    .bind(reason)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Set an entry's status and log the move. Rules are the caller's business.
+pub async fn apply(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    from: &str,
+    to: &str,
+    reason: Option<&str>,
+    user_id: Option<i64>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE purchase_entries SET status = ?2 WHERE id = ?1")
+        .bind(purchase_id)
+        .bind(to)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    log(conn, purchase_id, Some(from), to, reason, user_id).await
+}
+
+/// Moves out of `from` that a user with `role` may make.
+pub async fn allowed(conn: &mut SqliteConnection, from: &str, role: &str) -> Result<Vec<PurchaseStatusTransition>, String> {
+    sqlx::query_as::<_, PurchaseStatusTransition>(&format!(
+        "SELECT {TRANSITION_COLUMNS} FROM purchase_status_transitions
+         WHERE from_status = ?1 AND (required_role = 'user' OR ?2 = 'admin')
+         ORDER BY id",
+    ))
+    .bind(from)
+    .bind(role)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Moves `user_id` may make on `purchase_id` right now; none if the entry is missing or deleted.
+pub async fn available(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    user_id: i64,
+) -> Result<Vec<PurchaseStatusTransition>, String> {
+    let role = user_role(conn, user_id).await?;
+    match current(conn, purchase_id).await? {
+        Some(from) => allowed(conn, &from, &role).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Move a live entry to `to` under a transition rule whose role `user_id` holds, and log it.
+/// Reaching verified asks the matching approval rules for sign-offs; approving or posting
+/// needs all of them. Returns the status the entry left.
+pub async fn transition(
+    conn: &mut SqliteConnection,
+    purchase_id: i64,
+    to: &str,
+    reason: Option<String>,
+    user_id: i64,
+) -> Result<String, String> {
+    let to = status(to)?;
+    let role = user_role(conn, user_id).await?;
+    let from = current(conn, purchase_id)
+        .await?
+        .ok_or_else(|| "Purchase not found or deleted".to_string())?;
+    if from == to {
+        return Err(format!("This purchase is already {}", label(&to)));
+    }
+    let rule: Option<PurchaseStatusTransition> = sqlx::query_as(&format!(
+        "SELECT {TRANSITION_COLUMNS} FROM purchase_status_transitions WHERE from_status = ?1 AND to_status = ?2",
+    ))
+    .bind(&from)
+    .bind(&to)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let rule = rule.ok_or_else(|| format!("A purchase can't move from {} to {}", label(&from), label(&to)))?;
+    if rule.required_role == "admin" && role != "admin" {
+        return Err(format!(
+            "Only administrators can move a purchase from {} to {}",
+            label(&from),
+            label(&to)
+        ));
+    }
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if rule.requires_reason && reason.is_none() {
+        return Err(format!("Give a reason to move this purchase from {} to {}", label(&from), label(&to)));
+    }
+
+    if (from == "verified" && to == "approved") || to == "uploaded" {
+        approvals::check_complete(conn, purchase_id).await?;
+    }
+    if to == "cancelled" {
+        payments::check_unpaid(conn, purchase_id).await?;
+    }
+
+    apply(conn, purchase_id, &from, &to, reason.as_deref(), Some(user_id)).await?;
+    if from == "verified" {
+        approvals::cancel_pending(conn, purchase_id).await?;
+    }
+    if to == "verified" {
+        approvals::request(conn, purchase_id).await?;
+    }
+    Ok(from)
+}
@@ -0,0 +1,102 @@
+import { useDecidePurchase, usePurchaseApprovals } from "../../lib/queries";
+import { useAppStore } from "../../store/useAppStore";
+import type { ApprovalStatus, PurchaseApproval } from "../../lib/types";
+
+const APPROVAL_LABELS: Record<ApprovalStatus, string> = {
+  pending: "Waiting",
+  approved: "Approved",
+  rejected: "Rejected",
+  cancelled: "Withdrawn",
+};
+
+export function PurchaseApprovals({ purchaseId }: { purchaseId: number }) {
+  const user = useAppStore((s) => s.user);
+  const { data: approvals = [] } = usePurchaseApprovals(purchaseId);
+  const decide = useDecidePurchase();
+
+  if (approvals.length === 0) return null;
+
+  const latest = Math.max(...approvals.map((a) => a.round));
+  // the step the entry is waiting on: first pending step of the latest round
+  const waiting = approvals.find(
+    (a) => a.round === latest && a.status === "pending",
+  );
+  const rounds = approvals.reduce<Record<number, PurchaseApproval[]>>(
+    (acc, a) => {
+      (acc[a.round] ??= []).push(a);
+      return acc;
+    },
+    {},
+  );
+
+  async function submit(approve: boolean) {
+    if (!user) return;
+    const comment = prompt(
+      approve ? "Comment (optional):" : "Reason for rejecting:",
+    );
+    if (comment === null) return;
+    if (!approve && !comment.trim()) return;
+    try {
+      await decide.mutateAsync({
+        purchaseId,
+        approve,
+        comment: comment.trim() || undefined,
+        userId: user.id,
+      });
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  return (
+    <div>
+      <div className="mb-2 flex items-center justify-between">
+        <h4 className="text-sm font-medium text-slate-700 dark:text-slate-300">
+          Approvals
+        </h4>
+        {waiting && user && waiting.approver_id === user.id && (
+          <div className="flex gap-2">
+            <button
+              type="button"
+              className="rounded border border-slate-300 px-2 py-1 text-xs hover:bg-slate-100 dark:border-slate-600 dark:hover:bg-slate-700"
+              onClick={() => submit(true)}
+              disabled={decide.isPending}
+            >
+              Approve
+            </button>
+            <button
+              type="button"
+              className="rounded border border-red-300 px-2 py-1 text-xs text-red-600 hover:bg-red-50 dark:border-red-700 dark:hover:bg-red-900/30"
+              onClick={() => submit(false)}
+              disabled={decide.isPending}
+            >
+              Reject
+            </button>
+          </div>
+        )}
+      </div>
+      <div className="space-y-2 text-xs text-slate-600 dark:text-slate-400">
+        {Object.entries(rounds)
+          .sort(([a], [b]) => Number(b) - Number(a))
+          .map(([round, steps]) => (
+            <div key={round}>
+              {Number(round) !== latest && (
+                <div className="text-slate-400">Round {round}</div>
+              )}
+              {steps.map((a) => (
+                <div key={a.id}>
+                  {a.step_no}. {a.approver_name ?? `#${a.approver_id}`}
+                  {a.rule_name ? ` (${a.rule_name})` : ""} ·{" "}
+                  {a === waiting ? "Waiting" : APPROVAL_LABELS[a.status]}
+                  {a.decided_at && a.status !== "cancelled"
+                    ? ` ${a.decided_at}`
+                    : ""}
+                  {a.comment ? ` — ${a.comment}` : ""}
+                </div>
+              ))}
+            </div>
+          ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,166 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import {
+  useAddAttachment,
+  useAttachments,
+  useRemoveAttachment,
+} from "../../lib/queries";
+import { useAppStore } from "../../store/useAppStore";
+import type { AttachmentKind, AttachmentUse } from "../../lib/types";
+
+const KIND_LABELS: Record<AttachmentKind, string> = {
+  invoice: "Invoice",
+  eway_bill: "E-way bill",
+  grn: "GRN",
+  other: "Other",
+};
+
+function describeUses(uses: AttachmentUse[]) {
+  return uses
+    .map(
+      (u) =>
+        `- ${u.supplier_name} / ${u.invoice_no} (${u.date})${u.deleted ? " [deleted]" : ""}`,
+    )
+    .join("\n");
+}
+
+export function PurchaseAttachments({ purchaseId }: { purchaseId: number }) {
+  const { data: attachments = [], isLoading } = useAttachments(purchaseId);
+  const addAttachment = useAddAttachment();
+  const removeAttachment = useRemoveAttachment();
+  const user = useAppStore((s) => s.user);
+  const [kind, setKind] = useState<AttachmentKind>("invoice");
+
+  async function attach() {
+    const path = await open({
+      multiple: false,
+      filters: [
+        {
+          name: "Documents",
+          extensions: [
+            "pdf",
+            "png",
+            "jpg",
+            "jpeg",
+            "tif",
+            "tiff",
+            "webp",
+            "gif",
+            "heic",
+          ],
+        },
+      ],
+    });
+    if (!path) return;
+    try {
+      const res = await addAttachment.mutateAsync({
+        purchaseId,
+        path,
+        kind,
+        userId: user?.id,
+      });
+      if (res.also_attached_to.length > 0) {
+        alert(
+          `This file is also attached to:\n${describeUses(res.also_attached_to)}\n\nOne of these entries may be a duplicate.`,
+        );
+      }
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  async function showUses(id: number) {
+    const uses = await invoke<AttachmentUse[]>("get_attachment_uses", { id });
+    alert(`Same file attached to:\n${describeUses(uses)}`);
+  }
+
+  return (
+    <div>
+      <div className="mb-2 flex items-center justify-between">
+        <h4 className="text-sm font-medium text-slate-700 dark:text-slate-300">
+          Attachments
+        </h4>
+        <div className="flex gap-2">
+          <select
+            className="rounded border px-2 py-1 text-xs"
+            value={kind}
+            onChange={(e) => setKind(e.target.value as AttachmentKind)}
+          >
+            {Object.entries(KIND_LABELS).map(([k, label]) => (
+              <option key={k} value={k}>
+                {label}
+              </option>
+            ))}
+          </select>
+          <button
+            type="button"
+            className="rounded border border-slate-300 px-2 py-1 text-xs hover:bg-slate-100 dark:border-slate-600 dark:hover:bg-slate-700"
+            onClick={attach}
+            disabled={addAttachment.isPending}
+          >
+            Attach file
+          </button>
+        </div>
+      </div>
+      {isLoading ? (
+        <p className="text-xs text-slate-500">Loading attachments...</p>
+      ) : attachments.length === 0 ? (
+        <p className="text-xs text-slate-500">No documents attached</p>
+      ) : (
+        <div className="space-y-2">
+          {attachments.map((a) => (
+            <div
+              key={a.id}
+              className="flex items-center justify-between rounded border border-slate-200 p-2 text-xs dark:border-slate-700"
+            >
+              <div>
+                <span className="font-medium">{a.file_name}</span>{" "}
+                <span className="text-slate-500">
+                  {KIND_LABELS[a.kind]} · {(a.size_bytes / 1024).toFixed(0)} KB
+                </span>
+                {a.shared_with > 0 && (
+                  <button
+                    type="button"
+                    className="ml-2 text-amber-600 underline"
+                    onClick={() => showUses(a.id)}
+                  >
+                    also on {a.shared_with} other{" "}
+                    {a.shared_with === 1 ? "entry" : "entries"}
+                  </button>
+                )}
+              </div>
+              <div className="flex gap-2">
+                <button
+                  type="button"
+                  className="rounded border px-2 py-1"
+                  onClick={() =>
+                    invoke("open_attachment", { id: a.id }).catch((e) =>
+                      alert(String(e)),
+                    )
+                  }
+                >
+                  Open
+                </button>
+                <button
+                  type="button"
+                  className="rounded border border-red-300 px-2 py-1 text-red-700"
+                  onClick={async () => {
+                    if (confirm(`Remove ${a.file_name}?`)) {
+                      await removeAttachment.mutateAsync({
+                        id: a.id,
+                        userId: user?.id,
+                      });
+                    }
+                  }}
+                >
+                  Remove
+                </button>
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
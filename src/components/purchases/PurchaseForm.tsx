@@ -4,7 +4,11 @@ import {
   useSuppliers,
   useUpdatePurchase,
 } from "../../lib/queries";
+import { invoke } from "@tauri-apps/api/core";
 import type {
+  CustomValues,
+  DuplicateInvoiceSuspect,
+  PriceVariance,
   PurchaseCreate,
   PurchaseItemPayload,
   Supplier,
@@ -12,6 +16,7 @@ import type {
   PurchaseUpdate,
 } from "../../lib/types";
 import { useItemMaster } from "../../lib/itemMaster";
+import { CustomFieldInputs } from "../ui/CustomFieldInputs";
 
 export function PurchaseForm({
   onCreated,
@@ -47,6 +52,7 @@ export function PurchaseForm({
   const [gstRate, setGstRate] = useState<number | "">("");
   const [narration, setNarration] = useState("");
   const [narrationTouched, setNarrationTouched] = useState(false);
+  const [customValues, setCustomValues] = useState<CustomValues>({});
   const [assessable, setAssessable] = useState<number | "">("");
   const [difference, setDifference] = useState<number | "">("");
   const [partQuery, setPartQuery] = useState("");
@@ -114,6 +120,7 @@ export function PurchaseForm({
       setDifference(Number(diff.toFixed(2)));
       setNarration(initial.narration || "");
       setNarrationTouched(!!initial.narration);
+      setCustomValues(initial.custom_fields ?? {});
       // Part is not normalized in DB; keep last chosen or leave null
       setPart(null);
       setPartQuery("");
@@ -253,7 +260,7 @@ export function PurchaseForm({
         invoice_value: Number(invoiceValue.toFixed(2)),
         tds_value: Number(tds.toFixed(2)),
         narration: narrationTouched ? narration : autoNarration(),
-        status: "uploaded",
+        custom_fields: customValues,
         // Items editing not supported in this quick edit path; omit to keep existing
       };
       await updateMutation.mutateAsync(up);
@@ -274,7 +281,8 @@ export function PurchaseForm({
       invoice_value: Number(invoiceValue.toFixed(2)),
       tds_value: Number(tds.toFixed(2)),
       narration: narrationTouched ? narration : autoNarration(),
-      status: "uploaded",
+      status: "pending",
+      custom_fields: customValues,
       items: part
         ? [
             {
@@ -288,6 +296,48 @@ export function PurchaseForm({
         : [],
     };
 
+    const suspects = await invoke<DuplicateInvoiceSuspect[]>(
+      "check_purchase_duplicates",
+      {
+        supplierId: payload.supplier_id,
+        invoiceNo: payload.invoice_no,
+        date: payload.date,
+        invoiceValue: payload.invoice_value,
+      },
+    );
+    if (
+      suspects.length > 0 &&
+      !confirm(
+        `This bill may already be entered:\n${suspects
+          .map(
+            (d) =>
+              `- ${d.invoice_no} from ${d.supplier_name} dated ${d.date} for ${d.invoice_value.toFixed(2)}`,
+          )
+          .join("\n")}\n\nSave anyway?`,
+      )
+    ) {
+      return;
+    }
+
+    const variances = await invoke<PriceVariance[]>("check_price_variances", {
+      date: payload.date,
+      items: payload.items,
+      purchaseId: null,
+    });
+    if (
+      variances.length > 0 &&
+      !confirm(
+        `Prices differ from earlier purchases by more than ${variances[0].threshold_pct}%:\n${variances
+          .map(
+            (v) =>
+              `- ${v.part_no} at ${v.price.toFixed(2)}: last ${v.last_price.toFixed(2)}, average ${v.avg_price.toFixed(2)} over ${v.samples}`,
+          )
+          .join("\n")}\n\nSave anyway?`,
+      )
+    ) {
+      return;
+    }
+
     // Save current entry for duplication
     setLastEntry({
       supplierId,
@@ -297,7 +347,10 @@ export function PurchaseForm({
       partQuery,
     });
 
-    await addMutation.mutateAsync(payload);
+    await addMutation.mutateAsync({
+      payload,
+      allowDuplicate: suspects.length > 0,
+    });
 
     // Reset form but keep dates
     setSupplierId("");
@@ -310,6 +363,7 @@ export function PurchaseForm({
     setPartQuery("");
     setNarration("");
     setNarrationTouched(false);
+    setCustomValues({});
 
     // Focus on supplier for next entry
     setTimeout(() => supplierRef.current?.focus(), 100);
@@ -613,6 +667,18 @@ export function PurchaseForm({
           </div>
         </div>
 
+        {/* Custom Fields Row */}
+        <div className="grid grid-cols-12 items-end gap-2 empty:hidden">
+          <CustomFieldInputs
+            entity="purchase"
+            values={customValues}
+            onChange={setCustomValues}
+            className="col-span-3"
+            labelClassName="mb-1 block text-xs font-medium text-slate-600 dark:text-slate-300"
+            inputClassName="w-full rounded border border-slate-300 px-2 py-1.5 text-sm focus:border-blue-500 focus:ring-2 focus:ring-blue-500 dark:border-slate-600 dark:bg-slate-800"
+          />
+        </div>
+
         {/* Compact Summary Row */}
         <div className="grid grid-cols-8 gap-2 rounded bg-slate-100 p-2 text-xs dark:bg-slate-800">
           <div className="flex justify-between">
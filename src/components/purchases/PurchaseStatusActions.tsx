@@ -0,0 +1,100 @@
+import { useState } from "react";
+import {
+  useAvailableTransitions,
+  usePurchaseStatusHistory,
+  useTransitionPurchase,
+} from "../../lib/queries";
+import { useAppStore } from "../../store/useAppStore";
+import type { PurchaseStatus, PurchaseStatusTransition } from "../../lib/types";
+
+export const STATUS_LABELS: Record<PurchaseStatus, string> = {
+  draft: "Draft",
+  pending: "Pending",
+  verified: "Verified",
+  approved: "Approved",
+  uploaded: "Posted",
+  cancelled: "Cancelled",
+};
+
+export function PurchaseStatusActions({
+  purchaseId,
+  status,
+}: {
+  purchaseId: number;
+  status: PurchaseStatus;
+}) {
+  const user = useAppStore((s) => s.user);
+  const { data: transitions = [] } = useAvailableTransitions(
+    purchaseId,
+    user?.id,
+  );
+  const { data: history = [] } = usePurchaseStatusHistory(purchaseId);
+  const transition = useTransitionPurchase();
+  const [showHistory, setShowHistory] = useState(false);
+
+  async function move(t: PurchaseStatusTransition) {
+    if (!user) return;
+    let reason: string | undefined;
+    if (t.requires_reason) {
+      const given = prompt(
+        `Reason for moving to ${STATUS_LABELS[t.to_status]}:`,
+      );
+      if (!given?.trim()) return;
+      reason = given.trim();
+    }
+    try {
+      await transition.mutateAsync({
+        purchaseId,
+        toStatus: t.to_status,
+        reason,
+        userId: user.id,
+      });
+    } catch (err) {
+      alert(String(err));
+    }
+  }
+
+  return (
+    <div>
+      <div className="mb-2 flex items-center justify-between">
+        <h4 className="text-sm font-medium text-slate-700 dark:text-slate-300">
+          Status: {STATUS_LABELS[status] ?? status}
+        </h4>
+        <div className="flex gap-2">
+          {transitions.map((t) => (
+            <button
+              key={t.id}
+              type="button"
+              className="rounded border border-slate-300 px-2 py-1 text-xs hover:bg-slate-100 dark:border-slate-600 dark:hover:bg-slate-700"
+              onClick={() => move(t)}
+              disabled={transition.isPending}
+            >
+              {STATUS_LABELS[t.to_status]}
+            </button>
+          ))}
+          <button
+            type="button"
+            className="px-2 py-1 text-xs text-slate-500 underline"
+            onClick={() => setShowHistory((v) => !v)}
+          >
+            {showHistory ? "Hide history" : "History"}
+          </button>
+        </div>
+      </div>
+      {showHistory && (
+        <div className="space-y-1 text-xs text-slate-600 dark:text-slate-400">
+          {history.map((h) => (
+            <div key={h.id}>
+              {h.changed_at} ·{" "}
+              {h.from_status
+                ? `${STATUS_LABELS[h.from_status]} → ${STATUS_LABELS[h.to_status]}`
+                : `Created as ${STATUS_LABELS[h.to_status]}`}
+              {h.changed_by_name ? ` by ${h.changed_by_name}` : ""}
+              {h.reason ? ` — ${h.reason}` : ""}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
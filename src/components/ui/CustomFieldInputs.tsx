@@ -0,0 +1,74 @@
+import { useCustomFields } from "@/lib/queries";
+import type { CustomFieldEntity, CustomValues } from "@/lib/types";
+
+interface CustomFieldInputsProps {
+  entity: CustomFieldEntity;
+  values: CustomValues;
+  onChange: (values: CustomValues) => void;
+  className?: string; // wrapper of each field
+  labelClassName?: string;
+  inputClassName?: string;
+}
+
+// Inputs for the active custom fields of an entity. Values are sent as typed and
+// checked by the backend; a blank input clears the field.
+export function CustomFieldInputs({
+  entity,
+  values,
+  onChange,
+  className,
+  labelClassName,
+  inputClassName,
+}: CustomFieldInputsProps) {
+  const { data: fields = [] } = useCustomFields(entity);
+  if (fields.length === 0) return null;
+
+  const set = (key: string, value: string) =>
+    onChange({ ...values, [key]: value === "" ? null : value });
+
+  return (
+    <>
+      {fields.map((f) => {
+        const value = String(values[f.key] ?? "");
+        return (
+          <div key={f.id} className={className}>
+            <label className={labelClassName}>
+              {f.label}
+              {f.required ? " *" : ""}
+            </label>
+            {f.field_type === "enum" ? (
+              <select
+                className={inputClassName}
+                value={value}
+                onChange={(e) => set(f.key, e.target.value)}
+                required={f.required}
+              >
+                <option value="">-</option>
+                {f.options.map((o) => (
+                  <option key={o} value={o}>
+                    {o}
+                  </option>
+                ))}
+              </select>
+            ) : (
+              <input
+                type={
+                  f.field_type === "number"
+                    ? "number"
+                    : f.field_type === "date"
+                      ? "date"
+                      : "text"
+                }
+                step={f.field_type === "number" ? "any" : undefined}
+                className={inputClassName}
+                value={value}
+                onChange={(e) => set(f.key, e.target.value)}
+                required={f.required}
+              />
+            )}
+          </div>
+        );
+      })}
+    </>
+  );
+}
@@ -1,3 +1,8 @@
+import { useMemo } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { useAppStore } from "../store/useAppStore";
+
 export interface ItemMasterItem {
   id: number;
   part_no?: string | null;
@@ -7,58 +12,131 @@ export interface ItemMasterItem {
   active: boolean;
 }
 
+// Mirrors the Rust `Item` model
+interface Item {
+  id: number;
+  part_no: string;
+  description: string;
+  hsn_code: string | null;
+  gst_rate: number | null;
+  unit: string | null;
+  supplier_id: number | null;
+  active: number;
+  created_at: string;
+  updated_at: string;
+  custom_fields: Record<string, string | number | boolean | null>;
+}
+
+interface ItemImportResult {
+  imported: number;
+  updated: number;
+  skipped: string[];
+}
+
+// Key of the item master before it moved into the database
 const KEY = "app_item_master_v1";
+const QUERY_KEY = ["master-items"];
 
-function load(): ItemMasterItem[] {
-  try {
-    const raw = localStorage.getItem(KEY);
-    if (!raw) return [];
-    const parsed = JSON.parse(raw);
-    if (Array.isArray(parsed)) return parsed as ItemMasterItem[];
-    return [];
-  } catch {
-    return [];
-  }
+function toMasterItem(it: Item): ItemMasterItem {
+  return {
+    id: it.id,
+    part_no: it.part_no,
+    description: it.description,
+    gst_percent: it.gst_rate,
+    supplier_id: it.supplier_id,
+    active: it.active === 1,
+  };
 }
 
-function save(items: ItemMasterItem[]) {
-  localStorage.setItem(KEY, JSON.stringify(items));
+function toPayload(item: Partial<Omit<ItemMasterItem, "id">>) {
+  return {
+    part_no: item.part_no ?? undefined,
+    description: item.description,
+    gst_rate: item.gst_percent ?? undefined,
+    supplier_id: item.supplier_id ?? undefined,
+    active: item.active,
+  };
 }
 
-function nextId(items: ItemMasterItem[]) {
-  return (items.reduce((m, it) => Math.max(m, it.id), 0) || 0) + 1;
+let migration: Promise<void> | null = null;
+
+// Move entries saved in localStorage into the database once, then drop the key.
+function migrateLocalStorage(): Promise<void> {
+  if (migration) return migration;
+  migration = (async () => {
+    const raw = localStorage.getItem(KEY);
+    if (!raw) return;
+    let parsed: unknown;
+    try {
+      parsed = JSON.parse(raw);
+    } catch {
+      return;
+    }
+    if (!Array.isArray(parsed) || parsed.length === 0) {
+      localStorage.removeItem(KEY);
+      return;
+    }
+    const result = await invoke<ItemImportResult>("import_master_items", {
+      items: parsed,
+    });
+    if (result.skipped.length > 0) {
+      console.warn("Item master import skipped entries:", result.skipped);
+    }
+    localStorage.removeItem(KEY);
+  })().catch((err) => {
+    migration = null;
+    console.error("Item master import failed:", err);
+  });
+  return migration;
 }
 
 export function useItemMaster() {
-  function add(item: Omit<ItemMasterItem, "id">) {
-    const all = load();
-    const created: ItemMasterItem = { id: nextId(all), ...item };
-    const next = [...all, created];
-    save(next);
-    return created;
+  const qc = useQueryClient();
+  const user = useAppStore((s) => s.user);
+  const { data } = useQuery({
+    queryKey: QUERY_KEY,
+    queryFn: async (): Promise<Item[]> => {
+      await migrateLocalStorage();
+      return await invoke("get_master_items", { includeInactive: true });
+    },
+  });
+  const items = useMemo(() => (data ?? []).map(toMasterItem), [data]);
+
+  const refresh = () => qc.invalidateQueries({ queryKey: QUERY_KEY });
+
+  async function add(item: Omit<ItemMasterItem, "id">) {
+    const created = await invoke<Item>("add_master_item", {
+      payload: toPayload(item),
+    });
+    await refresh();
+    return toMasterItem(created);
   }
 
-  function update(id: number, patch: Partial<Omit<ItemMasterItem, "id">>) {
-    const all = load();
-    const next = all.map((it) => (it.id === id ? { ...it, ...patch } : it));
-    save(next);
+  async function update(
+    id: number,
+    patch: Partial<Omit<ItemMasterItem, "id">>,
+  ) {
+    await invoke<Item>("update_master_item", {
+      payload: { id, ...toPayload(patch) },
+      userId: user?.id,
+    });
+    await refresh();
   }
 
-  function remove(id: number) {
-    const all = load();
-    const next = all.filter((it) => it.id !== id);
-    save(next);
+  async function remove(id: number) {
+    await invoke<boolean>("delete_master_item", { id });
+    await refresh();
   }
 
   function search(query: string) {
     const q = query.trim().toLowerCase();
-    if (!q) return load();
-    return load().filter(
+    if (!q) return items;
+    return items.filter(
       (it) =>
         (it.part_no || "").toLowerCase().includes(q) ||
         it.description.toLowerCase().includes(q),
     );
   }
 
-  return { items: load(), add, update, remove, search };
+  return { items, add, update, remove, search };
 }
@@ -1,25 +1,73 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import type {
+  AgeingInvoice,
+  AgeingParams,
+  AgeingReport,
+  ApprovalInboxItem,
+  ApprovalRule,
+  ApprovalRuleCreate,
+  ApprovalRuleUpdate,
+  Attachment,
+  AttachmentAdded,
+  AttachmentKind,
+  CustomField,
+  CustomFieldCreate,
+  CustomFieldEntity,
+  CustomFieldUpdate,
+  Dimension,
+  DimensionCreate,
+  DimensionKind,
+  DimensionUpdate,
+  GoodsReceipt,
+  GoodsReceiptCreate,
+  GoodsReceiptDetail,
+  MatchException,
+  MatchExceptionRow,
+  MatchSettings,
+  OpeningBalanceSet,
+  OpenPoLine,
   Page,
+  PaymentCreate,
+  PaymentDetail,
+  PaymentUpdate,
+  PendingGrnLine,
   PurchaseEntry,
   PurchaseFilters,
   PurchaseCreate,
   PurchaseUpdate,
   PurchaseItem,
+  PurchaseApproval,
   PurchaseItemPayload,
+  PurchaseOrder,
+  PurchaseOrderCreate,
+  PurchaseOrderDetail,
+  PurchaseOrderStatus,
+  PurchasePayment,
+  PurchaseStatus,
+  PurchaseStatusChange,
+  PurchaseStatusTransition,
   Supplier,
   SupplierCreate,
+  SupplierLedger,
+  SupplierNote,
+  SupplierNoteCreate,
+  SupplierNoteUpdate,
+  SupplierOpeningBalance,
+  SupplierOutstanding,
+  SupplierPayment,
   SupplierUpdate,
 } from "./types";
 
 // Suppliers
-export function useSuppliers() {
+// Pickers get active suppliers only; pass includeInactive for lists and filters
+export function useSuppliers(options?: { includeInactive?: boolean }) {
+  const includeInactive = options?.includeInactive ?? false;
   return useQuery({
-    queryKey: ["suppliers"],
+    queryKey: ["suppliers", { includeInactive }],
     queryFn: async (): Promise<Page<Supplier>> => {
       // get_suppliers supports pagination and filter; keep defaults here
-      return await invoke("get_suppliers");
+      return await invoke("get_suppliers", { includeInactive });
     },
   });
 }
@@ -27,8 +75,14 @@ export function useSuppliers() {
 export function useAddSupplier() {
   const qc = useQueryClient();
   return useMutation({
-    mutationFn: async (payload: SupplierCreate): Promise<Supplier> => {
-      return await invoke("add_supplier", { payload });
+    mutationFn: async ({
+      payload,
+      allowDuplicate,
+    }: {
+      payload: SupplierCreate;
+      allowDuplicate?: boolean;
+    }): Promise<Supplier> => {
+      return await invoke("add_supplier", { payload, allowDuplicate });
     },
     onSuccess: () => qc.invalidateQueries({ queryKey: ["suppliers"] }),
   });
@@ -47,8 +101,32 @@ export function useUpdateSupplier() {
 export function useDeleteSupplier() {
   const qc = useQueryClient();
   return useMutation({
-    mutationFn: async (id: number): Promise<boolean> => {
-      return await invoke("delete_supplier", { id });
+    mutationFn: async ({
+      id,
+      userId,
+    }: {
+      id: number;
+      userId?: number;
+    }): Promise<boolean> => {
+      return await invoke("delete_supplier", { id, userId });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["suppliers"] }),
+  });
+}
+
+export function useSetSupplierActive() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      id,
+      active,
+      userId,
+    }: {
+      id: number;
+      active: boolean;
+      userId?: number;
+    }): Promise<Supplier> => {
+      return await invoke("set_supplier_active", { id, active, userId });
     },
     onSuccess: () => qc.invalidateQueries({ queryKey: ["suppliers"] }),
   });
@@ -90,11 +168,20 @@ export function useItemsByPurchase(purchaseId: number) {
 export function useAddPurchase() {
   const qc = useQueryClient();
   return useMutation({
-    mutationFn: async (payload: PurchaseCreate): Promise<PurchaseEntry> => {
-      return await invoke("add_purchase", { payload });
+    mutationFn: async ({
+      payload,
+      allowDuplicate,
+    }: {
+      payload: PurchaseCreate;
+      allowDuplicate?: boolean;
+    }): Promise<PurchaseEntry> => {
+      return await invoke("add_purchase", { payload, allowDuplicate });
     },
     onSuccess: () => {
       qc.invalidateQueries({ queryKey: ["purchases"] });
+      // linked lines change what is billed against orders and receipts
+      qc.invalidateQueries({ queryKey: ["open-po-report"] });
+      qc.invalidateQueries({ queryKey: ["pending-grn-report"] });
     },
   });
 }
@@ -108,19 +195,801 @@ export function useUpdatePurchase() {
     onSuccess: (entry) => {
       qc.invalidateQueries({ queryKey: ["purchases"] });
       qc.invalidateQueries({ queryKey: ["purchase-items", entry.id] });
+      qc.invalidateQueries({ queryKey: ["match-exceptions", entry.id] });
+      qc.invalidateQueries({ queryKey: ["open-po-report"] });
+      qc.invalidateQueries({ queryKey: ["pending-grn-report"] });
     },
   });
 }
 
 export function useDeletePurchase() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      id,
+      userId,
+    }: {
+      id: number;
+      userId?: number;
+    }): Promise<boolean> => {
+      return await invoke("delete_purchase", { id, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["purchases"] });
+    },
+  });
+}
+
+// Purchase workflow
+export function useAvailableTransitions(purchaseId: number, userId?: number) {
+  return useQuery({
+    queryKey: ["available-transitions", purchaseId, userId],
+    queryFn: async (): Promise<PurchaseStatusTransition[]> => {
+      return await invoke("get_available_transitions", { purchaseId, userId });
+    },
+    enabled: !!purchaseId && !!userId,
+  });
+}
+
+export function usePurchaseStatusHistory(purchaseId: number) {
+  return useQuery({
+    queryKey: ["purchase-status-history", purchaseId],
+    queryFn: async (): Promise<PurchaseStatusChange[]> => {
+      return await invoke("get_purchase_status_history", { purchaseId });
+    },
+    enabled: !!purchaseId,
+  });
+}
+
+export function useTransitionPurchase() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      purchaseId,
+      toStatus,
+      reason,
+      userId,
+    }: {
+      purchaseId: number;
+      toStatus: PurchaseStatus;
+      reason?: string;
+      userId: number;
+    }): Promise<PurchaseEntry> => {
+      return await invoke("transition_purchase", {
+        purchaseId,
+        toStatus,
+        reason,
+        userId,
+      });
+    },
+    onSuccess: (_data, vars) => {
+      qc.invalidateQueries({ queryKey: ["purchases"] });
+      qc.invalidateQueries({ queryKey: ["available-transitions", vars.purchaseId] });
+      qc.invalidateQueries({ queryKey: ["purchase-status-history", vars.purchaseId] });
+      // reaching or leaving verified opens or withdraws sign-offs
+      qc.invalidateQueries({ queryKey: ["purchase-approvals", vars.purchaseId] });
+      qc.invalidateQueries({ queryKey: ["approval-inbox"] });
+    },
+  });
+}
+
+// Approvals
+export function useApprovalRules(options?: { includeInactive?: boolean }) {
+  return useQuery({
+    queryKey: ["approval-rules", !!options?.includeInactive],
+    queryFn: async (): Promise<ApprovalRule[]> => {
+      return await invoke("get_approval_rules", {
+        includeInactive: options?.includeInactive,
+      });
+    },
+  });
+}
+
+// Rule changes are admin-only; the backend checks the acting user's role
+export function useAddApprovalRule() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: ApprovalRuleCreate;
+      userId: number;
+    }): Promise<ApprovalRule> => {
+      return await invoke("add_approval_rule", { payload, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["approval-rules"] });
+    },
+  });
+}
+
+export function useUpdateApprovalRule() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: ApprovalRuleUpdate;
+      userId: number;
+    }): Promise<ApprovalRule> => {
+      return await invoke("update_approval_rule", { payload, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["approval-rules"] });
+    },
+  });
+}
+
+export function useDeleteApprovalRule() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({ id, userId }: { id: number; userId: number }): Promise<boolean> => {
+      return await invoke("delete_approval_rule", { id, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["approval-rules"] });
+    },
+  });
+}
+
+export function useApprovalInbox(userId?: number) {
+  return useQuery({
+    queryKey: ["approval-inbox", userId],
+    queryFn: async (): Promise<ApprovalInboxItem[]> => {
+      return await invoke("get_approval_inbox", { userId });
+    },
+    enabled: !!userId,
+  });
+}
+
+export function usePurchaseApprovals(purchaseId: number) {
+  return useQuery({
+    queryKey: ["purchase-approvals", purchaseId],
+    queryFn: async (): Promise<PurchaseApproval[]> => {
+      return await invoke("get_purchase_approvals", { purchaseId });
+    },
+    enabled: !!purchaseId,
+  });
+}
+
+// Approve or reject the step a purchase is waiting on; rejecting needs a comment
+export function useDecidePurchase() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      purchaseId,
+      approve,
+      comment,
+      userId,
+    }: {
+      purchaseId: number;
+      approve: boolean;
+      comment?: string;
+      userId: number;
+    }): Promise<PurchaseEntry> => {
+      return await invoke(approve ? "approve_purchase" : "reject_purchase", {
+        purchaseId,
+        comment,
+        userId,
+      });
+    },
+    onSuccess: (_data, vars) => {
+      qc.invalidateQueries({ queryKey: ["purchases"] });
+      qc.invalidateQueries({ queryKey: ["purchase-approvals", vars.purchaseId] });
+      qc.invalidateQueries({ queryKey: ["approval-inbox"] });
+      qc.invalidateQueries({ queryKey: ["available-transitions", vars.purchaseId] });
+      qc.invalidateQueries({ queryKey: ["purchase-status-history", vars.purchaseId] });
+    },
+  });
+}
+
+// Purchase orders and goods receipts
+export function usePurchaseOrders(filters?: {
+  supplierId?: number;
+  status?: PurchaseOrderStatus;
+}) {
+  return useQuery({
+    queryKey: ["purchase-orders", filters ?? {}],
+    queryFn: async (): Promise<PurchaseOrder[]> => {
+      return await invoke("get_purchase_orders", {
+        supplierId: filters?.supplierId,
+        status: filters?.status,
+      });
+    },
+  });
+}
+
+export function usePurchaseOrder(id: number) {
+  return useQuery({
+    queryKey: ["purchase-order", id],
+    queryFn: async (): Promise<PurchaseOrderDetail> => {
+      return await invoke("get_purchase_order", { id });
+    },
+    enabled: !!id,
+  });
+}
+
+export function useAddPurchaseOrder() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: PurchaseOrderCreate;
+      userId?: number;
+    }): Promise<PurchaseOrderDetail> => {
+      return await invoke("add_purchase_order", { payload, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["purchase-orders"] });
+      qc.invalidateQueries({ queryKey: ["open-po-report"] });
+    },
+  });
+}
+
+export function useSetPurchaseOrderStatus() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      id,
+      status,
+    }: {
+      id: number;
+      status: PurchaseOrderStatus;
+    }): Promise<PurchaseOrder> => {
+      return await invoke("set_purchase_order_status", { id, status });
+    },
+    onSuccess: (_data, vars) => {
+      qc.invalidateQueries({ queryKey: ["purchase-orders"] });
+      qc.invalidateQueries({ queryKey: ["purchase-order", vars.id] });
+      qc.invalidateQueries({ queryKey: ["open-po-report"] });
+    },
+  });
+}
+
+export function useDeletePurchaseOrder() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: number): Promise<boolean> => {
+      return await invoke("delete_purchase_order", { id });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["purchase-orders"] });
+      qc.invalidateQueries({ queryKey: ["open-po-report"] });
+    },
+  });
+}
+
+export function useGoodsReceipts(filters?: { supplierId?: number; poId?: number }) {
+  return useQuery({
+    queryKey: ["goods-receipts", filters ?? {}],
+    queryFn: async (): Promise<GoodsReceipt[]> => {
+      return await invoke("get_goods_receipts", {
+        supplierId: filters?.supplierId,
+        poId: filters?.poId,
+      });
+    },
+  });
+}
+
+export function useGoodsReceipt(id: number) {
+  return useQuery({
+    queryKey: ["goods-receipt", id],
+    queryFn: async (): Promise<GoodsReceiptDetail> => {
+      return await invoke("get_goods_receipt", { id });
+    },
+    enabled: !!id,
+  });
+}
+
+// Receiving goods re-matches invoices already billing the order lines
+function invalidateReceipts(qc: ReturnType<typeof useQueryClient>) {
+  qc.invalidateQueries({ queryKey: ["goods-receipts"] });
+  qc.invalidateQueries({ queryKey: ["purchase-orders"] });
+  qc.invalidateQueries({ queryKey: ["purchase-order"] });
+  qc.invalidateQueries({ queryKey: ["open-po-report"] });
+  qc.invalidateQueries({ queryKey: ["pending-grn-report"] });
+  qc.invalidateQueries({ queryKey: ["match-exceptions"] });
+}
+
+export function useAddGoodsReceipt() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: GoodsReceiptCreate;
+      userId?: number;
+    }): Promise<GoodsReceiptDetail> => {
+      return await invoke("add_goods_receipt", { payload, userId });
+    },
+    onSuccess: () => invalidateReceipts(qc),
+  });
+}
+
+export function useDeleteGoodsReceipt() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: number): Promise<boolean> => {
+      return await invoke("delete_goods_receipt", { id });
+    },
+    onSuccess: () => invalidateReceipts(qc),
+  });
+}
+
+export function usePurchaseMatchExceptions(purchaseId: number) {
+  return useQuery({
+    queryKey: ["match-exceptions", purchaseId],
+    queryFn: async (): Promise<MatchException[]> => {
+      return await invoke("get_purchase_match_exceptions", { purchaseId });
+    },
+    enabled: !!purchaseId,
+  });
+}
+
+export function useMatchExceptionReport(filters: {
+  dateFrom?: string;
+  dateTo?: string;
+  supplierId?: number;
+  kind?: MatchException["kind"];
+}) {
+  return useQuery({
+    queryKey: ["match-exceptions", "report", filters],
+    queryFn: async (): Promise<MatchExceptionRow[]> => {
+      return await invoke("get_match_exception_report", filters);
+    },
+  });
+}
+
+export function useMatchSettings() {
+  return useQuery({
+    queryKey: ["match-settings"],
+    queryFn: async (): Promise<MatchSettings> => {
+      return await invoke("get_match_settings");
+    },
+  });
+}
+
+export function useUpdateMatchSettings() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (payload: Partial<MatchSettings>): Promise<MatchSettings> => {
+      return await invoke("update_match_settings", { payload });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["match-settings"] });
+    },
+  });
+}
+
+export function useOpenPoReport(supplierId?: number) {
+  return useQuery({
+    queryKey: ["open-po-report", supplierId],
+    queryFn: async (): Promise<OpenPoLine[]> => {
+      return await invoke("get_open_po_report", { supplierId });
+    },
+  });
+}
+
+export function usePendingGrnReport(supplierId?: number) {
+  return useQuery({
+    queryKey: ["pending-grn-report", supplierId],
+    queryFn: async (): Promise<PendingGrnLine[]> => {
+      return await invoke("get_pending_grn_report", { supplierId });
+    },
+  });
+}
+
+// Payments
+export function usePayments(filters?: {
+  supplierId?: number;
+  dateFrom?: string;
+  dateTo?: string;
+  unallocatedOnly?: boolean;
+}) {
+  return useQuery({
+    queryKey: ["payments", filters],
+    queryFn: async (): Promise<SupplierPayment[]> => {
+      return await invoke("get_payments", { ...filters });
+    },
+  });
+}
+
+export function usePayment(id?: number) {
+  return useQuery({
+    queryKey: ["payment", id],
+    queryFn: async (): Promise<PaymentDetail> => {
+      return await invoke("get_payment", { id });
+    },
+    enabled: id !== undefined,
+  });
+}
+
+export function usePurchasePayments(purchaseId: number) {
+  return useQuery({
+    queryKey: ["purchase-payments", purchaseId],
+    queryFn: async (): Promise<PurchasePayment[]> => {
+      return await invoke("get_purchase_payments", { purchaseId });
+    },
+  });
+}
+
+export function useSupplierOutstanding(supplierId?: number) {
+  return useQuery({
+    queryKey: ["supplier-outstanding", supplierId],
+    queryFn: async (): Promise<SupplierOutstanding[]> => {
+      return await invoke("get_supplier_outstanding", { supplierId });
+    },
+  });
+}
+
+// Payments move the paid and outstanding amounts shown on entries
+function invalidatePayments(qc: ReturnType<typeof useQueryClient>) {
+  qc.invalidateQueries({ queryKey: ["payments"] });
+  qc.invalidateQueries({ queryKey: ["payment"] });
+  qc.invalidateQueries({ queryKey: ["purchase-payments"] });
+  qc.invalidateQueries({ queryKey: ["supplier-outstanding"] });
+  qc.invalidateQueries({ queryKey: ["payables-ageing"] });
+  qc.invalidateQueries({ queryKey: ["payables-ageing-invoices"] });
+  qc.invalidateQueries({ queryKey: ["supplier-ledger"] });
+  qc.invalidateQueries({ queryKey: ["purchases"] });
+}
+
+export function useAddPayment() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: PaymentCreate;
+      userId?: number;
+    }): Promise<PaymentDetail> => {
+      return await invoke("add_payment", { payload, userId });
+    },
+    onSuccess: () => invalidatePayments(qc),
+  });
+}
+
+export function useUpdatePayment() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (payload: PaymentUpdate): Promise<PaymentDetail> => {
+      return await invoke("update_payment", { payload });
+    },
+    onSuccess: () => invalidatePayments(qc),
+  });
+}
+
+export function useDeletePayment() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: number): Promise<boolean> => {
+      return await invoke("delete_payment", { id });
+    },
+    onSuccess: () => invalidatePayments(qc),
+  });
+}
+
+// Payables ageing
+export function usePayablesAgeing(params: AgeingParams) {
+  return useQuery({
+    queryKey: ["payables-ageing", params],
+    queryFn: async (): Promise<AgeingReport> => {
+      return await invoke("get_payables_ageing", { ...params });
+    },
+  });
+}
+
+// Drill-down: bucket is a label from the report or "not_due"
+export function usePayablesAgeingInvoices(params: AgeingParams & { bucket?: string }, enabled = true) {
+  return useQuery({
+    queryKey: ["payables-ageing-invoices", params],
+    queryFn: async (): Promise<AgeingInvoice[]> => {
+      return await invoke("get_payables_ageing_invoices", { ...params });
+    },
+    enabled,
+  });
+}
+
+export function useExportPayablesAgeing() {
+  return useMutation({
+    mutationFn: async ({ path, ...params }: AgeingParams & { path: string }): Promise<void> => {
+      await invoke("export_payables_ageing", { path, ...params });
+    },
+  });
+}
+
+// Supplier ledger
+// Period defaults to the current financial year to date
+export function useSupplierLedger(supplierId?: number, dateFrom?: string, dateTo?: string) {
+  return useQuery({
+    queryKey: ["supplier-ledger", supplierId, dateFrom, dateTo],
+    queryFn: async (): Promise<SupplierLedger> => {
+      return await invoke("get_supplier_ledger", { supplierId, dateFrom, dateTo });
+    },
+    enabled: supplierId !== undefined,
+  });
+}
+
+export function useExportSupplierLedger() {
+  return useMutation({
+    mutationFn: async ({
+      supplierId,
+      dateFrom,
+      dateTo,
+      path,
+      format,
+    }: {
+      supplierId: number;
+      dateFrom?: string;
+      dateTo?: string;
+      path: string;
+      format: "xlsx" | "pdf";
+    }): Promise<void> => {
+      const command = format === "pdf" ? "export_supplier_ledger_pdf" : "export_supplier_ledger";
+      await invoke(command, { supplierId, dateFrom, dateTo, path });
+    },
+  });
+}
+
+export function useOpeningBalances(supplierId?: number) {
+  return useQuery({
+    queryKey: ["opening-balances", supplierId],
+    queryFn: async (): Promise<SupplierOpeningBalance[]> => {
+      return await invoke("get_opening_balances", { supplierId });
+    },
+  });
+}
+
+export function useSetOpeningBalance() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: OpeningBalanceSet;
+      userId?: number;
+    }): Promise<SupplierOpeningBalance> => {
+      return await invoke("set_opening_balance", { payload, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["opening-balances"] });
+      qc.invalidateQueries({ queryKey: ["supplier-ledger"] });
+    },
+  });
+}
+
+export function useDeleteOpeningBalance() {
   const qc = useQueryClient();
   return useMutation({
     mutationFn: async (id: number): Promise<boolean> => {
-      return await invoke("delete_purchase", { id });
+      return await invoke("delete_opening_balance", { id });
     },
     onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["opening-balances"] });
+      qc.invalidateQueries({ queryKey: ["supplier-ledger"] });
+    },
+  });
+}
+
+export function useSupplierNotes(filters?: { supplierId?: number; dateFrom?: string; dateTo?: string }) {
+  return useQuery({
+    queryKey: ["supplier-notes", filters],
+    queryFn: async (): Promise<SupplierNote[]> => {
+      return await invoke("get_supplier_notes", { ...filters });
+    },
+  });
+}
+
+function invalidateNotes(qc: ReturnType<typeof useQueryClient>) {
+  qc.invalidateQueries({ queryKey: ["supplier-notes"] });
+  qc.invalidateQueries({ queryKey: ["supplier-ledger"] });
+  qc.invalidateQueries({ queryKey: ["supplier-outstanding"] });
+  qc.invalidateQueries({ queryKey: ["payables-ageing"] });
+  qc.invalidateQueries({ queryKey: ["payables-ageing-invoices"] });
+  qc.invalidateQueries({ queryKey: ["purchases"] });
+}
+
+export function useAddSupplierNote() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: SupplierNoteCreate;
+      userId?: number;
+    }): Promise<SupplierNote> => {
+      return await invoke("add_supplier_note", { payload, userId });
+    },
+    onSuccess: () => invalidateNotes(qc),
+  });
+}
+
+export function useUpdateSupplierNote() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (payload: SupplierNoteUpdate): Promise<SupplierNote> => {
+      return await invoke("update_supplier_note", { payload });
+    },
+    onSuccess: () => invalidateNotes(qc),
+  });
+}
+
+export function useDeleteSupplierNote() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: number): Promise<boolean> => {
+      return await invoke("delete_supplier_note", { id });
+    },
+    onSuccess: () => invalidateNotes(qc),
+  });
+}
+
+// Custom fields
+export function useCustomFields(
+  entity: CustomFieldEntity,
+  options?: { includeInactive?: boolean },
+) {
+  return useQuery({
+    queryKey: ["custom-fields", entity, !!options?.includeInactive],
+    queryFn: async (): Promise<CustomField[]> => {
+      return await invoke("get_custom_fields", {
+        entity,
+        includeInactive: options?.includeInactive,
+      });
+    },
+  });
+}
+
+// Defining fields is admin-only; the backend checks the acting user's role
+export function useAddCustomField() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: CustomFieldCreate;
+      userId: number;
+    }): Promise<CustomField> => {
+      return await invoke("add_custom_field", { payload, userId });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["custom-fields"] }),
+  });
+}
+
+export function useUpdateCustomField() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      payload,
+      userId,
+    }: {
+      payload: CustomFieldUpdate;
+      userId: number;
+    }): Promise<CustomField> => {
+      return await invoke("update_custom_field", { payload, userId });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["custom-fields"] }),
+  });
+}
+
+export function useDeleteCustomField() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      id,
+      userId,
+    }: {
+      id: number;
+      userId: number;
+    }): Promise<boolean> => {
+      return await invoke("delete_custom_field", { id, userId });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ["custom-fields"] });
       qc.invalidateQueries({ queryKey: ["purchases"] });
+      qc.invalidateQueries({ queryKey: ["suppliers"] });
+    },
+  });
+}
+
+// Dimensions
+export function useDimensions(options?: {
+  kind?: DimensionKind;
+  includeInactive?: boolean;
+}) {
+  return useQuery({
+    queryKey: ["dimensions", options?.kind, !!options?.includeInactive],
+    queryFn: async (): Promise<Dimension[]> => {
+      return await invoke("get_dimensions", {
+        kind: options?.kind,
+        includeInactive: options?.includeInactive,
+      });
+    },
+  });
+}
+
+export function useAddDimension() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (payload: DimensionCreate): Promise<Dimension> => {
+      return await invoke("add_dimension", { payload });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["dimensions"] }),
+  });
+}
+
+export function useUpdateDimension() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (payload: DimensionUpdate): Promise<Dimension> => {
+      return await invoke("update_dimension", { payload });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["dimensions"] }),
+  });
+}
+
+export function useDeleteDimension() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: number): Promise<boolean> => {
+      return await invoke("delete_dimension", { id });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["dimensions"] }),
+  });
+}
+
+export function useAttachments(purchaseId: number) {
+  return useQuery({
+    queryKey: ["attachments", purchaseId],
+    queryFn: async (): Promise<Attachment[]> => {
+      return await invoke("get_attachments", { purchaseId });
+    },
+    enabled: !!purchaseId,
+  });
+}
+
+export function useAddAttachment() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      purchaseId,
+      path,
+      kind,
+      userId,
+    }: {
+      purchaseId: number;
+      path: string;
+      kind?: AttachmentKind;
+      userId?: number;
+    }): Promise<AttachmentAdded> => {
+      return await invoke("add_attachment", { purchaseId, path, kind, userId });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["attachments"] }),
+  });
+}
+
+export function useRemoveAttachment() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      id,
+      userId,
+    }: {
+      id: number;
+      userId?: number;
+    }): Promise<boolean> => {
+      return await invoke("remove_attachment", { id, userId });
     },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ["attachments"] }),
   });
 }
 
@@ -2,9 +2,10 @@
 
 export interface Page<T> {
   data: T[];
-  total: number;
+  total: number | null; // null when the count was skipped
   page: number;
   page_size: number;
+  next_cursor?: string | null; // pass back as `cursor` for the next page
 }
 
 export interface Supplier {
@@ -16,6 +17,31 @@ export interface Supplier {
   tds_rate?: number | null; // percent
   contact?: string | null;
   email?: string | null;
+  deleted_at?: string | null; // set while in the recycle bin
+  deleted_by?: number | null;
+  pan?: string | null;
+  msme_no?: string | null; // Udyam registration number
+  msme_category?: "micro" | "small" | "medium" | null;
+  payment_terms_days?: number | null; // credit period
+  payment_terms?: string | null;
+  merged_into?: number | null; // survivor when merged away
+  active: number; // 1 or 0; inactive suppliers are hidden from pickers
+  deactivated_at?: string | null;
+  deactivated_by?: number | null;
+  custom_fields: CustomValues;
+}
+
+// Error object rejected by delete_supplier
+export interface SupplierDeleteError {
+  code: "supplier_in_use" | "not_found" | "error";
+  message: string;
+  purchases: number; // live purchases blocking the delete
+  deleted_purchases: number; // purchases in the recycle bin
+  purchase_orders: number; // purchase orders blocking the delete
+  goods_receipts: number; // goods receipts blocking the delete
+  payments: number; // supplier payments blocking the delete
+  notes: number; // debit/credit notes blocking the delete
+  can_deactivate: boolean;
 }
 
 export interface SupplierCreate {
@@ -26,8 +52,18 @@ export interface SupplierCreate {
   tds_rate?: number | null; // percent
   contact?: string | null;
   email?: string | null;
+  pan?: string | null;
+  msme_no?: string | null; // Udyam registration number
+  msme_category?: "micro" | "small" | "medium" | null;
+  payment_terms_days?: number | null; // credit period
+  payment_terms?: string | null;
+  addresses?: SupplierAddressCreate[];
+  bank_accounts?: SupplierBankAccountCreate[];
+  contacts?: SupplierContactCreate[];
+  custom_fields?: CustomValues;
 }
 
+// Blank text clears a field; null clears tds_rate or payment_terms_days
 export interface SupplierUpdate {
   id: number;
   name?: string | null;
@@ -37,6 +73,139 @@ export interface SupplierUpdate {
   tds_rate?: number | null;
   contact?: string | null;
   email?: string | null;
+  pan?: string | null;
+  msme_no?: string | null; // Udyam registration number
+  msme_category?: "micro" | "small" | "medium" | null;
+  payment_terms_days?: number | null; // credit period
+  payment_terms?: string | null;
+  custom_fields?: CustomValues; // merged over the stored values; null clears a field
+}
+
+export interface SupplierAddress {
+  id: number;
+  supplier_id: number;
+  kind: "billing" | "shipping";
+  line1: string;
+  line2?: string | null;
+  city?: string | null;
+  state_code?: string | null;
+  pincode?: string | null;
+  country: string;
+  is_default: number; // 1 or 0, one default per kind
+}
+
+export type SupplierAddressCreate = Omit<SupplierAddress, "id" | "supplier_id" | "country" | "is_default"> & {
+  country?: string | null; // defaults to India
+  is_default?: boolean;
+};
+
+export interface SupplierBankAccount {
+  id: number;
+  supplier_id: number;
+  account_name: string;
+  account_no: string;
+  ifsc: string;
+  bank_name?: string | null;
+  branch?: string | null;
+  verification_status: "unverified" | "verified" | "failed";
+  verified_at?: string | null;
+  is_primary: number; // 1 or 0
+}
+
+export interface SupplierBankAccountCreate {
+  account_name: string;
+  account_no: string;
+  ifsc: string;
+  bank_name?: string | null;
+  branch?: string | null;
+  is_primary?: boolean;
+}
+
+export interface SupplierContact {
+  id: number;
+  supplier_id: number;
+  name: string;
+  role?: string | null; // e.g. sales, accounts, dispatch
+  phone?: string | null;
+  email?: string | null;
+  is_primary: number; // 1 or 0
+}
+
+export interface SupplierContactCreate {
+  name: string;
+  role?: string | null;
+  phone?: string | null;
+  email?: string | null;
+  is_primary?: boolean;
+}
+
+export interface SupplierDetails extends Supplier {
+  addresses: SupplierAddress[];
+  bank_accounts: SupplierBankAccount[];
+  contacts: SupplierContact[];
+}
+
+export interface SupplierMatch extends Supplier {
+  matched_on: ("gst_no" | "pan" | "name")[];
+  score: number; // 0..1
+}
+
+export interface SupplierDuplicatePair {
+  a: Supplier;
+  b: Supplier;
+  matched_on: ("gst_no" | "pan" | "name")[];
+  score: number;
+}
+
+export interface SupplierMerge {
+  id: number;
+  survivor_id: number;
+  merged_id: number;
+  merged_name: string;
+  merged_gst_no?: string | null;
+  purchases_moved: number;
+  items_moved: number;
+  renamed_invoices: { purchase_id: number; from: string; to: string }[];
+  merged_by?: number | null;
+  merged_at: string;
+}
+
+export interface SupplierMergeResult {
+  survivor: Supplier;
+  merges: SupplierMerge[];
+}
+
+// A purchase that may be the same bill as another one
+export interface DuplicateInvoiceSuspect {
+  purchase_id: number;
+  supplier_id: number;
+  supplier_name: string;
+  gst_no?: string | null;
+  pan?: string | null;
+  invoice_no: string;
+  date: string;
+  invoice_value: number;
+}
+
+export interface DuplicateInvoiceGroup {
+  invoice_key: string; // normalised invoice number
+  same_amount: boolean;
+  same_date: boolean;
+  purchases: DuplicateInvoiceSuspect[];
+}
+
+// A line whose price is off from earlier purchases of the same part
+export interface PriceVariance {
+  line_no: number;
+  part_no: string;
+  description: string;
+  price: number;
+  last_price: number;
+  avg_price: number; // rolling average over `samples` earlier purchases
+  samples: number;
+  deviation_last_pct: number; // signed, relative to last_price
+  deviation_avg_pct: number;
+  threshold_pct: number;
 }
 
 export interface PurchaseEntry {
@@ -53,7 +222,121 @@ export interface PurchaseEntry {
   invoice_value: number;
   tds_value: number;
   narration?: string | null;
-  status: string;
+  status: PurchaseStatus;
+  deleted_at?: string | null; // set while in the recycle bin
+  deleted_by?: number | null;
+  custom_fields: CustomValues;
+  paid_amount: number; // allocated from supplier payments
+  noted_amount: number; // debit notes less credit notes against the entry
+  outstanding: number; // invoice_value - tds_value - paid_amount - noted_amount
+}
+
+// "uploaded" is the posted state
+export type PurchaseStatus =
+  | "draft"
+  | "pending"
+  | "verified"
+  | "approved"
+  | "uploaded"
+  | "cancelled";
+
+export interface PurchaseStatusTransition {
+  id: number;
+  from_status: PurchaseStatus;
+  to_status: PurchaseStatus;
+  required_role: "admin" | "user";
+  requires_reason: boolean;
+}
+
+export interface PurchaseStatusTransitionInput {
+  from_status: PurchaseStatus;
+  to_status: PurchaseStatus;
+  required_role?: "admin" | "user";
+  requires_reason?: boolean;
+}
+
+export interface PurchaseStatusChange {
+  id: number;
+  purchase_id: number;
+  from_status?: PurchaseStatus | null; // null for the status it was created in
+  to_status: PurchaseStatus;
+  reason?: string | null;
+  changed_by?: number | null;
+  changed_by_name?: string | null;
+  changed_at: string;
+}
+
+export interface Approver {
+  user_id: number;
+  username: string;
+}
+
+export interface ApprovalRule {
+  id: number;
+  name: string;
+  min_amount: number;
+  max_amount?: number | null; // exclusive; null for no upper bound
+  supplier_id?: number | null;
+  dimension_id?: number | null;
+  active: boolean;
+  position: number;
+  approvers: Approver[]; // in signing order
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface ApprovalRuleCreate {
+  name: string;
+  min_amount?: number;
+  max_amount?: number | null;
+  supplier_id?: number | null;
+  dimension_id?: number | null;
+  approver_ids: number[];
+  position?: number | null;
+}
+
+export interface ApprovalRuleUpdate {
+  id: number;
+  name?: string;
+  min_amount?: number;
+  max_amount?: number | null; // null removes the limit
+  supplier_id?: number | null;
+  dimension_id?: number | null;
+  active?: boolean;
+  position?: number;
+  approver_ids?: number[];
+}
+
+export type ApprovalStatus = "pending" | "approved" | "rejected" | "cancelled";
+
+export interface PurchaseApproval {
+  id: number;
+  purchase_id: number;
+  round: number;
+  step_no: number;
+  rule_id?: number | null;
+  rule_name?: string | null;
+  approver_id: number;
+  approver_name?: string | null;
+  status: ApprovalStatus;
+  comment?: string | null;
+  amount: number;
+  requested_at: string;
+  decided_at?: string | null;
+}
+
+export interface ApprovalInboxItem {
+  approval_id: number;
+  purchase_id: number;
+  supplier_id: number;
+  supplier_name: string;
+  invoice_no: string;
+  date: string;
+  invoice_value: number;
+  step_no: number;
+  steps: number;
+  rule_name?: string | null;
+  requested_at: string;
 }
 
 export interface PurchaseItem {
@@ -65,6 +348,524 @@ export interface PurchaseItem {
   unit?: string | null;
   price: number;
   amount: number;
+  po_line_id?: number | null;
+  grn_line_id?: number | null;
+}
+
+export type PurchaseOrderStatus = "open" | "closed" | "cancelled";
+
+export interface PurchaseOrder {
+  id: number;
+  po_no: string;
+  supplier_id: number;
+  supplier_name: string;
+  po_date: string;
+  expected_date?: string | null;
+  status: PurchaseOrderStatus;
+  narration?: string | null;
+  total_value: number;
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface PurchaseOrderLine {
+  id: number;
+  po_id: number;
+  line_no: number;
+  part_no: string;
+  description: string;
+  qty: number;
+  unit?: string | null;
+  price: number;
+  received_qty: number;
+  invoiced_qty: number;
+}
+
+export interface PurchaseOrderDetail {
+  order: PurchaseOrder;
+  lines: PurchaseOrderLine[];
+}
+
+export interface PurchaseOrderCreate {
+  po_no: string;
+  supplier_id: number;
+  po_date: string;
+  expected_date?: string | null;
+  narration?: string | null;
+  lines: {
+    part_no: string;
+    description?: string | null; // defaults to the item master's
+    qty: number;
+    unit?: string | null;
+    price: number;
+  }[];
+}
+
+export interface GoodsReceipt {
+  id: number;
+  grn_no: string;
+  supplier_id: number;
+  supplier_name: string;
+  po_id?: number | null;
+  po_no?: string | null;
+  received_date: string;
+  narration?: string | null;
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface GoodsReceiptLine {
+  id: number;
+  grn_id: number;
+  line_no: number;
+  po_line_id?: number | null;
+  part_no: string;
+  description: string;
+  qty: number;
+  unit?: string | null;
+  invoiced_qty: number;
+}
+
+export interface GoodsReceiptDetail {
+  receipt: GoodsReceipt;
+  lines: GoodsReceiptLine[];
+}
+
+export interface GoodsReceiptCreate {
+  grn_no: string;
+  supplier_id: number;
+  po_id?: number | null;
+  received_date: string;
+  narration?: string | null;
+  lines: {
+    po_line_id?: number | null;
+    part_no?: string | null; // taken from the PO line when linked
+    description?: string | null;
+    qty: number;
+    unit?: string | null;
+  }[];
+}
+
+export interface MatchSettings {
+  qty_tolerance_pct: number;
+  price_tolerance_pct: number;
+}
+
+export type MatchExceptionKind = "price" | "qty_ordered" | "qty_received";
+
+export interface MatchException {
+  line_no: number;
+  part_no?: string | null;
+  kind: MatchExceptionKind;
+  expected: number; // PO price, ordered qty or received qty
+  actual: number; // invoice price, or qty billed so far
+  deviation_pct?: number | null;
+  tolerance_pct: number;
+  po_line_id?: number | null;
+  grn_line_id?: number | null;
+}
+
+export interface MatchExceptionRow extends MatchException {
+  id: number;
+  purchase_id: number;
+  invoice_no: string;
+  date: string;
+  supplier_id: number;
+  supplier_name: string;
+  created_at: string;
+}
+
+export interface OpenPoLine {
+  po_id: number;
+  po_no: string;
+  po_date: string;
+  expected_date?: string | null;
+  supplier_id: number;
+  supplier_name: string;
+  po_line_id: number;
+  line_no: number;
+  part_no: string;
+  description: string;
+  ordered_qty: number;
+  received_qty: number;
+  invoiced_qty: number;
+  pending_qty: number;
+  price: number;
+  pending_value: number;
+}
+
+export interface PendingGrnLine {
+  grn_id: number;
+  grn_no: string;
+  received_date: string;
+  supplier_id: number;
+  supplier_name: string;
+  po_no?: string | null;
+  grn_line_id: number;
+  line_no: number;
+  part_no: string;
+  description: string;
+  received_qty: number;
+  invoiced_qty: number;
+  pending_qty: number;
+  price?: number | null;
+}
+
+export type PaymentMode =
+  | "cash"
+  | "cheque"
+  | "neft"
+  | "rtgs"
+  | "imps"
+  | "upi"
+  | "card"
+  | "other";
+
+export interface SupplierPayment {
+  id: number;
+  supplier_id: number;
+  supplier_name: string;
+  payment_date: string;
+  mode: PaymentMode;
+  reference?: string | null; // cheque no, UTR and the like
+  amount: number;
+  tds_amount: number; // deducted at payment; settles bills along with amount
+  narration?: string | null;
+  allocated: number;
+  unallocated: number; // an advance until allocated
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface PaymentAllocation {
+  id: number;
+  payment_id: number;
+  purchase_id: number;
+  invoice_no: string;
+  date: string;
+  invoice_value: number;
+  amount: number;
+}
+
+export interface PaymentDetail {
+  payment: SupplierPayment;
+  allocations: PaymentAllocation[];
+}
+
+export interface PaymentAllocationInput {
+  purchase_id: number;
+  amount: number;
+}
+
+export interface PaymentCreate {
+  supplier_id: number;
+  payment_date: string;
+  mode: PaymentMode;
+  reference?: string | null;
+  amount: number;
+  tds_amount?: number;
+  narration?: string | null;
+  allocations?: PaymentAllocationInput[]; // leave empty to record an advance
+}
+
+export interface PaymentUpdate {
+  id: number;
+  payment_date?: string;
+  mode?: PaymentMode;
+  reference?: string;
+  amount?: number;
+  tds_amount?: number;
+  narration?: string;
+  allocations?: PaymentAllocationInput[]; // replaces the allocations when given
+}
+
+export interface PurchasePayment {
+  allocation_id: number;
+  payment_id: number;
+  payment_date: string;
+  mode: PaymentMode;
+  reference?: string | null;
+  amount: number; // allocated to the entry
+}
+
+export interface SupplierOutstanding {
+  supplier_id: number;
+  supplier_name: string;
+  bills: number; // entries with something left to pay
+  payable: number;
+  paid: number;
+  noted: number; // debit notes less credit notes against those entries
+  outstanding: number;
+  advance: number; // unallocated payments
+  unapplied_notes: number; // debit notes less credit notes against no live entry
+  net_balance: number; // outstanding - advance - unapplied_notes
+}
+
+export interface AgeingBucket {
+  label: string; // e.g. "31-60", "90+"
+  from_days: number;
+  to_days?: number | null; // open-ended when null
+}
+
+export interface AgeingInvoice {
+  purchase_id: number;
+  supplier_id: number;
+  supplier_name: string;
+  payment_terms_days?: number | null;
+  invoice_no: string;
+  date: string;
+  due_date?: string | null; // ageing runs from it when the supplier has payment terms
+  days: number; // negative when not yet due
+  payable: number;
+  paid: number; // by the ageing date
+  noted: number; // debit notes less credit notes by the ageing date
+  outstanding: number;
+  bucket?: number | null; // index into the report's buckets; null when not yet due
+}
+
+export interface AgeingSupplierRow {
+  supplier_id: number;
+  supplier_name: string;
+  payment_terms_days?: number | null;
+  invoices: number;
+  not_due: number;
+  buckets: number[]; // in the report's bucket order
+  outstanding: number;
+  advance: number;
+  unapplied_notes: number; // notes against none of the open bills
+  net_balance: number;
+}
+
+export interface AgeingReport {
+  as_of: string;
+  buckets: AgeingBucket[];
+  rows: AgeingSupplierRow[];
+  not_due: number;
+  totals: number[];
+  outstanding: number;
+  advance: number;
+  unapplied_notes: number;
+  net_balance: number;
+}
+
+export interface AgeingParams {
+  asOf?: string; // YYYY-MM-DD, today when omitted
+  bucketDays?: number[]; // upper limits of the closed buckets, [30, 60, 90] by default
+  supplierId?: number;
+}
+
+export interface SupplierOpeningBalance {
+  id: number;
+  supplier_id: number;
+  supplier_name: string;
+  fy_start: string; // YYYY-04-01
+  fy: string; // e.g. "2024-25"
+  amount: number; // owed to the supplier; negative for an advance
+  narration?: string | null;
+  created_by?: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface OpeningBalanceSet {
+  supplier_id: number;
+  fy_start_year: number; // 2024 for FY 2024-25
+  amount: number;
+  narration?: string | null;
+}
+
+export type SupplierNoteKind = "debit" | "credit";
+
+export interface SupplierNote {
+  id: number;
+  supplier_id: number;
+  supplier_name: string;
+  kind: SupplierNoteKind;
+  note_no: string;
+  note_date: string;
+  purchase_id?: number | null;
+  invoice_no?: string | null;
+  amount: number;
+  reason?: string | null;
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface SupplierNoteCreate {
+  supplier_id: number;
+  kind: SupplierNoteKind;
+  note_no: string;
+  note_date: string;
+  purchase_id?: number | null; // the invoice it adjusts
+  amount: number;
+  reason?: string | null;
+}
+
+export interface SupplierNoteUpdate {
+  id: number;
+  note_no?: string;
+  note_date?: string;
+  amount?: number;
+  reason?: string;
+}
+
+export type LedgerLineKind =
+  | "opening"
+  | "invoice"
+  | "tds"
+  | "payment"
+  | "debit_note"
+  | "credit_note";
+
+// Credits add to what is owed to the supplier, debits take from it
+export interface LedgerLine {
+  date: string;
+  kind: LedgerLineKind;
+  reference?: string | null;
+  narration?: string | null;
+  debit: number;
+  credit: number;
+  balance: number; // running; owed to the supplier when positive
+  purchase_id?: number | null;
+  payment_id?: number | null;
+  note_id?: number | null;
+}
+
+export interface SupplierLedger {
+  supplier_id: number;
+  supplier_name: string;
+  gst_no?: string | null;
+  date_from: string;
+  date_to: string;
+  opening_balance: number;
+  lines: LedgerLine[];
+  total_debit: number;
+  total_credit: number;
+  closing_balance: number;
+}
+
+export type CustomFieldEntity = "supplier" | "purchase" | "item";
+export type CustomFieldType = "text" | "number" | "date" | "enum";
+export type CustomValues = Record<string, string | number | boolean | null>; // keyed by field key
+
+export interface CustomField {
+  id: number;
+  entity: CustomFieldEntity;
+  key: string;
+  label: string;
+  field_type: CustomFieldType;
+  options: string[]; // enum fields only
+  required: boolean;
+  active: boolean;
+  position: number;
+  created_by?: number | null;
+  created_at: string;
+}
+
+export interface CustomFieldCreate {
+  entity: CustomFieldEntity;
+  key?: string | null; // derived from the label when omitted
+  label: string;
+  field_type: CustomFieldType;
+  options?: string[];
+  required?: boolean;
+  position?: number | null;
+}
+
+export interface CustomFieldUpdate {
+  id: number;
+  label?: string;
+  options?: string[];
+  required?: boolean;
+  active?: boolean;
+  position?: number;
+}
+
+export interface CustomFieldFilter {
+  entity?: CustomFieldEntity | null;
+  key: string;
+  equals?: string | number | boolean | null;
+  contains?: string | null;
+  min?: string | number | null; // inclusive
+  max?: string | number | null; // inclusive
+  present?: boolean | null; // true requires a value, false excludes
+}
+
+export type DimensionKind = "cost_center" | "department" | "project";
+
+export interface Dimension {
+  id: number;
+  kind: DimensionKind;
+  code: string;
+  name: string;
+  active: number;
+  created_at: string;
+}
+
+export interface DimensionCreate {
+  kind: DimensionKind;
+  code: string;
+  name: string;
+}
+
+export interface DimensionUpdate {
+  id: number;
+  code?: string;
+  name?: string;
+  active?: boolean;
+}
+
+export interface AllocationInput {
+  dimension_id: number;
+  percent: number;
+}
+
+export interface PurchaseAllocation {
+  id: number;
+  purchase_id: number;
+  item_id?: number | null; // null for entry-level splits
+  dimension_id: number;
+  kind: DimensionKind;
+  code: string;
+  name: string;
+  percent: number;
+}
+
+export interface DimensionSpend {
+  dimension_id?: number | null; // null for the unallocated remainder
+  code?: string | null;
+  name?: string | null;
+  spend: number;
+  purchases: number;
+}
+
+export type AttachmentKind = "invoice" | "eway_bill" | "grn" | "other";
+
+export interface Attachment {
+  id: number;
+  purchase_id: number;
+  sha256: string;
+  file_name: string;
+  kind: AttachmentKind;
+  mime_type?: string | null;
+  size_bytes: number;
+  created_by?: number | null;
+  created_at: string;
+  shared_with: number; // other entries carrying the same file
+}
+
+export interface AttachmentUse {
+  attachment_id: number;
+  purchase_id: number;
+  supplier_name: string;
+  invoice_no: string;
+  date: string;
+  deleted: boolean;
+}
+
+export interface AttachmentAdded {
+  attachment: Attachment;
+  also_attached_to: AttachmentUse[];
 }
 
 export interface PurchaseItemPayload {
@@ -75,6 +876,9 @@ export interface PurchaseItemPayload {
   unit?: string | null;
   price: number;
   amount?: number | null;
+  allocations?: AllocationInput[] | null; // line-level splits
+  po_line_id?: number | null; // purchase order line this bills
+  grn_line_id?: number | null; // goods receipt line this bills
 }
 
 export interface PurchaseCreate {
@@ -90,8 +894,10 @@ export interface PurchaseCreate {
   invoice_value: number;
   tds_value: number;
   narration?: string | null;
-  status: string;
+  status: "draft" | "pending"; // later moves go through transition_purchase
   items: PurchaseItemPayload[];
+  allocations?: AllocationInput[]; // entry-level splits
+  custom_fields?: CustomValues;
 }
 
 export interface PurchaseUpdate {
@@ -108,8 +914,10 @@ export interface PurchaseUpdate {
   invoice_value?: number;
   tds_value?: number;
   narration?: string | null;
-  status?: string;
+  status?: PurchaseStatus; // must match the current status
   items?: PurchaseItemPayload[];
+  allocations?: AllocationInput[];
+  custom_fields?: CustomValues; // merged over the stored values; null clears a field
 }
 
 export interface ReportSummary {
@@ -129,8 +937,32 @@ export interface PurchaseFilters {
   date_from?: string; // inclusive
   date_to?: string; // inclusive
   gst_rate?: number;
+  gst_rates?: number[]; // any of
   invoice_no?: string;
   status?: string;
+  statuses?: string[]; // any of
+  // Amount ranges, inclusive
+  basic_value_min?: number;
+  basic_value_max?: number;
+  invoice_value_min?: number;
+  invoice_value_max?: number;
+  tax_value_min?: number; // sgst + cgst + igst
+  tax_value_max?: number;
+  // Supplier fields
+  supplier_name?: string;
+  supplier_gst_no?: string;
+  supplier_state_code?: string;
+  // Presence: true requires, false excludes
+  has_tds?: boolean;
+  has_outstanding?: boolean;
+  has_narration?: boolean;
+  has_items?: boolean;
+  part_no?: string;
+  dimension_ids?: number[]; // tagged with every one of these
+  unallocated_kind?: DimensionKind; // no entry-level split of this kind
+  custom_fields?: CustomFieldFilter[]; // purchase fields unless entity is "supplier"
+  any_of?: PurchaseFilters[]; // OR groups, each AND-ed internally
+  include_deleted?: boolean;
 }
 
 export interface User {
@@ -1,4 +1,9 @@
+import { useApprovalInbox } from "../lib/queries";
+import { useAppStore } from "../store/useAppStore";
+
 export function DashboardPage() {
+  const user = useAppStore((s) => s.user);
+  const { data: inbox = [] } = useApprovalInbox(user?.id);
   // Placeholder KPIs; hook up queries to backend later
   const cards = [
     { title: "Purchase (This Month)", value: "₹0.00" },
@@ -17,6 +22,31 @@ export function DashboardPage() {
           </div>
         ))}
       </div>
+      {inbox.length > 0 && (
+        <div className="rounded border p-4">
+          <div className="text-muted-foreground text-sm">
+            Waiting for your approval ({inbox.length})
+          </div>
+          <table className="mt-2 w-full text-sm">
+            <tbody>
+              {inbox.map((i) => (
+                <tr key={i.approval_id} className="border-t">
+                  <td className="py-1">{i.date}</td>
+                  <td className="py-1">{i.supplier_name}</td>
+                  <td className="py-1">{i.invoice_no}</td>
+                  <td className="py-1 text-right font-mono">
+                    ₹{i.invoice_value.toFixed(2)}
+                  </td>
+                  <td className="py-1 text-right text-xs text-slate-500">
+                    step {i.step_no} of {i.steps}
+                    {i.rule_name ? ` · ${i.rule_name}` : ""}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
     </div>
   );
 }
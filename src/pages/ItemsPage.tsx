@@ -19,10 +19,10 @@ export function ItemsPage() {
   const suppliers = useMemo(() => suppliersPage?.data ?? [], [suppliersPage]);
 
   useEffect(() => {
-    // Refresh list from storage when query changes
+    // Refresh list when the query or the stored items change
     setItems(api.search(query));
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [query]);
+  }, [query, api.items]);
 
   const count = useMemo(() => items.length, [items]);
 
@@ -48,15 +48,18 @@ export function ItemsPage() {
     });
   }
 
-  function onSubmit(e: React.FormEvent) {
+  async function onSubmit(e: React.FormEvent) {
     e.preventDefault();
-    if (editing) {
-      api.update(editing.id, form);
-    } else {
-      api.add(form);
+    try {
+      if (editing) {
+        await api.update(editing.id, form);
+      } else {
+        await api.add(form);
+      }
+      resetForm();
+    } catch (err) {
+      alert(String(err));
     }
-    setItems(api.search(query));
-    resetForm();
   }
 
   return (
@@ -118,8 +121,7 @@ export function ItemsPage() {
                       <button
                         className="border-destructive text-destructive rounded border px-2 py-1 text-xs"
                         onClick={() => {
-                          api.remove(it.id);
-                          setItems(api.search(query));
+                          api.remove(it.id).catch((err) => alert(String(err)));
                         }}
                       >
                         Delete
@@ -8,6 +8,13 @@ import {
 } from "../lib/queries";
 import type { PurchaseFilters, Supplier, PurchaseEntry } from "../lib/types";
 import { PurchaseForm } from "../components/purchases/PurchaseForm";
+import { useAppStore } from "../store/useAppStore";
+import { PurchaseAttachments } from "../components/purchases/PurchaseAttachments";
+import { PurchaseApprovals } from "../components/purchases/PurchaseApprovals";
+import {
+  PurchaseStatusActions,
+  STATUS_LABELS,
+} from "../components/purchases/PurchaseStatusActions";
 
 export function PurchaseFormPage() {
   const navigate = useNavigate();
@@ -23,6 +30,7 @@ export function PurchaseFormPage() {
     [suppliersPage],
   );
   const deletePurchase = useDeletePurchase();
+  const user = useAppStore((s) => s.user);
 
   const handleCreated = () => {
     // Refresh the purchase list after creating new entry
@@ -222,7 +230,7 @@ export function PurchaseFormPage() {
                                     : "bg-gray-100 text-gray-800 dark:bg-gray-900 dark:text-gray-200"
                               }`}
                             >
-                              {p.status}
+                              {STATUS_LABELS[p.status] ?? p.status}
                             </span>
                           </td>
                           <td className="p-2 text-right">
@@ -249,7 +257,10 @@ export function PurchaseFormPage() {
                               onClick={async (e) => {
                                 e.stopPropagation();
                                 if (confirm("Delete this purchase?")) {
-                                  await deletePurchase.mutateAsync(p.id);
+                                  await deletePurchase.mutateAsync({
+                                    id: p.id,
+                                    userId: user?.id,
+                                  });
                                   refetch();
                                 }
                               }}
@@ -381,6 +392,22 @@ export function PurchaseFormPage() {
                         ))}
                       </div>
                     </div>
+
+                    {(() => {
+                      const purchase = data?.data?.find(
+                        (p) => p.id === selectedId,
+                      );
+                      return purchase ? (
+                        <PurchaseStatusActions
+                          purchaseId={purchase.id}
+                          status={purchase.status}
+                        />
+                      ) : null;
+                    })()}
+
+                    <PurchaseApprovals purchaseId={selectedId} />
+
+                    <PurchaseAttachments purchaseId={selectedId} />
                   </div>
                 )}
               </div>
@@ -23,7 +23,7 @@ export function PurchasesPage() {
   const deletePurchase = useDeletePurchase();
   const [selectedId, setSelectedId] = useState<number | null>(null);
   const items = useItemsByPurchase(selectedId ?? 0);
-  const { data: suppliersPage } = useSuppliers();
+  const { data: suppliersPage } = useSuppliers({ includeInactive: true });
   const suppliers: Supplier[] = useMemo(
     () => suppliersPage?.data || [],
     [suppliersPage],
@@ -2,6 +2,9 @@ import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { DateRangePicker } from "@/components/ui/DateRangePicker";
 import type {
+  CustomField,
+  DimensionKind,
+  DimensionSpend,
   PurchaseEntry,
   PurchasesBySupplier,
   ReportSummary,
@@ -26,6 +29,11 @@ export function ReportsPage() {
   const [purchasesBySupplier, setPurchasesBySupplier] = useState<
     PurchasesBySupplier[]
   >([]);
+  const [dimensionKind, setDimensionKind] =
+    useState<DimensionKind>("department");
+  const [spendByDimension, setSpendByDimension] = useState<DimensionSpend[]>(
+    [],
+  );
   const [dateRange, setDateRange] = useState<DateRange | undefined>({
     from: new Date(2022, 0, 20),
     to: new Date(),
@@ -51,6 +59,16 @@ export function ReportsPage() {
     fetchData();
   }, [dateRange]);
 
+  useEffect(() => {
+    if (dateRange?.from && dateRange?.to) {
+      invoke<DimensionSpend[]>("get_spend_by_dimension", {
+        kind: dimensionKind,
+        dateFrom: dateRange.from.toISOString().split("T")[0],
+        dateTo: dateRange.to.toISOString().split("T")[0],
+      }).then(setSpendByDimension);
+    }
+  }, [dateRange, dimensionKind]);
+
   const handleExport = async () => {
     if (dateRange?.from && dateRange?.to) {
       const purchases = await invoke<PurchaseEntry[]>("export_purchases", {
@@ -58,8 +76,15 @@ export function ReportsPage() {
         dateTo: dateRange.to.toISOString().split("T")[0],
       });
 
+      const customFields = await invoke<CustomField[]>("get_custom_fields", {
+        entity: "purchase",
+      });
+
       const csvContent = [
-        "ID,Supplier ID,Invoice No,Date,Entry Date,GST Rate,Basic Value,SGST,CGST,IGST,Invoice Value,TDS Value,Narration,Status",
+        [
+          "ID,Supplier ID,Invoice No,Date,Entry Date,GST Rate,Basic Value,SGST,CGST,IGST,Invoice Value,TDS Value,Narration,Status",
+          ...customFields.map((f) => f.label),
+        ].join(","),
         ...purchases.map((p) =>
           [
             p.id,
@@ -76,6 +101,7 @@ export function ReportsPage() {
             p.tds_value,
             p.narration,
             p.status,
+            ...customFields.map((f) => p.custom_fields[f.key] ?? ""),
           ].join(","),
         ),
       ].join("\n");
@@ -160,6 +186,47 @@ export function ReportsPage() {
           </ResponsiveContainer>
         </CardContent>
       </Card>
+      <Card>
+        <CardHeader className="flex flex-row items-center justify-between">
+          <CardTitle>Spend by Dimension</CardTitle>
+          <select
+            className="rounded border px-2 py-1 text-sm"
+            value={dimensionKind}
+            onChange={(e) => setDimensionKind(e.target.value as DimensionKind)}
+          >
+            <option value="cost_center">Cost center</option>
+            <option value="department">Department</option>
+            <option value="project">Project</option>
+          </select>
+        </CardHeader>
+        <CardContent>
+          <table className="min-w-full text-sm">
+            <thead className="text-left">
+              <tr>
+                <th className="p-2">Code</th>
+                <th className="p-2">Name</th>
+                <th className="p-2 text-right">Purchases</th>
+                <th className="p-2 text-right">Taxable value</th>
+              </tr>
+            </thead>
+            <tbody>
+              {spendByDimension.map((r) => (
+                <tr key={r.dimension_id ?? "none"} className="border-t">
+                  <td className="p-2">{r.code ?? "-"}</td>
+                  <td className="p-2">{r.name ?? "Unallocated"}</td>
+                  <td className="p-2 text-right">{r.purchases}</td>
+                  <td className="p-2 text-right">
+                    {r.spend.toLocaleString("en-IN", {
+                      style: "currency",
+                      currency: "INR",
+                    })}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </CardContent>
+      </Card>
     </div>
   );
 }
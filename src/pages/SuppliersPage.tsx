@@ -6,19 +6,52 @@ import {
   useSuppliers,
   useUpdateSupplier,
   useImportSuppliers,
+  useSetSupplierActive,
 } from "../lib/queries";
-import type { Supplier, SupplierCreate, SupplierUpdate } from "../lib/types";
+import type {
+  Supplier,
+  SupplierCreate,
+  SupplierDeleteError,
+  SupplierMatch,
+  SupplierUpdate,
+} from "../lib/types";
+import { CustomFieldInputs } from "../components/ui/CustomFieldInputs";
+import { useAppStore } from "../store/useAppStore";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { downloadDir } from "@tauri-apps/api/path";
 
 export function SuppliersPage() {
   const navigate = useNavigate();
-  const { data: suppliersPage, isLoading, isError, refetch } = useSuppliers();
+  const { data: suppliersPage, isLoading, isError, refetch } = useSuppliers({
+    includeInactive: true,
+  });
   const importMutation = useImportSuppliers();
   const addMutation = useAddSupplier();
   const updateMutation = useUpdateSupplier();
   const deleteMutation = useDeleteSupplier();
+  const activeMutation = useSetSupplierActive();
+  const user = useAppStore((s) => s.user);
+
+  async function remove(s: Supplier) {
+    try {
+      await deleteMutation.mutateAsync({ id: s.id, userId: user?.id });
+    } catch (err) {
+      const e = err as Partial<SupplierDeleteError>;
+      if (e.code === "supplier_in_use" && e.can_deactivate) {
+        if (confirm(`${e.message}\n\nDeactivate ${s.name} now?`)) {
+          await activeMutation.mutateAsync({
+            id: s.id,
+            active: false,
+            userId: user?.id,
+          });
+        }
+      } else {
+        alert(e.message ?? String(err));
+      }
+    }
+    await refetch();
+  }
 
   const emptyForm: SupplierCreate = {
     name: "",
@@ -28,6 +61,7 @@ export function SuppliersPage() {
     tds_rate: null,
     contact: "",
     email: "",
+    custom_fields: {},
   };
   const [editing, setEditing] = useState<Supplier | null>(null);
   const [form, setForm] = useState<SupplierCreate>(emptyForm);
@@ -43,6 +77,7 @@ export function SuppliersPage() {
         tds_rate: rest.tds_rate ?? null,
         contact: rest.contact || "",
         email: rest.email || "",
+        custom_fields: rest.custom_fields ?? {},
       });
     } else {
       setForm({
@@ -53,6 +88,7 @@ export function SuppliersPage() {
         tds_rate: null,
         contact: "",
         email: "",
+        custom_fields: {},
       });
     }
   }, [editing]);
@@ -96,11 +132,29 @@ export function SuppliersPage() {
           tds_rate: normalized.tds_rate,
           contact: normalized.contact,
           email: normalized.email,
+          custom_fields: normalized.custom_fields,
         };
         await updateMutation.mutateAsync(updatePayload);
         setEditing(null);
       } else {
-        await addMutation.mutateAsync(normalized);
+        const matches = await invoke<SupplierMatch[]>(
+          "check_supplier_duplicates",
+          { name: normalized.name, gstNo: gst || null, pan: null },
+        );
+        if (
+          matches.length > 0 &&
+          !confirm(
+            `This looks like an existing supplier:\n${matches
+              .map((m) => `- ${m.name} (${m.matched_on.join(", ")})`)
+              .join("\n")}\n\nAdd it anyway?`,
+          )
+        ) {
+          return;
+        }
+        await addMutation.mutateAsync({
+          payload: normalized,
+          allowDuplicate: matches.length > 0,
+        });
       }
       await refetch();
     } catch (err) {
@@ -177,7 +231,14 @@ export function SuppliersPage() {
               <tbody>
                 {(suppliersPage?.data || []).map((s: Supplier) => (
                   <tr key={s.id} className="border-t">
-                    <td className="p-2">{s.name}</td>
+                    <td className="p-2">
+                      {s.name}
+                      {s.active === 0 && (
+                        <span className="text-muted-foreground ml-2 text-xs">
+                          (inactive)
+                        </span>
+                      )}
+                    </td>
                     <td className="p-2">{s.gst_no || "-"}</td>
                     <td className="p-2 text-center">{s.state_code || "-"}</td>
                     <td className="p-2">{s.tds_flag ? "Yes" : "No"}</td>
@@ -201,11 +262,21 @@ export function SuppliersPage() {
                         Edit
                       </button>
                       <button
-                        className="border-destructive text-destructive rounded border px-2 py-1 text-xs"
+                        className="rounded border px-2 py-1 text-xs"
                         onClick={async () => {
-                          await deleteMutation.mutateAsync(s.id);
+                          await activeMutation.mutateAsync({
+                            id: s.id,
+                            active: s.active === 0,
+                            userId: user?.id,
+                          });
                           await refetch();
                         }}
+                      >
+                        {s.active === 0 ? "Activate" : "Deactivate"}
+                      </button>
+                      <button
+                        className="border-destructive text-destructive rounded border px-2 py-1 text-xs"
+                        onClick={() => remove(s)}
                       >
                         Delete
                       </button>
@@ -310,6 +381,16 @@ export function SuppliersPage() {
               }
             />
           </div>
+          <CustomFieldInputs
+            entity="supplier"
+            values={form.custom_fields ?? {}}
+            onChange={(custom_fields) =>
+              setForm((f) => ({ ...f, custom_fields }))
+            }
+            className="grid gap-2"
+            labelClassName="text-xs"
+            inputClassName="rounded border px-2 py-1"
+          />
           <div className="flex gap-2 pt-2">
             <button
               type="submit"